@@ -0,0 +1,138 @@
+// Manifest of the artifacts emitted by `orbiton build`
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::utils::crypto::content_hash;
+
+/// File name of the manifest written into the output directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version of the manifest format, bumped on incompatible changes
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// List of the files a build emitted, for deploy scripts and integrity checks
+#[derive(Debug, Serialize)]
+pub struct BuildManifest {
+    /// Version of the manifest format
+    pub schema_version: u32,
+    /// Build target the artifacts were produced for
+    pub target: String,
+    /// Emitted artifacts, sorted by path
+    pub artifacts: Vec<Artifact>,
+}
+
+/// A single emitted file
+#[derive(Debug, Serialize)]
+pub struct Artifact {
+    /// Path relative to the output directory, with `/` separators
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Hex-encoded SHA-256 digest of the contents
+    pub hash: String,
+    /// Kind of artifact (wasm, js, css, html or other)
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl BuildManifest {
+    /// Describe the artifacts a build emitted into `output_dir`
+    ///
+    /// Only the listed files are included, so leftovers from earlier builds
+    /// and intermediate files in the output directory are never reported.
+    pub fn from_artifacts(output_dir: &Path, target: &str, files: &[PathBuf]) -> Result<Self> {
+        let mut artifacts = Vec::new();
+
+        for file in files {
+            let path = output_dir.join(file);
+            let rel_path = path
+                .strip_prefix(output_dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let contents =
+                std::fs::read(&path).with_context(|| format!("Failed to read {path:?}"))?;
+            artifacts.push(Artifact {
+                kind: artifact_kind(&path).to_string(),
+                path: rel_path,
+                size: contents.len() as u64,
+                hash: content_hash(&contents),
+            });
+        }
+
+        artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+        artifacts.dedup_by(|a, b| a.path == b.path);
+
+        Ok(Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            target: target.to_string(),
+            artifacts,
+        })
+    }
+
+    /// Write the manifest into `output_dir` and return its path
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        let path = output_dir.join(MANIFEST_FILE);
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, json).with_context(|| format!("Failed to write {path:?}"))?;
+        Ok(path)
+    }
+}
+
+/// Classify an artifact by its file extension
+fn artifact_kind(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("wasm") => "wasm",
+        Some("js" | "mjs") => "js",
+        Some("css") => "css",
+        Some("html" | "htm") => "html",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_manifest_lists_artifacts() {
+        let output_dir = tempdir().unwrap();
+        let output = output_dir.path();
+
+        std::fs::write(output.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(output.join("app_bg.wasm"), b"\0asm").unwrap();
+        std::fs::create_dir_all(output.join("assets")).unwrap();
+        std::fs::write(output.join("assets/reset.css"), "body {}").unwrap();
+        std::fs::create_dir_all(output.join("generated/src")).unwrap();
+        std::fs::write(output.join("generated/src/app.rs"), "// generated").unwrap();
+        std::fs::write(output.join("app.0123abcd.js"), "// earlier build").unwrap();
+
+        let files = [
+            output.join("index.html"),
+            PathBuf::from("assets/reset.css"),
+            PathBuf::from("app_bg.wasm"),
+        ];
+        let manifest = BuildManifest::from_artifacts(output, "web", &files).unwrap();
+        manifest.write(output).unwrap();
+
+        // Files the build didn't report are left out
+        let manifest = BuildManifest::from_artifacts(output, "web", &files).unwrap();
+        assert_eq!(manifest.schema_version, MANIFEST_SCHEMA_VERSION);
+        let paths: Vec<_> = manifest.artifacts.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, ["app_bg.wasm", "assets/reset.css", "index.html"]);
+
+        let wasm = &manifest.artifacts[0];
+        assert_eq!(wasm.kind, "wasm");
+        assert_eq!(wasm.size, 4);
+        assert_eq!(wasm.hash, content_hash(b"\0asm"));
+
+        let written = std::fs::read_to_string(output.join(MANIFEST_FILE)).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["artifacts"][2]["type"], "html");
+    }
+}
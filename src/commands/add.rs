@@ -0,0 +1,241 @@
+// Command for adding new items to an existing Orbit project
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use console::style;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::status;
+use crate::templates::components::{Component, PropType};
+use crate::templates::project_templates::ComponentFormat;
+
+#[derive(Args)]
+pub struct AddArgs {
+    #[command(subcommand)]
+    command: AddCommand,
+}
+
+#[derive(Subcommand)]
+enum AddCommand {
+    /// Add a new `.orbit` component under src/components
+    Component {
+        /// Name of the component (e.g. `user_card` or `UserCard`)
+        name: String,
+
+        /// Prop to declare, as `name:type` (string, number, boolean, object, array<T>)
+        #[arg(short, long = "prop")]
+        props: Vec<String>,
+
+        /// Component file format (legacy, modern, markdown)
+        #[arg(short, long, default_value = "legacy")]
+        format: String,
+
+        /// Project directory
+        #[arg(short, long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+pub fn execute(args: AddArgs) -> Result<()> {
+    match args.command {
+        AddCommand::Component {
+            name,
+            props,
+            format,
+            dir,
+        } => {
+            let project_dir = match dir {
+                Some(dir) => dir,
+                None => std::env::current_dir()?,
+            };
+            let format: ComponentFormat = format.parse()?;
+            add_component(&project_dir, &name, &props, &format)
+        }
+    }
+}
+
+/// Generate `src/components/<name>.orbit` and register it in `mod.rs` if present
+fn add_component(
+    project_dir: &Path,
+    name: &str,
+    props: &[String],
+    format: &ComponentFormat,
+) -> Result<()> {
+    let module_name = to_snake_case(name);
+    if module_name.is_empty()
+        || module_name.starts_with(|c: char| c.is_ascii_digit())
+        || !module_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(anyhow::anyhow!("Invalid component name: {name}"));
+    }
+
+    let components_dir = project_dir.join("src").join("components");
+    let component_path = components_dir.join(format!("{module_name}.{}", format.extension()));
+
+    // Both formats compile to the same module, so either one blocks the name
+    for extension in ComponentFormat::EXTENSIONS {
+        let existing = components_dir.join(format!("{module_name}.{extension}"));
+        if existing.exists() {
+            return Err(anyhow::anyhow!(
+                "Component already exists: {}",
+                existing.display()
+            ));
+        }
+    }
+
+    let mut component = Component::new(
+        to_pascal_case(&module_name),
+        module_name.replace('_', "-"),
+        HashMap::new(),
+        format!("The {} component", to_pascal_case(&module_name)),
+    );
+    for prop in props {
+        let (prop_name, prop_type) = parse_prop(prop)?;
+        component.add_prop(prop_name, prop_type);
+    }
+
+    status!(
+        "{} component {}",
+        style("Adding").bold().green(),
+        style(&component.name).bold()
+    );
+
+    fs::create_dir_all(&components_dir)
+        .with_context(|| format!("Failed to create directory: {components_dir:?}"))?;
+    fs::write(&component_path, component.to_orbit(format))
+        .with_context(|| format!("Failed to write component: {component_path:?}"))?;
+    status!(
+        "  {} {}",
+        style("Created").green(),
+        component_path.display()
+    );
+
+    let mod_path = components_dir.join("mod.rs");
+    if mod_path.exists() {
+        let mut content = fs::read_to_string(&mod_path)
+            .with_context(|| format!("Failed to read {mod_path:?}"))?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("pub mod {module_name};\n"));
+        fs::write(&mod_path, content).with_context(|| format!("Failed to write {mod_path:?}"))?;
+        status!("  {} {}", style("Updated").green(), mod_path.display());
+    }
+
+    Ok(())
+}
+
+/// Parse a `name:type` prop flag
+fn parse_prop(spec: &str) -> Result<(String, PropType)> {
+    let (name, prop_type) = spec
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Invalid prop '{spec}', expected name:type"))?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(anyhow::anyhow!("Invalid prop name in '{spec}'"));
+    }
+    Ok((name.to_string(), prop_type.parse()?))
+}
+
+/// Convert `UserCard` or `user-card` into `user_card`
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.trim().chars().enumerate() {
+        if c == '-' || c == ' ' {
+            result.push('_');
+        } else if c.is_ascii_uppercase() {
+            if i > 0 && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Convert `user_card` into `UserCard`
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_add_component() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let components_dir = temp_dir.path().join("src/components");
+        fs::create_dir_all(&components_dir)?;
+        fs::write(components_dir.join("mod.rs"), "pub mod app;")?;
+
+        add_component(
+            temp_dir.path(),
+            "UserCard",
+            &["title:string".to_string(), "tags:array<string>".to_string()],
+            &ComponentFormat::Legacy,
+        )?;
+
+        let content = fs::read_to_string(components_dir.join("user_card.orbit"))?;
+        assert!(content.contains("pub struct UserCardProps"));
+        assert!(content.contains("pub tags: Vec<String>,"));
+        assert!(content.contains("pub title: String,"));
+        assert_eq!(
+            fs::read_to_string(components_dir.join("mod.rs"))?,
+            "pub mod app;\npub mod user_card;\n"
+        );
+
+        // A second add must not overwrite the existing component
+        let result = add_component(temp_dir.path(), "user_card", &[], &ComponentFormat::Legacy);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_markdown_component() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let components_dir = temp_dir.path().join("src/components");
+
+        add_component(temp_dir.path(), "Badge", &[], &ComponentFormat::Markdown)?;
+        assert!(components_dir.join("badge.orbit.md").is_file());
+        assert!(!components_dir.join("badge.orbit").exists());
+
+        // The same name in the other format would generate the same module
+        for format in [ComponentFormat::Legacy, ComponentFormat::Markdown] {
+            let err = add_component(temp_dir.path(), "badge", &[], &format).unwrap_err();
+            assert!(err.to_string().contains("badge.orbit.md"));
+        }
+
+        add_component(temp_dir.path(), "Chip", &[], &ComponentFormat::Modern)?;
+        let result = add_component(temp_dir.path(), "chip", &[], &ComponentFormat::Markdown);
+        assert!(result.is_err());
+        assert!(!components_dir.join("chip.orbit.md").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_conversion() {
+        assert_eq!(to_snake_case("UserCard"), "user_card");
+        assert_eq!(to_snake_case("user-card"), "user_card");
+        assert_eq!(to_pascal_case("user_card"), "UserCard");
+        assert!(parse_prop("title").is_err());
+    }
+}
@@ -3,8 +3,18 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use log::info;
+use log::{debug, info};
+use serde::Deserialize;
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::build_manifest::BuildManifest;
+use crate::config::OrbitonConfig;
+use crate::parse_cache::ParseCache;
+use crate::status;
+use crate::templates::project_templates::ComponentFormat;
+use crate::utils::crypto;
 
 /// Supported build target platforms
 #[derive(Debug, Clone, PartialEq)]
@@ -52,6 +62,30 @@ pub struct BuildArgs {
     /// Release mode
     #[arg(short, long)]
     release: bool,
+
+    /// Don't pass variables from the project's .env file to cargo
+    #[arg(long)]
+    no_env: bool,
+
+    /// Regenerate code for every .orbit file instead of reusing the parse cache
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Maximum number of .orbit files to process in parallel (default: one per CPU)
+    #[arg(short, long)]
+    jobs: Option<usize>,
+
+    /// Target triple for embedded builds, e.g. thumbv7em-none-eabihf (default: build.target)
+    #[arg(long)]
+    target_triple: Option<String>,
+
+    /// Leave out .orbit files matching a glob, relative to the project (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Report what would be built without running cargo or creating the output directory
+    #[arg(long)]
+    dry_run: bool,
 }
 
 pub fn execute(args: BuildArgs) -> Result<()> {
@@ -68,21 +102,85 @@ pub fn execute(args: BuildArgs) -> Result<()> {
         ));
     }
 
+    // Load configuration from .orbiton.toml or use defaults
+    let config = OrbitonConfig::load_from_project(&project_dir)?;
+
     // Convert target string to enum for better type safety
     let target = BuildTarget::from(args.target.as_str());
 
+    let options = BuildOptions {
+        output_dir: args.output,
+        release: args.release,
+        no_env: args.no_env,
+        no_cache: args.no_cache,
+        jobs: args.jobs,
+        target_triple: args.target_triple,
+        exclude: args.exclude,
+        dry_run: args.dry_run,
+    };
+    run(&project_dir, &config, target, &options)?;
+
+    Ok(())
+}
+
+/// Options for a single build, mirroring the `orbiton build` flags
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Output directory (default: `build/<target>` in the project)
+    pub output_dir: Option<PathBuf>,
+    /// Build in release mode
+    pub release: bool,
+    /// Don't pass variables from the project's .env file to cargo
+    pub no_env: bool,
+    /// Regenerate code for every .orbit file instead of reusing the parse cache
+    pub no_cache: bool,
+    /// Maximum number of .orbit files to process in parallel
+    pub jobs: Option<usize>,
+    /// Target triple for embedded builds (default: `build.target`)
+    pub target_triple: Option<String>,
+    /// Glob patterns of `.orbit` files to leave out, in addition to `build.exclude`
+    pub exclude: Vec<String>,
+    /// Only report what would be built
+    pub dry_run: bool,
+}
+
+/// Build a project for a target and return the output directory
+///
+/// With `dry_run` set, the build plan is printed and nothing is written.
+pub fn run(
+    project_dir: &Path,
+    config: &OrbitonConfig,
+    target: BuildTarget,
+    options: &BuildOptions,
+) -> Result<PathBuf> {
+    // Variables from .env are handed to cargo without touching our own environment
+    let env = if options.no_env {
+        Vec::new()
+    } else {
+        crate::utils::env::load_dotenv(project_dir)?
+    };
+
     // Determine the output directory
-    let output_dir = match args.output {
-        Some(dir) => dir,
+    let output_dir = match &options.output_dir {
+        Some(dir) => dir.clone(),
         None => {
-            let mut dir = project_dir.clone();
+            let mut dir = project_dir.to_path_buf();
             dir.push("build");
             dir.push(target.to_string());
             dir
         }
     };
 
-    println!(
+    let exclude = crate::utils::fs::glob_set(
+        &[config.build.exclude.as_slice(), options.exclude.as_slice()].concat(),
+    )?;
+
+    if options.dry_run {
+        print_build_plan(project_dir, config, &target, &output_dir, &exclude, options)?;
+        return Ok(output_dir);
+    }
+
+    status!(
         "{} project for target {}",
         style("Building").bold().green(),
         style(&target).bold()
@@ -94,24 +192,137 @@ pub fn execute(args: BuildArgs) -> Result<()> {
             .with_context(|| format!("Failed to create output directory: {output_dir:?}"))?;
     }
 
+    // Make sure the configured toolchain exists before cargo is spawned
+    let toolchain = config.build.use_beta_toolchain.then_some("beta");
+    if let Some(toolchain) = toolchain {
+        crate::utils::toolchain::ensure_toolchain(toolchain)?;
+        status!(
+            "{} Using Rust {toolchain} toolchain for builds",
+            style("Info:").bold().blue()
+        );
+    }
+
+    let codegen = CodegenOptions {
+        cache: (!options.no_cache).then(|| ParseCache::new(project_dir)),
+        jobs: options.jobs,
+        exclude,
+    };
+
     // Execute appropriate build command based on target
-    match target {
+    let artifacts = match target {
         BuildTarget::Web => {
-            build_for_web(project_dir.as_path(), output_dir.as_path(), args.release)?
-        }
-        BuildTarget::Desktop => {
-            build_for_desktop(project_dir.as_path(), output_dir.as_path(), args.release)?
+            let hash_assets = config.build.hash_assets.unwrap_or(options.release);
+            build_for_web(
+                project_dir,
+                output_dir.as_path(),
+                options.release,
+                hash_assets,
+                &env,
+                toolchain,
+                &codegen,
+            )?
         }
+        BuildTarget::Desktop => build_for_desktop(
+            project_dir,
+            output_dir.as_path(),
+            options.release,
+            &config.build.features,
+            &env,
+            toolchain,
+            &codegen,
+        )?,
         BuildTarget::Embedded => {
-            build_for_embedded(project_dir.as_path(), output_dir.as_path(), args.release)?
+            // Building for the host would silently produce the wrong firmware
+            let target_triple = options
+                .target_triple
+                .clone()
+                .or(config.build.target.clone())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Embedded builds need a target triple. Pass --target-triple (e.g. thumbv7em-none-eabihf) or set build.target in .orbiton.toml"
+                    )
+                })?;
+            crate::utils::toolchain::ensure_target(&target_triple, toolchain)?;
+
+            let embedded = EmbeddedOptions {
+                target_triple: &target_triple,
+                opt_level: config.build.opt_level.as_deref(),
+            };
+            build_for_embedded(
+                project_dir,
+                output_dir.as_path(),
+                options.release,
+                &embedded,
+                &env,
+                toolchain,
+                &codegen,
+            )?
         }
-    }
+    };
 
-    println!(
+    // List the emitted artifacts for deploy tooling
+    let manifest = BuildManifest::from_artifacts(&output_dir, &target.to_string(), &artifacts)?;
+    let manifest_path = manifest.write(&output_dir)?;
+    debug!(
+        "Wrote manifest with {} artifacts to {manifest_path:?}",
+        manifest.artifacts.len()
+    );
+
+    status!(
         "\n{} successful. Output at {output_dir:?}",
         style("Build").bold().green()
     );
 
+    Ok(output_dir)
+}
+
+/// Print what a build would do, for `orbiton build --dry-run`
+fn print_build_plan(
+    project_dir: &Path,
+    config: &OrbitonConfig,
+    target: &BuildTarget,
+    output_dir: &Path,
+    exclude: &globset::GlobSet,
+    options: &BuildOptions,
+) -> Result<()> {
+    let orbit_files = find_orbit_files(project_dir, exclude)?;
+    let toolchain = if config.build.use_beta_toolchain {
+        "beta"
+    } else {
+        "stable"
+    };
+    let features = if config.build.features.is_empty() {
+        "none".to_string()
+    } else {
+        config.build.features.join(", ")
+    };
+
+    println!(
+        "{} Dry run, nothing will be built",
+        style("Info:").bold().blue()
+    );
+    println!("  Target:     {}", style(target).bold());
+    if *target == BuildTarget::Embedded {
+        let triple = options
+            .target_triple
+            .as_deref()
+            .or(config.build.target.as_deref())
+            .unwrap_or("not set");
+        println!("  Triple:     {triple}");
+    }
+    println!(
+        "  Mode:       {}",
+        if options.release { "release" } else { "debug" }
+    );
+    println!("  Output:     {}", output_dir.display());
+    println!("  Toolchain:  {toolchain}");
+    println!("  Features:   {features}");
+    println!("  Orbit files ({}):", orbit_files.len());
+    for file in &orbit_files {
+        let relative = file.strip_prefix(project_dir).unwrap_or(file);
+        println!("    {}", relative.display());
+    }
+
     Ok(())
 }
 
@@ -129,6 +340,9 @@ impl BuildProgress {
                 .progress_chars("#>-"),
         );
         progress_bar.set_message(format!("Building for {target}"));
+        if crate::output::is_quiet() {
+            progress_bar.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+        }
         Self { progress_bar }
     }
 
@@ -137,138 +351,929 @@ impl BuildProgress {
         self.progress_bar.set_message(msg.to_string());
     }
 
+    /// Extend the bar by the number of crates cargo is expected to compile
+    fn add_crates(&self, count: u64) {
+        self.progress_bar.inc_length(count);
+    }
+
+    /// Advance the bar for a crate reported by cargo
+    fn crate_compiled(&self, name: &str) {
+        self.progress_bar.inc(1);
+        self.progress_bar.set_message(format!("Compiling {name}"));
+    }
+
+    /// Print a warning above the progress bar without corrupting it
+    fn warn(&self, msg: &str) {
+        self.progress_bar
+            .println(format!("{} {msg}", style("Warning:").bold().yellow()));
+    }
+
     fn finish(&self, msg: &str) {
         self.progress_bar.finish_with_message(msg.to_string());
     }
 }
 
-fn build_for_web(project_dir: &Path, output_dir: &Path, release: bool) -> Result<()> {
+/// A single JSON message emitted by `cargo build --message-format=json`
+#[derive(Debug, Deserialize)]
+struct CargoMessage {
+    reason: String,
+    target: Option<CargoTarget>,
+    #[serde(default)]
+    filenames: Vec<PathBuf>,
+    executable: Option<PathBuf>,
+    success: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoTarget {
+    name: String,
+}
+
+/// An artifact produced by a cargo build
+#[derive(Debug, Clone)]
+struct CargoArtifact {
+    /// Name of the crate target that produced the artifact
+    name: String,
+    /// Files written by the compiler for this target
+    filenames: Vec<PathBuf>,
+    /// Path to the executable, for binary targets
+    executable: Option<PathBuf>,
+}
+
+/// Estimate how many crates a build will compile from the dependency graph
+fn estimate_crate_count(project_dir: &Path) -> Option<u64> {
+    let output = Command::new("cargo")
+        .args(["metadata", "--format-version", "1"])
+        .args(crate::utils::network::cargo_offline_arg())
+        .current_dir(project_dir)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let nodes = metadata.get("resolve")?.get("nodes")?.as_array()?;
+    Some(nodes.len() as u64)
+}
+
+/// Run `cargo build` with JSON output, advancing the progress bar per compiled crate
+///
+/// Returns the artifacts produced by the workspace members that were built.
+fn run_cargo_build(
+    project_dir: &Path,
+    args: &[String],
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    progress: &BuildProgress,
+) -> Result<Vec<CargoArtifact>> {
+    if let Some(count) = estimate_crate_count(project_dir) {
+        progress.add_crates(count);
+    }
+
+    let mut command = Command::new("cargo");
+    if let Some(toolchain) = toolchain {
+        command.arg(format!("+{toolchain}"));
+    }
+    command
+        .arg("build")
+        .args(crate::utils::network::cargo_offline_arg())
+        .arg("--message-format=json-render-diagnostics")
+        .args(args)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .current_dir(project_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    debug!("Running build command: {command:?}");
+
+    let mut child = command
+        .spawn()
+        .context("Failed to run cargo build. Is cargo installed?")?;
+
+    // Drain stderr on a separate thread so cargo never blocks on a full pipe
+    let stderr = child.stderr.take().expect("stderr was piped");
+    let stderr_handle = std::thread::spawn(move || {
+        let mut buffer = String::new();
+        let _ = BufReader::new(stderr).read_to_string(&mut buffer);
+        buffer
+    });
+
+    let mut artifacts = Vec::new();
+    let mut build_succeeded = None;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    for line in BufReader::new(stdout).lines() {
+        let line = line.context("Failed to read cargo output")?;
+        let Ok(message) = serde_json::from_str::<CargoMessage>(&line) else {
+            continue;
+        };
+
+        match message.reason.as_str() {
+            "compiler-artifact" => {
+                if let Some(target) = message.target {
+                    progress.crate_compiled(&target.name);
+                    artifacts.push(CargoArtifact {
+                        name: target.name,
+                        filenames: message.filenames,
+                        executable: message.executable,
+                    });
+                }
+            }
+            "build-finished" => build_succeeded = message.success,
+            _ => {}
+        }
+    }
+
+    let status = child.wait().context("Failed to wait for cargo build")?;
+    let stderr_output = stderr_handle.join().unwrap_or_default();
+
+    if !status.success() || build_succeeded == Some(false) {
+        progress.progress_bar.abandon_with_message("Build failed");
+        return Err(anyhow::anyhow!("cargo build failed:\n{stderr_output}"));
+    }
+
+    Ok(artifacts)
+}
+
+fn build_for_web(
+    project_dir: &Path,
+    output_dir: &Path,
+    release: bool,
+    hash_assets: bool,
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    codegen: &CodegenOptions,
+) -> Result<Vec<PathBuf>> {
     info!("Starting Web build process");
     let progress = BuildProgress::new(4, &BuildTarget::Web);
 
     // Parse .orbit files
     progress.step("Parsing .orbit files");
-    let orbit_files = find_orbit_files(project_dir)?;
+    let orbit_files = find_orbit_files(project_dir, &codegen.exclude)?;
 
     // Generate Rust code
     progress.step("Generating Rust code");
-    generate_rust_code(&orbit_files, output_dir)?;
+    generate_rust_code(project_dir, &orbit_files, output_dir, codegen)?;
 
     // Compile to WASM
     progress.step("Compiling to WASM");
-    compile_to_wasm(output_dir, release)?;
+    let emitted = compile_to_wasm(project_dir, output_dir, release, env, toolchain, &progress)?;
 
     // Generate wrapper files
     progress.step("Generating HTML/JS/CSS wrappers");
-    generate_web_wrappers(output_dir)?;
+    let artifacts = generate_web_wrappers(project_dir, output_dir, &emitted, hash_assets)?;
 
     progress.finish("Web build completed successfully");
-    Ok(())
+    Ok(artifacts.iter().map(|name| output_dir.join(name)).collect())
 }
 
-fn build_for_desktop(project_dir: &Path, output_dir: &Path, release: bool) -> Result<()> {
+fn build_for_desktop(
+    project_dir: &Path,
+    output_dir: &Path,
+    release: bool,
+    features: &[String],
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    codegen: &CodegenOptions,
+) -> Result<Vec<PathBuf>> {
     info!("Starting Desktop build process");
     let progress = BuildProgress::new(3, &BuildTarget::Desktop);
 
     // Parse .orbit files
     progress.step("Parsing .orbit files");
-    let orbit_files = find_orbit_files(project_dir)?;
+    let orbit_files = find_orbit_files(project_dir, &codegen.exclude)?;
 
     // Generate Rust code
     progress.step("Generating Rust code");
-    generate_rust_code(&orbit_files, output_dir)?;
+    generate_rust_code(project_dir, &orbit_files, output_dir, codegen)?;
 
     // Compile native binary
     progress.step("Compiling native binary");
-    compile_native_binary(output_dir, release)?;
+    let binary = compile_native_binary(
+        project_dir,
+        output_dir,
+        release,
+        features,
+        env,
+        toolchain,
+        &progress,
+    )?;
+    debug!("Copied desktop binary to {binary:?}");
 
     progress.finish("Desktop build completed successfully");
-    Ok(())
+    Ok(vec![binary])
 }
 
-fn build_for_embedded(project_dir: &Path, output_dir: &Path, release: bool) -> Result<()> {
+/// Settings specific to embedded builds
+struct EmbeddedOptions<'a> {
+    /// Target triple of the MCU, e.g. `thumbv7em-none-eabihf`
+    target_triple: &'a str,
+    /// Value of `build.opt_level`, if set
+    opt_level: Option<&'a str>,
+}
+
+fn build_for_embedded(
+    project_dir: &Path,
+    output_dir: &Path,
+    release: bool,
+    embedded: &EmbeddedOptions,
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    codegen: &CodegenOptions,
+) -> Result<Vec<PathBuf>> {
     info!("Starting Embedded build process");
     let progress = BuildProgress::new(4, &BuildTarget::Embedded);
 
     // Parse .orbit files
     progress.step("Parsing .orbit files");
-    let orbit_files = find_orbit_files(project_dir)?;
+    let orbit_files = find_orbit_files(project_dir, &codegen.exclude)?;
 
     // Generate Rust code
     progress.step("Generating Rust code");
-    generate_rust_code(&orbit_files, output_dir)?;
+    generate_rust_code(project_dir, &orbit_files, output_dir, codegen)?;
 
     // Optimize for embedded
     progress.step("Optimizing for embedded target");
-    optimize_for_embedded(output_dir)?;
+    let (firmware, size_before, size_after) = optimize_for_embedded(
+        project_dir,
+        output_dir,
+        release,
+        embedded,
+        env,
+        toolchain,
+        &progress,
+    )?;
 
     // Create firmware package
     progress.step("Creating firmware package");
     create_firmware_package(output_dir, release)?;
 
-    progress.finish("Embedded build completed successfully");
-    Ok(())
+    progress.finish(&format!(
+        "Embedded build completed successfully ({}, saved {})",
+        indicatif::HumanBytes(size_after),
+        indicatif::HumanBytes(size_before.saturating_sub(size_after))
+    ));
+    Ok(vec![firmware])
 }
 
-fn find_orbit_files(dir: &Path) -> Result<Vec<PathBuf>> {
+/// Find the components of a project, in both `.orbit` and `.orbit.md` form
+///
+/// Files matching `exclude` (relative to `dir`) are skipped.
+pub(crate) fn find_orbit_files(dir: &Path, exclude: &globset::GlobSet) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     for entry in walkdir::WalkDir::new(dir) {
         let entry = entry.context("Failed to read directory entry")?;
-        if entry.path().extension().is_some_and(|ext| ext == "orbit") {
-            files.push(entry.path().to_path_buf());
+        if !entry.file_type().is_file() || !is_orbit_file(entry.path()) {
+            continue;
+        }
+        if crate::utils::fs::is_ignored(exclude, dir, entry.path()) {
+            debug!("Excluding {}", entry.path().display());
+            continue;
         }
+        files.push(entry.path().to_path_buf());
     }
+
+    debug!("Found {} .orbit files", files.len());
+    for file in &files {
+        debug!("  {}", file.strip_prefix(dir).unwrap_or(file).display());
+    }
+
     Ok(files)
 }
 
-fn generate_rust_code(_orbit_files: &[PathBuf], _output_dir: &Path) -> Result<()> {
-    // Placeholder: In a real implementation, this would:
-    // 1. Parse each .orbit file
-    // 2. Generate corresponding Rust code
-    // 3. Write the generated code to the output directory
-    std::thread::sleep(std::time::Duration::from_millis(500));
-    Ok(())
+/// Whether a path names a component file (`.orbit` or `.orbit.md`)
+fn is_orbit_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".orbit") || name.ends_with(".orbit.md"))
 }
 
-fn compile_to_wasm(_output_dir: &Path, _release: bool) -> Result<()> {
-    // Placeholder: In a real implementation, this would:
-    // 1. Set up wasm-pack or similar tool
-    // 2. Run the compilation process
-    // 3. Handle optimization if release=true
-    std::thread::sleep(std::time::Duration::from_millis(1000));
-    Ok(())
+/// How `.orbit` files are turned into Rust code
+#[derive(Debug, Default)]
+struct CodegenOptions {
+    /// Cache of previously generated code, if enabled
+    cache: Option<ParseCache>,
+    /// Maximum number of files processed in parallel (`None` = one per CPU)
+    jobs: Option<usize>,
+    /// Components left out of the build
+    exclude: globset::GlobSet,
 }
 
-fn generate_web_wrappers(output_dir: &Path) -> Result<()> {
-    let _ = output_dir; // Acknowledge unused parameter in placeholder
-                        // Placeholder: In a real implementation, this would:
-                        // 1. Generate index.html
-                        // 2. Generate JavaScript glue code
-                        // 3. Copy static assets
-    std::thread::sleep(std::time::Duration::from_millis(300));
+/// Generate Rust code for each `.orbit` file into `output_dir/generated`
+///
+/// Files are processed in parallel. Files whose content is unchanged since the
+/// last build are taken from the parse cache when it is enabled. If several
+/// files fail, the error for the first one by path is reported.
+fn generate_rust_code(
+    project_dir: &Path,
+    orbit_files: &[PathBuf],
+    output_dir: &Path,
+    options: &CodegenOptions,
+) -> Result<()> {
+    use rayon::prelude::*;
+
+    let mut orbit_files = orbit_files.to_vec();
+    orbit_files.sort();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(0))
+        .build()
+        .context("Failed to create code generation thread pool")?;
+
+    // Results keep the input order, so the first error is deterministic
+    let results: Vec<Result<(PathBuf, String, bool)>> = pool.install(|| {
+        orbit_files
+            .par_iter()
+            .map(|path| {
+                let relative = path.strip_prefix(project_dir).unwrap_or(path);
+                let source = std::fs::read(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let generate = || generate_component_code(relative, &source);
+
+                let (code, hit) = match &options.cache {
+                    Some(cache) => cache.get_or_generate(relative, &source, generate)?,
+                    None => (generate()?, false),
+                };
+                Ok((generated_path(relative), code, hit))
+            })
+            .collect()
+    });
+
+    let generated_dir = output_dir.join("generated");
+    let mut reused = 0;
+
+    for result in results {
+        let (relative, code, hit) = result?;
+        reused += usize::from(hit);
+
+        let target = generated_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {parent:?}"))?;
+        }
+        std::fs::write(&target, code)
+            .with_context(|| format!("Failed to write generated code: {target:?}"))?;
+    }
+
+    debug!(
+        "Generated code for {} .orbit files ({reused} reused from cache)",
+        orbit_files.len()
+    );
     Ok(())
 }
 
-fn compile_native_binary(output_dir: &Path, release: bool) -> Result<()> {
-    let _ = (output_dir, release); // Acknowledge unused parameters in placeholder
-                                   // Placeholder: In a real implementation, this would:
-                                   // 1. Set up platform-specific compilation flags
-                                   // 2. Run cargo build with appropriate features
-                                   // 3. Handle optimization if release=true
-    std::thread::sleep(std::time::Duration::from_millis(1500));
-    Ok(())
+/// Path of the generated Rust file for a component, e.g. `app.orbit.md` -> `app.rs`
+fn generated_path(relative: &Path) -> PathBuf {
+    let path = match ComponentFormat::from_path(relative) {
+        ComponentFormat::Markdown => relative.with_extension(""),
+        ComponentFormat::Legacy | ComponentFormat::Modern => relative.to_path_buf(),
+    };
+    path.with_extension("rs")
+}
+
+/// Generate the Rust code for a single component
+///
+/// Only the component's Rust section is extracted for now; template and style
+/// compilation will build on this.
+fn generate_component_code(path: &Path, source: &[u8]) -> Result<String> {
+    let source = std::str::from_utf8(source)
+        .with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+
+    let markdown = ComponentFormat::from_path(path) == ComponentFormat::Markdown;
+    let mut code = String::new();
+    let mut in_code = false;
+    for line in source.lines() {
+        let trimmed = line.trim();
+        let (opens, closes) = if markdown {
+            (trimmed == "```rust", trimmed == "```")
+        } else {
+            (
+                trimmed.starts_with("<code") || trimmed == "<script>",
+                trimmed == "</code>" || trimmed == "</script>",
+            )
+        };
+        if !in_code {
+            in_code = opens;
+        } else if closes {
+            in_code = false;
+        } else {
+            code.push_str(line);
+            code.push('\n');
+        }
+    }
+
+    Ok(format!(
+        "// Generated by orbiton from {}\n{code}",
+        path.display()
+    ))
+}
+
+fn compile_to_wasm(
+    project_dir: &Path,
+    output_dir: &Path,
+    release: bool,
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    progress: &BuildProgress,
+) -> Result<Vec<String>> {
+    let mut args = vec!["--target".to_string(), "wasm32-unknown-unknown".to_string()];
+    if release {
+        args.push("--release".to_string());
+    }
+
+    let artifacts = run_cargo_build(project_dir, &args, env, toolchain, progress)?;
+
+    let wasm_bindgen_available = Command::new("wasm-bindgen")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+
+    if !wasm_bindgen_available {
+        progress.warn(
+            "wasm-bindgen not found, copying raw .wasm without JS glue (install with `cargo install wasm-bindgen-cli`)",
+        );
+    }
+
+    let mut emitted = Vec::new();
+    for path in artifacts.iter().flat_map(|artifact| &artifact.filenames) {
+        if path.extension().is_none_or(|ext| ext != "wasm") {
+            continue;
+        }
+        let stem = path
+            .file_stem()
+            .ok_or_else(|| anyhow::anyhow!("Invalid artifact path: {path:?}"))?
+            .to_string_lossy();
+
+        if wasm_bindgen_available {
+            // Generate the JS glue and processed module for loading in the browser
+            let status = Command::new("wasm-bindgen")
+                .args(["--target", "web", "--no-typescript", "--out-dir"])
+                .arg(output_dir)
+                .arg(path)
+                .status()
+                .context("Failed to run wasm-bindgen")?;
+
+            if !status.success() {
+                return Err(anyhow::anyhow!(
+                    "wasm-bindgen failed for {path:?} with status: {status}"
+                ));
+            }
+            emitted.push(format!("{stem}.js"));
+            emitted.push(format!("{stem}_bg.wasm"));
+        } else {
+            let file_name = format!("{stem}.wasm");
+            std::fs::copy(path, output_dir.join(&file_name))
+                .with_context(|| format!("Failed to copy {path:?} to {output_dir:?}"))?;
+            emitted.push(file_name);
+        }
+    }
+
+    Ok(emitted)
+}
+
+/// Minimal CSS reset shipped alongside the generated `index.html`
+const CSS_RESET: &str = r#"*, *::before, *::after {
+    box-sizing: border-box;
+}
+
+html, body {
+    margin: 0;
+    padding: 0;
+    height: 100%;
+}
+
+body {
+    font-family: system-ui, -apple-system, "Segoe UI", Roboto, sans-serif;
+    line-height: 1.5;
+    -webkit-font-smoothing: antialiased;
+}
+
+img, picture, video, canvas, svg {
+    display: block;
+    max-width: 100%;
+}
+
+#app {
+    height: 100%;
+}
+"#;
+
+/// Default HTML shell used when the project doesn't provide `web/index.html`
+const DEFAULT_INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>{{ project_name }}</title>
+    <link rel="stylesheet" href="./{{ stylesheet }}">
+</head>
+<body>
+    <div id="app"></div>
+{%- if glue_js != "" %}
+    <script type="module">
+        import init from './{{ glue_js }}';
+        init({ module_or_path: './{{ wasm }}' });
+    </script>
+{%- elsif wasm != "" %}
+    <script type="module">
+        WebAssembly.instantiateStreaming(fetch('./{{ wasm }}'), {});
+    </script>
+{%- endif %}
+</body>
+</html>
+"#;
+
+/// Names of the web assets referenced from `index.html`, relative to the output directory
+#[derive(Debug, Default)]
+struct WebAssets {
+    /// wasm-bindgen JS glue module
+    glue_js: Option<String>,
+    /// WebAssembly module loaded by the glue
+    wasm: Option<String>,
+    /// Stylesheet containing the CSS reset
+    stylesheet: String,
+}
+
+impl WebAssets {
+    /// Pick the assets out of the files the compile step emitted
+    ///
+    /// Only files from this build are considered, so leftovers of earlier
+    /// builds in the output directory are never referenced.
+    fn from_emitted(emitted: &[String]) -> Self {
+        let mut assets = Self::default();
+
+        for name in emitted {
+            match Path::new(name).extension().and_then(|ext| ext.to_str()) {
+                Some("js") => assets.glue_js = Some(name.clone()),
+                // Prefer the wasm-bindgen processed module over a raw one
+                Some("wasm") if assets.wasm.is_none() || name.ends_with("_bg.wasm") => {
+                    assets.wasm = Some(name.clone())
+                }
+                _ => {}
+            }
+        }
+
+        assets
+    }
+
+    /// Names of all assets, relative to the output directory
+    fn names(&self) -> Vec<String> {
+        self.glue_js
+            .iter()
+            .chain(&self.wasm)
+            .chain(std::iter::once(&self.stylesheet))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Parse the project's Cargo.toml
+fn read_cargo_manifest(project_dir: &Path) -> Option<toml::Value> {
+    let manifest = std::fs::read_to_string(project_dir.join("Cargo.toml")).ok()?;
+    toml::from_str(&manifest).ok()
+}
+
+/// Read the `[package] name` from the project's Cargo.toml
+fn read_package_name(project_dir: &Path) -> Option<String> {
+    read_cargo_manifest(project_dir)?
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Name of the project's binary: the first `[[bin]]` target, or the package
+/// name when cargo infers a binary from `src/main.rs`
+fn read_binary_name(project_dir: &Path) -> Option<String> {
+    let manifest = read_cargo_manifest(project_dir)?;
+    let explicit_bin = manifest
+        .get("bin")
+        .and_then(|bins| bins.as_array()?.first()?.get("name")?.as_str());
+    if let Some(name) = explicit_bin {
+        return Some(name.to_string());
+    }
+
+    if !project_dir.join("src/main.rs").exists() {
+        return None;
+    }
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Insert a short content hash before the file extension (`app.wasm` -> `app.a1b2c3d4.wasm`)
+fn hashed_file_name(name: &str, contents: &[u8]) -> String {
+    let hash = crypto::content_hash(contents);
+    let short_hash = &hash[..8];
+
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{stem}.{short_hash}.{ext}"),
+        None => format!("{name}.{short_hash}"),
+    }
 }
 
-fn optimize_for_embedded(output_dir: &Path) -> Result<()> {
-    let _ = output_dir; // Acknowledge unused parameter in placeholder
-                        // Placeholder: In a real implementation, this would:
-                        // 1. Apply embedded-specific optimizations
-                        // 2. Minimize binary size
-                        // 3. Verify memory constraints
-    std::thread::sleep(std::time::Duration::from_millis(800));
+/// Whether `candidate` is `name` with a content hash inserted, as produced by [`hashed_file_name`]
+fn is_hashed_variant(candidate: &str, name: &str) -> bool {
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+    let hash = candidate
+        .strip_prefix(stem)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .and_then(|rest| match ext {
+            "" => Some(rest),
+            ext => rest.strip_suffix(ext)?.strip_suffix('.'),
+        });
+    hash.is_some_and(|hash| hash.len() == 8 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Rename an asset to its content-hashed name and return the new name
+///
+/// Hashed copies of the asset left by earlier builds are removed.
+fn hash_asset(output_dir: &Path, name: &str) -> Result<String> {
+    let path = output_dir.join(name);
+    let contents = std::fs::read(&path).with_context(|| format!("Failed to read {path:?}"))?;
+    let hashed_name = hashed_file_name(name, &contents);
+
+    for entry in std::fs::read_dir(output_dir)
+        .with_context(|| format!("Failed to read output directory: {output_dir:?}"))?
+    {
+        let stale = entry?.path();
+        let stale_name = stale.file_name().unwrap_or_default().to_string_lossy();
+        if stale_name != hashed_name && is_hashed_variant(&stale_name, name) {
+            debug!("Removing stale asset {stale_name}");
+            std::fs::remove_file(&stale).with_context(|| format!("Failed to remove {stale:?}"))?;
+        }
+    }
+
+    std::fs::rename(&path, output_dir.join(&hashed_name))
+        .with_context(|| format!("Failed to rename {path:?} to {hashed_name}"))?;
+    debug!("Hashed asset {name} -> {hashed_name}");
+
+    Ok(hashed_name)
+}
+
+/// Rename web assets to content-hashed names and rewrite references between them
+fn hash_web_assets(output_dir: &Path, assets: &mut WebAssets) -> Result<()> {
+    // Hash the wasm module first so the JS glue can be rewritten to reference it
+    if let Some(wasm) = assets.wasm.take() {
+        let hashed_wasm = hash_asset(output_dir, &wasm)?;
+
+        if let Some(glue_js) = &assets.glue_js {
+            let glue_path = output_dir.join(glue_js);
+            let glue = std::fs::read_to_string(&glue_path)
+                .with_context(|| format!("Failed to read {glue_path:?}"))?;
+            std::fs::write(&glue_path, glue.replace(&wasm, &hashed_wasm))
+                .with_context(|| format!("Failed to write {glue_path:?}"))?;
+        }
+
+        assets.wasm = Some(hashed_wasm);
+    }
+
+    if let Some(glue_js) = assets.glue_js.take() {
+        assets.glue_js = Some(hash_asset(output_dir, &glue_js)?);
+    }
+
+    assets.stylesheet = hash_asset(output_dir, &assets.stylesheet)?;
+
     Ok(())
 }
 
+/// Write `index.html` and the stylesheet around the files emitted by the compile step
+///
+/// Returns the names of the web artifacts, relative to `output_dir`.
+fn generate_web_wrappers(
+    project_dir: &Path,
+    output_dir: &Path,
+    emitted: &[String],
+    hash_assets: bool,
+) -> Result<Vec<String>> {
+    let mut assets = WebAssets::from_emitted(emitted);
+
+    // Write the CSS reset next to the compiled module
+    assets.stylesheet = "reset.css".to_string();
+    std::fs::write(output_dir.join(&assets.stylesheet), CSS_RESET)
+        .with_context(|| format!("Failed to write stylesheet to {output_dir:?}"))?;
+
+    if hash_assets {
+        hash_web_assets(output_dir, &mut assets)?;
+    }
+
+    // Projects can customize the shell by providing web/index.html
+    let custom_template = project_dir.join("web").join("index.html");
+    let template_source = if custom_template.exists() {
+        std::fs::read_to_string(&custom_template)
+            .with_context(|| format!("Failed to read {custom_template:?}"))?
+    } else {
+        DEFAULT_INDEX_HTML.to_string()
+    };
+
+    let project_name = read_package_name(project_dir).unwrap_or_else(|| "Orbit App".to_string());
+    let globals = liquid::object!({
+        "project_name": project_name,
+        "glue_js": assets.glue_js.clone().unwrap_or_default(),
+        "wasm": assets.wasm.clone().unwrap_or_default(),
+        "stylesheet": assets.stylesheet.clone(),
+    });
+
+    let html = liquid::ParserBuilder::with_stdlib()
+        .build()?
+        .parse(&template_source)
+        .context("Failed to parse index.html template")?
+        .render(&globals)
+        .context("Failed to render index.html template")?;
+
+    std::fs::write(output_dir.join("index.html"), html)
+        .with_context(|| format!("Failed to write index.html to {output_dir:?}"))?;
+
+    let mut artifacts = assets.names();
+    artifacts.push("index.html".to_string());
+    Ok(artifacts)
+}
+
+/// Build the project's binary for the host and copy it into `output_dir`
+///
+/// Returns the path of the copied binary.
+fn compile_native_binary(
+    project_dir: &Path,
+    output_dir: &Path,
+    release: bool,
+    features: &[String],
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    progress: &BuildProgress,
+) -> Result<PathBuf> {
+    let binary_name = read_binary_name(project_dir).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No binary target found in {:?}. Desktop builds need a [[bin]] target or src/main.rs; library-only crates such as component libraries can't be built for desktop",
+            project_dir.join("Cargo.toml")
+        )
+    })?;
+
+    let mut args = vec!["--bin".to_string(), binary_name.clone()];
+    if release {
+        args.push("--release".to_string());
+    }
+    if !features.is_empty() {
+        args.push("--features".to_string());
+        args.push(features.join(","));
+    }
+
+    let artifacts = run_cargo_build(project_dir, &args, env, toolchain, progress)?;
+    let executable = find_executable(&artifacts, &binary_name)?;
+
+    let destination = output_dir.join(format!("{binary_name}{}", std::env::consts::EXE_SUFFIX));
+    std::fs::copy(executable, &destination)
+        .with_context(|| format!("Failed to copy {executable:?} to {destination:?}"))?;
+
+    Ok(destination)
+}
+
+/// Find the executable cargo built for the named binary target
+fn find_executable<'a>(artifacts: &'a [CargoArtifact], binary_name: &str) -> Result<&'a Path> {
+    artifacts
+        .iter()
+        .filter(|artifact| artifact.name == binary_name)
+        .find_map(|artifact| artifact.executable.as_deref())
+        .ok_or_else(|| anyhow::anyhow!("cargo build did not produce the `{binary_name}` binary"))
+}
+
+/// Optimization level used for embedded builds when `build.opt_level` is unset
+const DEFAULT_EMBEDDED_OPT_LEVEL: &str = "z";
+
+/// Build the project's binary optimized for size and shrink it in `output_dir`
+///
+/// Returns the artifact's path and its size before and after shrinking it with
+/// `rust-objcopy`/`llvm-strip` or `wasm-opt`.
+fn optimize_for_embedded(
+    project_dir: &Path,
+    output_dir: &Path,
+    release: bool,
+    embedded: &EmbeddedOptions,
+    env: &[(String, String)],
+    toolchain: Option<&str>,
+    progress: &BuildProgress,
+) -> Result<(PathBuf, u64, u64)> {
+    let binary_name = read_binary_name(project_dir).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No binary target found in {:?}. Embedded builds need a [[bin]] target or src/main.rs",
+            project_dir.join("Cargo.toml")
+        )
+    })?;
+
+    // Override the profile's opt-level without touching the project's Cargo.toml
+    let profile = if release { "RELEASE" } else { "DEV" };
+    let mut env = env.to_vec();
+    env.push((
+        format!("CARGO_PROFILE_{profile}_OPT_LEVEL"),
+        embedded
+            .opt_level
+            .unwrap_or(DEFAULT_EMBEDDED_OPT_LEVEL)
+            .to_string(),
+    ));
+
+    let mut args = vec![
+        "--bin".to_string(),
+        binary_name.clone(),
+        "--target".to_string(),
+        embedded.target_triple.to_string(),
+    ];
+    if release {
+        args.push("--release".to_string());
+    }
+
+    let artifacts = run_cargo_build(project_dir, &args, &env, toolchain, progress)?;
+    let executable = find_executable(&artifacts, &binary_name)?;
+
+    let file_name = executable
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid artifact path: {executable:?}"))?;
+    let destination = output_dir.join(file_name);
+    std::fs::copy(executable, &destination)
+        .with_context(|| format!("Failed to copy {executable:?} to {destination:?}"))?;
+
+    let size_before = std::fs::metadata(&destination)?.len();
+    let shrunk = shrunk_path(&destination);
+    if let Some(warning) = shrink_artifact(
+        &destination,
+        &shrunk,
+        shrink_commands(&destination, &shrunk),
+    ) {
+        progress.warn(&warning);
+    }
+    let size_after = std::fs::metadata(&destination)?.len();
+    debug!("Shrunk {destination:?} from {size_before} to {size_after} bytes");
+
+    Ok((destination, size_before, size_after))
+}
+
+/// Temporary file a shrink tool writes to, renamed over the artifact on success
+fn shrunk_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".shrunk");
+    path.with_file_name(name)
+}
+
+/// Commands that shrink `path` into `output`, in order of preference
+///
+/// `wasm-opt` handles wasm. Native firmware is cross-compiled, which the
+/// host's `strip` often can't read, so the target-independent LLVM tools
+/// from `cargo-binutils` or an LLVM install are used instead.
+fn shrink_commands(path: &Path, output: &Path) -> Vec<Command> {
+    if path.extension().is_some_and(|ext| ext == "wasm") {
+        let mut command = Command::new("wasm-opt");
+        command.arg("-Oz").arg(path).arg("-o").arg(output);
+        return vec![command];
+    }
+
+    let mut objcopy = Command::new("rust-objcopy");
+    objcopy.arg("--strip-all").arg(path).arg(output);
+    let mut strip = Command::new("llvm-strip");
+    strip.arg(path).arg("-o").arg(output);
+    vec![objcopy, strip]
+}
+
+/// Shrink `path` with the first installed tool of `commands`
+///
+/// Returns a warning when no tool is installed or the tool fails; the
+/// artifact is then left as it was rather than failing the build.
+fn shrink_artifact(path: &Path, output: &Path, commands: Vec<Command>) -> Option<String> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut tools = Vec::new();
+
+    for mut command in commands {
+        let tool = command.get_program().to_string_lossy().into_owned();
+        let result = command.output();
+        let failure = match result {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tools.push(tool);
+                continue;
+            }
+            Err(e) => format!("{e}"),
+            Ok(result) if !result.status.success() => {
+                String::from_utf8_lossy(&result.stderr).trim().to_string()
+            }
+            Ok(_) => match std::fs::rename(output, path) {
+                Ok(()) => return None,
+                Err(e) => format!("Failed to replace {path:?}: {e}"),
+            },
+        };
+
+        let _ = std::fs::remove_file(output);
+        return Some(format!(
+            "{tool} failed, leaving {name} unoptimized: {failure}"
+        ));
+    }
+
+    Some(format!(
+        "{} not found, skipping size optimization of {name}",
+        tools.join(" or ")
+    ))
+}
+
 fn create_firmware_package(output_dir: &Path, release: bool) -> Result<()> {
     let _ = (output_dir, release); // Acknowledge unused parameters in placeholder
                                    // Placeholder: In a real implementation, this would:
@@ -278,3 +1283,361 @@ fn create_firmware_package(output_dir: &Path, release: bool) -> Result<()> {
     std::thread::sleep(std::time::Duration::from_millis(500));
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_orbit_files() -> Result<()> {
+        let temp_dir = tempdir()?;
+        std::fs::write(temp_dir.path().join("foo.orbit.md"), "# Foo")?;
+        std::fs::write(temp_dir.path().join("bar.orbit"), "<template></template>")?;
+        std::fs::write(temp_dir.path().join("README.md"), "# Readme")?;
+
+        let mut files = find_orbit_files(temp_dir.path(), &globset::GlobSet::empty())?;
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                temp_dir.path().join("bar.orbit"),
+                temp_dir.path().join("foo.orbit.md")
+            ]
+        );
+
+        std::fs::create_dir_all(temp_dir.path().join("examples/demo"))?;
+        std::fs::write(temp_dir.path().join("examples/demo/demo.orbit"), "")?;
+        let exclude =
+            crate::utils::fs::glob_set(&["examples/**".to_string(), "foo.*".to_string()])?;
+        let files = find_orbit_files(temp_dir.path(), &exclude)?;
+        assert_eq!(files, [temp_dir.path().join("bar.orbit")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_component_code_markdown() -> Result<()> {
+        let source = "# Foo\n\nUses a `<code>` tag in prose.\n\n<code>\nnot rust\n</code>\n\n```rust\npub struct Foo;\n```\n";
+        let code = generate_component_code(Path::new("src/foo.orbit.md"), source.as_bytes())?;
+        assert!(code.ends_with("\npub struct Foo;\n"));
+        assert!(!code.contains("not rust"));
+
+        assert_eq!(
+            generated_path(Path::new("src/foo.orbit.md")),
+            Path::new("src/foo.rs")
+        );
+        assert_eq!(
+            generated_path(Path::new("src/bar.orbit")),
+            Path::new("src/bar.rs")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run_does_not_create_output() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let project_dir = temp_dir.path();
+        std::fs::create_dir_all(project_dir.join("src"))?;
+        std::fs::write(project_dir.join("src/app.orbit"), "<template></template>")?;
+
+        let options = BuildOptions {
+            dry_run: true,
+            no_env: true,
+            ..BuildOptions::default()
+        };
+        let output_dir = run(
+            project_dir,
+            &OrbitonConfig::default(),
+            BuildTarget::Web,
+            &options,
+        )?;
+
+        assert_eq!(output_dir, project_dir.join("build/web"));
+        assert!(!project_dir.join("build").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_rust_code_uses_cache() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let project_dir = temp_dir.path();
+        let component = project_dir.join("src/components/button.orbit");
+        std::fs::create_dir_all(component.parent().unwrap())?;
+        std::fs::write(
+            &component,
+            "<template>\n  <button />\n</template>\n\n<code lang=\"rust\">\npub struct Button;\n</code>\n",
+        )?;
+
+        let output_dir = project_dir.join("build/web");
+        let options = CodegenOptions {
+            cache: Some(ParseCache::new(project_dir)),
+            ..CodegenOptions::default()
+        };
+        let files = vec![component];
+        generate_rust_code(project_dir, &files, &output_dir, &options)?;
+
+        let generated = output_dir.join("generated/src/components/button.rs");
+        let code = std::fs::read_to_string(&generated)?;
+        assert!(code.contains("pub struct Button;"));
+        assert!(!code.contains("<template>"));
+
+        // A second build reuses the cached code for the unchanged file
+        std::fs::remove_file(&generated)?;
+        generate_rust_code(project_dir, &files, &output_dir, &options)?;
+        assert_eq!(std::fs::read_to_string(&generated)?, code);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_rust_code_in_parallel() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let project_dir = temp_dir.path();
+        let components_dir = project_dir.join("src/components");
+        std::fs::create_dir_all(&components_dir)?;
+
+        let files: Vec<PathBuf> = (0..200)
+            .map(|i| {
+                let path = components_dir.join(format!("component_{i:03}.orbit"));
+                let source = format!(
+                    "<template>\n  <div>{i}</div>\n</template>\n\n<code lang=\"rust\">\npub struct Component{i};\n</code>\n"
+                );
+                std::fs::write(&path, source).map(|_| path)
+            })
+            .collect::<std::io::Result<_>>()?;
+
+        let mut outputs = Vec::new();
+        for jobs in [1, 4] {
+            let output_dir = project_dir.join(format!("build-{jobs}"));
+            let options = CodegenOptions {
+                jobs: Some(jobs),
+                ..CodegenOptions::default()
+            };
+
+            generate_rust_code(project_dir, &files, &output_dir, &options)?;
+
+            outputs.push(std::fs::read_to_string(
+                output_dir.join("generated/src/components/component_123.rs"),
+            )?);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        assert!(outputs[0].contains("pub struct Component123;"));
+
+        // With several broken files the first one by path is reported
+        std::fs::write(components_dir.join("component_150.orbit"), [0xff, 0xfe])?;
+        std::fs::write(components_dir.join("component_050.orbit"), [0xff, 0xfe])?;
+        let options = CodegenOptions {
+            jobs: Some(4),
+            ..CodegenOptions::default()
+        };
+        let error = generate_rust_code(project_dir, &files, &project_dir.join("build"), &options)
+            .unwrap_err();
+        assert!(error.to_string().contains("component_050.orbit"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_binary_name() {
+        let project_dir = tempdir().unwrap();
+        let project = project_dir.path();
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"my-app\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        // Library-only crates have no binary to ship
+        assert_eq!(read_binary_name(project), None);
+
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src/main.rs"), "fn main() {}").unwrap();
+        assert_eq!(read_binary_name(project).as_deref(), Some("my-app"));
+
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"my-app\"\n\n[[bin]]\nname = \"launcher\"\npath = \"src/main.rs\"\n",
+        )
+        .unwrap();
+        assert_eq!(read_binary_name(project).as_deref(), Some("launcher"));
+    }
+
+    #[test]
+    fn test_shrink_commands() {
+        let output = Path::new("build/out");
+        let wasm = shrink_commands(Path::new("build/firmware.wasm"), output);
+        assert_eq!(wasm[0].get_program(), "wasm-opt");
+        assert_eq!(wasm[0].get_args().next().unwrap(), "-Oz");
+
+        // Cross-compiled firmware isn't handed to the host's strip
+        let native = shrink_commands(Path::new("build/firmware"), output);
+        let programs: Vec<_> = native.iter().map(Command::get_program).collect();
+        assert_eq!(programs, ["rust-objcopy", "llvm-strip"]);
+        assert_eq!(
+            shrunk_path(Path::new("build/firmware")),
+            Path::new("build/firmware.shrunk")
+        );
+    }
+
+    #[test]
+    fn test_shrink_failures_keep_the_artifact() {
+        let dir = tempdir().unwrap();
+        let firmware = dir.path().join("firmware");
+        std::fs::write(&firmware, "elf").unwrap();
+        let output = shrunk_path(&firmware);
+
+        // Missing tools are skipped rather than failing the build
+        let missing = vec![
+            Command::new("orbiton-test-missing-tool"),
+            Command::new("orbiton-test-other-tool"),
+        ];
+        let warning = shrink_artifact(&firmware, &output, missing).unwrap();
+        assert!(
+            warning.starts_with("orbiton-test-missing-tool or orbiton-test-other-tool not found")
+        );
+
+        // So are tools that reject the artifact
+        let mut failing = Command::new("cargo");
+        failing.arg("--orbiton-test-unknown-flag");
+        let warning = shrink_artifact(&firmware, &output, vec![failing]).unwrap();
+        assert!(warning.starts_with("cargo failed, leaving firmware unoptimized"));
+        assert_eq!(std::fs::read_to_string(&firmware).unwrap(), "elf");
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_generate_web_wrappers_default_shell() {
+        let project_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+
+        std::fs::write(
+            project_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"my-app\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(output_dir.path().join("my_app.js"), "// glue").unwrap();
+        std::fs::write(output_dir.path().join("my_app_bg.wasm"), b"\0asm").unwrap();
+
+        let emitted = ["my_app.js".to_string(), "my_app_bg.wasm".to_string()];
+        generate_web_wrappers(project_dir.path(), output_dir.path(), &emitted, false).unwrap();
+
+        let html = std::fs::read_to_string(output_dir.path().join("index.html")).unwrap();
+        assert!(html.contains("<title>my-app</title>"));
+        assert!(html.contains("<div id=\"app\"></div>"));
+        assert!(html.contains("import init from './my_app.js'"));
+        assert!(html.contains("./my_app_bg.wasm"));
+        assert!(html.contains("./reset.css"));
+        assert!(output_dir.path().join("reset.css").exists());
+    }
+
+    #[test]
+    fn test_generate_web_wrappers_custom_shell() {
+        let project_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+
+        let web_dir = project_dir.path().join("web");
+        std::fs::create_dir_all(&web_dir).unwrap();
+        std::fs::write(
+            web_dir.join("index.html"),
+            "<html><body><main id=\"app\"></main><script src=\"{{ glue_js }}\"></script></body></html>",
+        )
+        .unwrap();
+        std::fs::write(output_dir.path().join("app.js"), "// glue").unwrap();
+
+        let emitted = ["app.js".to_string()];
+        generate_web_wrappers(project_dir.path(), output_dir.path(), &emitted, false).unwrap();
+
+        let html = std::fs::read_to_string(output_dir.path().join("index.html")).unwrap();
+        assert!(html.contains("<main id=\"app\"></main>"));
+        assert!(html.contains("<script src=\"app.js\"></script>"));
+    }
+
+    #[test]
+    fn test_hashed_web_assets() {
+        let project_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+
+        std::fs::write(
+            output_dir.path().join("app.js"),
+            "const url = new URL('app_bg.wasm', import.meta.url);",
+        )
+        .unwrap();
+        std::fs::write(output_dir.path().join("app_bg.wasm"), b"\0asm").unwrap();
+
+        let emitted = ["app.js".to_string(), "app_bg.wasm".to_string()];
+        let artifacts =
+            generate_web_wrappers(project_dir.path(), output_dir.path(), &emitted, true).unwrap();
+
+        assert!(!output_dir.path().join("app.js").exists());
+        assert!(!output_dir.path().join("app_bg.wasm").exists());
+        assert!(!output_dir.path().join("reset.css").exists());
+
+        let [glue_js, wasm, stylesheet, index] = artifacts.as_slice() else {
+            panic!("unexpected artifacts: {artifacts:?}");
+        };
+        assert!(stylesheet.starts_with("reset."));
+        assert_eq!(index, "index.html");
+        assert!(glue_js.starts_with("app.") && glue_js.len() == "app.12345678.js".len());
+        assert!(wasm.starts_with("app_bg."));
+
+        let glue = std::fs::read_to_string(output_dir.path().join(glue_js)).unwrap();
+        assert!(glue.contains(wasm.as_str()));
+
+        let html = std::fs::read_to_string(output_dir.path().join("index.html")).unwrap();
+        assert!(html.contains(glue_js.as_str()));
+        assert!(html.contains(wasm.as_str()));
+        assert!(html.contains("./reset."));
+    }
+
+    #[test]
+    fn test_rebuild_replaces_hashed_web_assets() {
+        let project_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+        let emitted = ["app.js".to_string(), "app_bg.wasm".to_string()];
+
+        let mut builds = Vec::new();
+        for version in ["v1", "v2"] {
+            std::fs::write(
+                output_dir.path().join("app.js"),
+                format!("// {version}\nconst url = new URL('app_bg.wasm', import.meta.url);"),
+            )
+            .unwrap();
+            std::fs::write(output_dir.path().join("app_bg.wasm"), version).unwrap();
+            builds.push(
+                generate_web_wrappers(project_dir.path(), output_dir.path(), &emitted, true)
+                    .unwrap(),
+            );
+        }
+
+        // The second wrapper references the second build's assets only
+        let html = std::fs::read_to_string(output_dir.path().join("index.html")).unwrap();
+        for (first, second) in builds[0].iter().zip(&builds[1]).take(2) {
+            assert_ne!(first, second);
+            assert!(html.contains(second.as_str()));
+            assert!(!html.contains(first.as_str()));
+            assert!(!output_dir.path().join(first).exists());
+        }
+
+        // Only this build's files are left behind
+        let mut files: Vec<String> = std::fs::read_dir(output_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        let mut expected = builds[1].clone();
+        expected.sort();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn test_is_hashed_variant() {
+        assert!(is_hashed_variant("app.a1b2c3d4.js", "app.js"));
+        assert!(is_hashed_variant("app_bg.0123abcd.wasm", "app_bg.wasm"));
+        assert!(!is_hashed_variant("app_bg.a1b2c3d4.wasm", "app.wasm"));
+        assert!(!is_hashed_variant("app.js", "app.js"));
+        assert!(!is_hashed_variant("app.vendor.js", "app.js"));
+    }
+}
@@ -0,0 +1,34 @@
+// Command for generating shell completion scripts
+
+use anyhow::Result;
+use clap::Args;
+use clap_complete::Shell;
+
+#[derive(Args)]
+#[command(after_help = "\
+Installation:
+  bash:        orbiton completions bash > ~/.local/share/bash-completion/completions/orbiton
+  zsh:         orbiton completions zsh > ~/.zfunc/_orbiton
+               (add `fpath+=~/.zfunc` before `compinit` in ~/.zshrc)
+  fish:        orbiton completions fish > ~/.config/fish/completions/orbiton.fish
+  powershell:  orbiton completions powershell >> $PROFILE
+  elvish:      orbiton completions elvish >> ~/.config/elvish/rc.elv
+
+Restart the shell afterwards to load the completions.")]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+/// Print a completion script for `command` to stdout
+pub fn execute(args: CompletionsArgs, command: &mut clap::Command) -> Result<()> {
+    write_script(args.shell, command, &mut std::io::stdout());
+    Ok(())
+}
+
+/// Write the completion script of `command` for `shell` to `out`
+pub fn write_script(shell: Shell, command: &mut clap::Command, out: &mut dyn std::io::Write) {
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, command, name, out);
+}
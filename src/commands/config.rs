@@ -3,9 +3,10 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 use console::style;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::OrbitonConfig;
+use crate::status;
 
 #[derive(Args)]
 pub struct ConfigArgs {
@@ -20,6 +21,10 @@ enum ConfigCommand {
         /// Project directory
         #[arg(short, long)]
         dir: Option<PathBuf>,
+
+        /// Print the effective configuration as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Create a default configuration file
     Init {
@@ -33,16 +38,76 @@ enum ConfigCommand {
         #[arg(short, long)]
         dir: Option<PathBuf>,
     },
+    /// Print a single configuration value (e.g. `dev_server.port`)
+    Get {
+        /// Dotted configuration key
+        key: String,
+
+        /// Project directory
+        #[arg(short, long)]
+        dir: Option<PathBuf>,
+    },
+    /// Print a JSON Schema for .orbiton.toml, for editor validation
+    Schema,
+    /// Set a configuration value (e.g. `dev_server.port 8080` or `hmr.enabled=false`)
+    Set {
+        /// Dotted configuration key, optionally with `=value`
+        key: String,
+
+        /// New value for the key
+        value: Option<String>,
+
+        /// Project directory
+        #[arg(short, long)]
+        dir: Option<PathBuf>,
+    },
 }
 
 pub fn execute(args: ConfigArgs) -> Result<()> {
     match args.command {
-        ConfigCommand::Show { dir } => show_config(dir),
+        ConfigCommand::Show { dir, json: false } => show_config(dir),
+        ConfigCommand::Show { dir, json: true } => print_config_json(dir),
         ConfigCommand::Init { dir } => init_config(dir),
         ConfigCommand::Validate { dir } => validate_config(dir),
+        ConfigCommand::Get { key, dir } => get_config(dir, &key),
+        ConfigCommand::Set { key, value, dir } => set_config(dir, &key, value.as_deref()),
+        ConfigCommand::Schema => print_schema(),
+    }
+}
+
+impl ConfigArgs {
+    /// Whether the command's stdout is meant to be consumed by scripts
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(
+            self.command,
+            ConfigCommand::Get { .. }
+                | ConfigCommand::Schema
+                | ConfigCommand::Show { json: true, .. }
+        )
     }
 }
 
+/// Print the JSON Schema for `.orbiton.toml`
+fn print_schema() -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&OrbitonConfig::json_schema())?
+    );
+    Ok(())
+}
+
+/// Print the effective configuration, with defaults and base files merged in, as JSON
+fn print_config_json(dir: Option<PathBuf>) -> Result<()> {
+    let project_dir = get_project_dir(dir)?;
+    println!("{}", config_json(&project_dir)?);
+    Ok(())
+}
+
+fn config_json(project_dir: &Path) -> Result<String> {
+    let config = OrbitonConfig::load_from_project(project_dir)?;
+    Ok(serde_json::to_string_pretty(&config)?)
+}
+
 fn get_project_dir(dir: Option<PathBuf>) -> Result<PathBuf> {
     match dir {
         Some(d) => Ok(d),
@@ -54,7 +119,7 @@ fn get_project_dir(dir: Option<PathBuf>) -> Result<PathBuf> {
 fn show_config(dir: Option<PathBuf>) -> Result<()> {
     let project_dir = get_project_dir(dir)?;
 
-    println!(
+    status!(
         "{} configuration for project at {project_dir:?}",
         style("Showing").bold().blue()
     );
@@ -82,6 +147,18 @@ fn show_config(dir: Option<PathBuf>) -> Result<()> {
         "  Auto-open browser: {}",
         style(config.dev_server.auto_open).cyan()
     );
+    println!(
+        "  Max WebSocket clients: {}",
+        style(config.dev_server.max_ws_clients).cyan()
+    );
+    println!(
+        "  Directory listing: {}",
+        style(config.dev_server.directory_listing).cyan()
+    );
+    println!(
+        "  Index file: {}",
+        style(&config.dev_server.index_file).cyan()
+    );
 
     println!("\n{}", style("Hot Module Reload:").bold().underlined());
     println!("  Enabled: {}", style(config.hmr.enabled).cyan());
@@ -94,6 +171,12 @@ fn show_config(dir: Option<PathBuf>) -> Result<()> {
         style(config.hmr.preserve_state).cyan()
     );
     println!("  Max retries: {}", style(config.hmr.max_retries).cyan());
+    if !config.hmr.watch_dirs.is_empty() {
+        println!(
+            "  Extra watch directories: {}",
+            style(config.hmr.watch_dirs.join(", ")).cyan()
+        );
+    }
 
     println!("\n{}", style("Build Configuration:").bold().underlined());
     println!(
@@ -104,30 +187,45 @@ fn show_config(dir: Option<PathBuf>) -> Result<()> {
     if let Some(target) = &config.build.target {
         println!("  Target: {}", style(target).cyan());
     }
+    if let Some(command) = &config.build.command {
+        println!(
+            "  Rebuild command: {}",
+            style(shell_words::join(command)).cyan()
+        );
+    }
+    if let Some(hook) = &config.build.pre_rebuild_hook {
+        println!("  Pre-rebuild hook: {}", style(hook).cyan());
+    }
+    if let Some(hook) = &config.build.post_rebuild_hook {
+        println!("  Post-rebuild hook: {}", style(hook).cyan());
+    }
 
     println!("\n{}", style("Lint Configuration:").bold().underlined());
     println!("  Enabled: {}", style(config.lint.enabled).cyan());
 
+    println!("\n{}", style("Renderer Configuration:").bold().underlined());
+    println!("  Backend: {}", style(config.renderer.backend).cyan());
+
     Ok(())
 }
 
 fn init_config(dir: Option<PathBuf>) -> Result<()> {
     let project_dir = get_project_dir(dir)?;
 
-    println!(
+    status!(
         "{} default configuration in {project_dir:?}",
         style("Creating").bold().green()
     );
 
     let config_path = OrbitonConfig::create_default_config(&project_dir)?;
 
-    println!(
+    status!(
         "{} Configuration file created at: {}",
         style("Success!").bold().green(),
         style(config_path.display()).cyan()
     );
 
-    println!("\nYou can now customize the configuration by editing the .orbiton.toml file.");
+    status!("\nYou can now customize the configuration by editing the .orbiton.toml file.");
 
     Ok(())
 }
@@ -135,7 +233,7 @@ fn init_config(dir: Option<PathBuf>) -> Result<()> {
 fn validate_config(dir: Option<PathBuf>) -> Result<()> {
     let project_dir = get_project_dir(dir)?;
 
-    println!(
+    status!(
         "{} configuration for project at {project_dir:?}",
         style("Validating").bold().yellow()
     );
@@ -144,7 +242,7 @@ fn validate_config(dir: Option<PathBuf>) -> Result<()> {
 
     match config.validate() {
         Ok(()) => {
-            println!(
+            status!(
                 "{} Configuration is valid!",
                 style("Success!").bold().green()
             );
@@ -162,6 +260,46 @@ fn validate_config(dir: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+fn get_config(dir: Option<PathBuf>, key: &str) -> Result<()> {
+    let project_dir = get_project_dir(dir)?;
+    let config = OrbitonConfig::load_from_project(&project_dir)?;
+
+    // Print the bare value so it can be captured by shell scripts
+    println!("{}", config.get_value(key)?);
+
+    Ok(())
+}
+
+fn set_config(dir: Option<PathBuf>, key: &str, value: Option<&str>) -> Result<()> {
+    let project_dir = get_project_dir(dir)?;
+
+    // Accept both `key value` and `key=value`
+    let (key, value) = match value {
+        Some(value) => (key, value),
+        None => key.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Missing value for {key}. Use `config set <key> <value>` or `<key>=<value>`"
+            )
+        })?,
+    };
+
+    // Edit the nearest config file, or create one in the project directory
+    let (mut config, config_path) = OrbitonConfig::load_for_update(&project_dir)?;
+
+    config.set_value(key, value)?;
+    config.validate()?;
+    config.save_to_file(&config_path)?;
+
+    status!(
+        "{} {} = {}",
+        style("Set").bold().green(),
+        style(key).cyan(),
+        style(value).bold()
+    );
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +326,40 @@ mod tests {
         let result = validate_config(Some(temp_dir.path().to_path_buf()));
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_config_show_json() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join(".orbiton.toml"),
+            "[dev_server]\nport = 4000\n",
+        )
+        .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&config_json(temp_dir.path()).unwrap()).unwrap();
+        assert_eq!(json["dev_server"]["port"], 4000);
+        assert_eq!(json["hmr"]["enabled"], true);
+        assert_eq!(json["renderer"]["backend"], "auto");
+    }
+
+    #[test]
+    fn test_config_set() {
+        let temp_dir = tempdir().unwrap();
+        let dir = Some(temp_dir.path().to_path_buf());
+
+        set_config(dir.clone(), "dev_server.port=8080", None).unwrap();
+        set_config(dir.clone(), "hmr.enabled", Some("false")).unwrap();
+
+        let config = OrbitonConfig::load_from_file(&temp_dir.path().join(".orbiton.toml")).unwrap();
+        assert_eq!(config.dev_server.port, 8080);
+        assert!(!config.hmr.enabled);
+
+        // Invalid values are rejected without touching the file
+        assert!(set_config(dir.clone(), "dev_server.port", Some("0")).is_err());
+        assert!(set_config(dir, "no.such.key", Some("1")).is_err());
+
+        let config = OrbitonConfig::load_from_file(&temp_dir.path().join(".orbiton.toml")).unwrap();
+        assert_eq!(config.dev_server.port, 8080);
+    }
 }
@@ -4,13 +4,20 @@ use anyhow::Result;
 use clap::Args;
 use console::style;
 use log::{debug, error, info};
-use notify::{Event, RecursiveMode, Watcher};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::config::OrbitonConfig;
+use crate::config::{BuildConfig, OrbitonConfig};
 use crate::dev_server::DevServer;
+use crate::hmr::protocol::DevMessage;
+use crate::hmr::{HmrContext, FULL_RELOAD_MODULE};
+use crate::hmr_inject::is_html_file;
+use crate::status;
 
 #[derive(Args)]
 pub struct DevArgs {
@@ -18,6 +25,10 @@ pub struct DevArgs {
     #[arg(short, long, default_value = "8000")]
     port: u16,
 
+    /// Host address to bind to (e.g. 0.0.0.0 to allow LAN access)
+    #[arg(long)]
+    host: Option<String>,
+
     /// Project directory
     #[arg(short, long)]
     dir: Option<PathBuf>,
@@ -26,9 +37,17 @@ pub struct DevArgs {
     #[arg(short, long)]
     open: bool,
 
+    /// Path to open in the browser (e.g. /app)
+    #[arg(long)]
+    open_path: Option<String>,
+
     /// Use beta toolchain for building and testing
     #[arg(long)]
     beta: bool,
+
+    /// Don't pass variables from the project's .env file to cargo
+    #[arg(long)]
+    no_env: bool,
 }
 
 pub fn execute(args: DevArgs) -> Result<()> {
@@ -45,63 +64,58 @@ pub fn execute(args: DevArgs) -> Result<()> {
     if args.port != 8000 {
         config.dev_server.port = args.port;
     }
+    if let Some(host) = args.host {
+        config.dev_server.host = host;
+    }
+    if let Some(open_path) = args.open_path {
+        config.dev_server.open_path = open_path;
+    }
     if args.beta {
         config.build.use_beta_toolchain = true;
     }
 
+    let options = DevOptions {
+        open: args.open,
+        no_env: args.no_env,
+    };
+    run(&project_dir, &config, &options)
+}
+
+/// Options for the development server, mirroring the `orbiton dev` flags
+#[derive(Debug, Clone, Default)]
+pub struct DevOptions {
+    /// Open the browser once the server is running (also enabled by `dev_server.auto_open`)
+    pub open: bool,
+    /// Don't pass variables from the project's .env file to cargo
+    pub no_env: bool,
+}
+
+/// Run the development server for a project until the process is interrupted
+pub fn run(project_dir: &Path, config: &OrbitonConfig, options: &DevOptions) -> Result<()> {
     // Validate the configuration
     config.validate()?;
 
     if config.build.use_beta_toolchain {
-        println!(
+        status!(
             "{} development server with {} toolchain for project at {project_dir:?}",
             style("Starting").bold().green(),
             style("beta").bold().yellow()
         );
     } else {
-        println!(
+        status!(
             "{} development server for project at {project_dir:?}",
             style("Starting").bold().green()
         );
     }
 
     // Create a development server using the configuration
-    let mut server = DevServer::new_with_options(
-        config.dev_server.port,
-        &project_dir,
-        config.build.use_beta_toolchain,
-    )?;
+    let mut server = DevServer::from_config(project_dir, config)?;
 
     if config.build.use_beta_toolchain {
         // Verify beta toolchain is installed
-        match std::process::Command::new("rustup")
-            .args(["toolchain", "list"])
-            .output()
-        {
-            Ok(output) => {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                if !output_str.contains("beta") {
-                    println!(
-                        "{} Beta toolchain not installed. Installing...",
-                        style("Warning:").bold().yellow()
-                    );
-
-                    // Try to install beta toolchain
-                    let install_result = std::process::Command::new("rustup")
-                        .args(["toolchain", "install", "beta"])
-                        .status();
-
-                    if let Err(e) = install_result {
-                        return Err(anyhow::anyhow!("Failed to install beta toolchain: {}", e));
-                    }
-                }
-            }
-            Err(e) => {
-                return Err(anyhow::anyhow!("Failed to check for beta toolchain: {}", e));
-            }
-        }
+        crate::utils::toolchain::ensure_toolchain("beta")?;
 
-        println!(
+        status!(
             "{} Using Rust beta toolchain for builds",
             style("Info:").bold().blue()
         );
@@ -110,84 +124,833 @@ pub fn execute(args: DevArgs) -> Result<()> {
     // Start the server in a separate thread
     let _server_handle = server.start()?;
 
-    println!(
-        "Development server running at {}",
-        style(format!("http://localhost:{}", config.dev_server.port))
-            .bold()
-            .blue()
-            .underlined()
-    );
+    let urls = ServerUrls::resolve(&config.dev_server.host, config.dev_server.port);
+    println!("Development server running at:");
+    urls.print();
 
     // Open the browser if requested (use config or CLI args)
-    let should_open = args.open || config.dev_server.auto_open;
+    let should_open = options.open || config.dev_server.auto_open;
     if should_open {
-        if let Err(e) = open::that(format!("http://localhost:{}", config.dev_server.port)) {
+        let url = join_url_path(urls.browser_url(), &config.dev_server.open_path);
+        if let Err(e) = open::that(url) {
             error!("Failed to open browser: {e}");
         }
     }
 
     // Set up file watching
-    setup_file_watching(project_dir.as_path(), &server)?;
+    let stats = Arc::new(SessionStats::default());
+    setup_file_watching(project_dir, &server, !options.no_env, Arc::clone(&stats))?;
 
     // Wait for Ctrl+C
-    println!("Press {} to stop the server", style("Ctrl+C").bold());
+    status!("Press {} to stop the server", style("Ctrl+C").bold());
+    let summary_server = server.clone();
     ctrlc::set_handler(move || {
-        println!("\n{} development server", style("Stopping").bold().red());
+        status!("\n{} development server", style("Stopping").bold().red());
+        print_session_summary(&stats, &summary_server);
         std::process::exit(0);
     })?;
 
-    // Keep the main thread running
-    loop {
-        std::thread::sleep(Duration::from_secs(1));
+    // Serve until Ctrl+C; the server only stops on its own when it fails
+    server.wait()?;
+    Err(anyhow::anyhow!(
+        "The development server stopped unexpectedly"
+    ))
+}
+
+/// Rebuild counters for the summary printed when the dev server stops
+#[derive(Debug, Default)]
+struct SessionStats {
+    rebuilds: AtomicU64,
+    failed_rebuilds: AtomicU64,
+    rebuild_millis: AtomicU64,
+}
+
+impl SessionStats {
+    fn record_rebuild(&self, duration: Duration, success: bool) {
+        self.rebuilds.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failed_rebuilds.fetch_add(1, Ordering::Relaxed);
+        }
+        self.rebuild_millis
+            .fetch_add(duration.as_millis() as u64, Ordering::Relaxed);
     }
+
+    /// Mean duration of the rebuilds so far, if there were any
+    fn average_rebuild(&self) -> Option<Duration> {
+        let rebuilds = self.rebuilds.load(Ordering::Relaxed);
+        (rebuilds > 0)
+            .then(|| Duration::from_millis(self.rebuild_millis.load(Ordering::Relaxed) / rebuilds))
+    }
+}
+
+/// Print rebuild and HMR activity for the session
+fn print_session_summary(stats: &SessionStats, server: &DevServer) {
+    let average = stats
+        .average_rebuild()
+        .map_or_else(|| "-".to_string(), |average| format!("{average:.2?}"));
+
+    status!("{}", style("Session summary").bold());
+    status!(
+        "  Rebuilds:        {} ({} failed)",
+        stats.rebuilds.load(Ordering::Relaxed),
+        stats.failed_rebuilds.load(Ordering::Relaxed)
+    );
+    status!("  Average rebuild: {average}");
+    status!("  HMR updates:     {}", server.hmr_update_count());
+    status!("  Clients:         {}", server.client_connection_count());
+}
+
+/// Record a watcher event in the HMR context
+///
+/// Removals drop the module's pending update and renames move it to the new
+/// path. Duplicate paths in the event are only recorded once. Returns the
+/// changed paths with their modules.
+fn record_event(hmr_context: &HmrContext, event: &Event) -> Vec<(PathBuf, String)> {
+    let mut seen = HashSet::new();
+    let paths: Vec<&PathBuf> = event.paths.iter().filter(|p| seen.insert(*p)).collect();
+
+    match (&event.kind, paths.as_slice()) {
+        (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) => hmr_context
+            .record_file_renamed(from, to)
+            .map(|module| vec![((*to).clone(), module)])
+            .unwrap_or_default(),
+        (EventKind::Remove(_), paths)
+        | (EventKind::Modify(ModifyKind::Name(RenameMode::From)), paths) => paths
+            .iter()
+            .filter_map(|path| {
+                hmr_context
+                    .record_file_removed(path)
+                    .map(|module| ((*path).clone(), module))
+            })
+            .collect(),
+        (_, paths) => paths
+            .iter()
+            .filter_map(|path| {
+                hmr_context
+                    .record_file_change(path)
+                    .map(|module| ((*path).clone(), module))
+            })
+            .collect(),
+    }
+}
+
+/// Outcome of a dev-mode rebuild
+struct RebuildResult {
+    /// Whether the build command succeeded
+    success: bool,
+    /// Combined stdout and stderr of the build, with ANSI colors stripped
+    output: String,
+    /// Whether the pre-rebuild hook failed, so the build never ran
+    hook_failed: bool,
+    /// Whether the build was killed because newer changes arrived
+    cancelled: bool,
+    /// Time taken by the build command
+    duration: Duration,
 }
 
 /// Rebuild the project using cargo
 ///
-/// Returns true if the build was successful, false otherwise
-fn rebuild_project(project_dir: &Path, use_beta: bool) -> bool {
-    // Determine which toolchain to use
-    let mut command = if use_beta {
-        let mut cmd = std::process::Command::new("cargo");
-        cmd.arg("+beta");
-        cmd
-    } else {
-        std::process::Command::new("cargo")
-    };
+/// Cargo's output is streamed to the terminal as it arrives and captured for
+/// forwarding to browser clients. `cancel` is polled while the build runs;
+/// once it returns true the build is killed and reported as cancelled.
+fn rebuild_project(
+    project_dir: &Path,
+    build: &BuildConfig,
+    use_beta: bool,
+    load_env: bool,
+    cancel: &mut dyn FnMut() -> bool,
+) -> RebuildResult {
+    // Re-read .env on every rebuild so edits apply without restarting
+    let env = hook_env(project_dir, load_env);
+
+    if let Some(hook) = &build.pre_rebuild_hook {
+        if let Err(e) = run_hook(hook, project_dir, &env) {
+            error!("Pre-rebuild hook failed: {e:#}");
+            return RebuildResult {
+                success: false,
+                output: format!("{e:#}"),
+                hook_failed: true,
+                cancelled: false,
+                duration: Duration::ZERO,
+            };
+        }
+    }
 
-    // Set up the build command with appropriate arguments
+    let offline = crate::utils::network::is_offline();
+    let mut command = rebuild_command(build.command.as_deref(), use_beta, offline);
     command
-        .arg("build")
-        .arg("--color=always")
-        .current_dir(project_dir);
+        .current_dir(project_dir)
+        .envs(env)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
 
     // Execute the build command
     debug!("Running build command: {:?}", command);
+    let started = Instant::now();
 
-    match command.status() {
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            error!("Failed to execute build command: {}", e);
+            return RebuildResult {
+                success: false,
+                output: format!("Failed to execute build command: {e}"),
+                hook_failed: false,
+                cancelled: false,
+                duration: started.elapsed(),
+            };
+        }
+    };
+
+    let captured = Arc::new(std::sync::Mutex::new(String::new()));
+    let stdout = child
+        .stdout
+        .take()
+        .map(|pipe| tee_output(pipe, std::io::stdout(), Arc::clone(&captured)));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|pipe| tee_output(pipe, std::io::stderr(), Arc::clone(&captured)));
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(None) if cancel() => {
+                info!("Cancelling the build for newer changes");
+                let _ = child.kill();
+                let _ = child.wait();
+                // Processes started by the build may still hold the pipes, so
+                // the output threads are left to finish on their own
+                return RebuildResult {
+                    success: false,
+                    output: String::new(),
+                    hook_failed: false,
+                    cancelled: true,
+                    duration: started.elapsed(),
+                };
+            }
+            Ok(None) => std::thread::sleep(BUILD_POLL_INTERVAL),
+            result => break result.map(|status| status.expect("the build has exited")),
+        }
+    };
+
+    for handle in [stdout, stderr].into_iter().flatten() {
+        let _ = handle.join();
+    }
+
+    let output = std::mem::take(&mut *captured.lock().unwrap());
+    match status {
         Ok(status) => {
             if status.success() {
                 info!("Project rebuilt successfully");
-                true
             } else {
                 error!("Project rebuild failed with status: {}", status);
-                false
+            }
+            RebuildResult {
+                success: status.success(),
+                output,
+                hook_failed: false,
+                cancelled: false,
+                duration: started.elapsed(),
             }
         }
         Err(e) => {
-            error!("Failed to execute build command: {}", e);
-            false
+            error!("Failed to wait for build command: {}", e);
+            RebuildResult {
+                success: false,
+                output,
+                hook_failed: false,
+                cancelled: false,
+                duration: started.elapsed(),
+            }
+        }
+    }
+}
+
+/// Warning for a rebuild slower than `threshold`, with hints for speeding it up
+fn slow_build_warning(
+    duration: Duration,
+    threshold: Option<Duration>,
+    average: Option<Duration>,
+) -> Option<String> {
+    let threshold = threshold.filter(|threshold| duration > *threshold)?;
+    let average = average
+        .map(|average| format!(", recent average {:.1}s", average.as_secs_f64()))
+        .unwrap_or_default();
+    Some(format!(
+        "Rebuild took {:.1}s (threshold {:.1}s{average}). Check that incremental compilation is enabled, or reduce the watch scope with hmr.ignore_patterns and hmr.watch_dirs. Set build.slow_build_warning_ms = 0 to silence this warning.",
+        duration.as_secs_f64(),
+        threshold.as_secs_f64()
+    ))
+}
+
+/// Run a hook command through the shell in `dir`
+///
+/// Fails when the command can't be started or exits unsuccessfully.
+fn run_hook(command: &str, dir: &Path, env: &[(String, String)]) -> Result<()> {
+    info!("Running hook: {command}");
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .current_dir(dir)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to run hook `{command}`: {e}"))?;
+
+    if status.success() {
+        debug!("Hook `{command}` finished with {status}");
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Hook `{command}` exited with {status}"))
+    }
+}
+
+/// Load `.env` variables for spawned commands, logging rather than failing
+///
+/// Shared by the build command and hooks so they see the same environment.
+fn hook_env(project_dir: &Path, load_env: bool) -> Vec<(String, String)> {
+    if !load_env {
+        return Vec::new();
+    }
+    crate::utils::env::load_dotenv(project_dir).unwrap_or_else(|e| {
+        error!("Ignoring .env: {e:#}");
+        Vec::new()
+    })
+}
+
+/// Create the rebuild command: `build.command` if configured, else `cargo build`
+///
+/// `+beta` and `--offline` are only added when the program is cargo.
+fn rebuild_command(
+    custom: Option<&[String]>,
+    use_beta: bool,
+    offline: bool,
+) -> std::process::Command {
+    let (program, args) = match custom {
+        Some([program, args @ ..]) => (program.as_str(), args.to_vec()),
+        _ => (
+            "cargo",
+            vec!["build".to_string(), "--color=always".to_string()],
+        ),
+    };
+
+    let mut command = std::process::Command::new(program);
+    if program == "cargo" {
+        if use_beta {
+            command.arg("+beta");
+        }
+        if offline {
+            command.arg("--offline");
+        }
+    }
+    command.args(args);
+    command
+}
+
+/// Copy a child's output to `terminal` line by line, keeping an uncolored copy
+fn tee_output<R, W>(
+    pipe: R,
+    mut terminal: W,
+    captured: Arc<std::sync::Mutex<String>>,
+) -> std::thread::JoinHandle<()>
+where
+    R: std::io::Read + Send + 'static,
+    W: std::io::Write + Send + 'static,
+{
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+
+        let mut reader = std::io::BufReader::new(pipe);
+        let mut line = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut line), Ok(n) if n > 0) {
+            let _ = terminal.write_all(&line).and_then(|_| terminal.flush());
+            let text = String::from_utf8_lossy(&line);
+            captured
+                .lock()
+                .unwrap()
+                .push_str(&console::strip_ansi_codes(&text));
+            line.clear();
+        }
+    })
+}
+
+/// URLs under which the dev server can be reached
+#[derive(Debug, PartialEq)]
+pub(crate) struct ServerUrls {
+    /// URL on this machine, when bound to a loopback or wildcard address
+    local: Option<String>,
+    /// URL for other devices, when bound to a non-loopback address
+    network: Option<String>,
+}
+
+impl ServerUrls {
+    pub(crate) fn resolve(host: &str, port: u16) -> Self {
+        Self::resolve_with(host, port, || local_ip_address::local_ip().ok())
+    }
+
+    fn resolve_with(
+        host: &str,
+        port: u16,
+        lan_ip: impl FnOnce() -> Option<std::net::IpAddr>,
+    ) -> Self {
+        let url = |host: &str| {
+            if host.contains(':') {
+                format!("http://[{host}]:{port}")
+            } else {
+                format!("http://{host}:{port}")
+            }
+        };
+        let local = Some(format!("http://localhost:{port}"));
+
+        match host.parse::<std::net::IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => Self {
+                local,
+                network: lan_ip().map(|ip| url(&ip.to_string())),
+            },
+            Ok(ip) if ip.is_loopback() => Self {
+                local,
+                network: None,
+            },
+            Ok(_) => Self {
+                local: None,
+                network: Some(url(host)),
+            },
+            Err(_) if host.eq_ignore_ascii_case("localhost") => Self {
+                local,
+                network: None,
+            },
+            Err(_) => Self {
+                local: None,
+                network: Some(url(host)),
+            },
+        }
+    }
+
+    /// URL to open in the browser, preferring the local one
+    pub(crate) fn browser_url(&self) -> &str {
+        self.local
+            .as_deref()
+            .or(self.network.as_deref())
+            .unwrap_or("http://localhost")
+    }
+
+    /// Print the Local and Network URLs, one per line
+    pub(crate) fn print(&self) {
+        if let Some(local) = &self.local {
+            println!(
+                "  {}   {}",
+                style("Local:").bold(),
+                style(local).bold().blue().underlined()
+            );
+        }
+        if let Some(network) = &self.network {
+            println!(
+                "  {} {}",
+                style("Network:").bold(),
+                style(network).bold().blue().underlined()
+            );
+        }
+    }
+}
+
+/// Append a path such as `/app` or `app` to a base URL
+pub(crate) fn join_url_path(base: &str, path: &str) -> String {
+    format!("{base}/{}", path.trim_start_matches('/'))
+}
+
+/// Quiet period after the last `.orbiton.toml` event before the config is reloaded
+const CONFIG_RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often a running build is checked for completion or cancellation
+const BUILD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Check whether a path is a project configuration file
+fn is_config_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == ".orbiton.toml")
+        || crate::config::config_override().is_some_and(|config| config == path)
+}
+
+/// Reload `.orbiton.toml` and apply the settings that can change while running
+fn reload_config(project_dir: &Path, server: &DevServer) -> Option<globset::GlobSet> {
+    let new_config = match OrbitonConfig::load_from_project(project_dir)
+        .and_then(|config| config.validate().map(|_| config))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            println!(
+                "{} Failed to reload configuration, keeping previous settings: {e}",
+                style("Error:").bold().red()
+            );
+            return None;
+        }
+    };
+
+    let ignore = match crate::utils::fs::glob_set(&new_config.hmr.ignore_patterns) {
+        Ok(ignore) => ignore,
+        Err(e) => {
+            println!(
+                "{} Invalid ignore patterns, keeping previous settings: {e}",
+                style("Error:").bold().red()
+            );
+            return None;
+        }
+    };
+
+    let old_config = server.config();
+    if old_config.dev_server.port != new_config.dev_server.port
+        || old_config.dev_server.host != new_config.dev_server.host
+    {
+        println!(
+            "{} dev_server.port/host changed; restart `orbiton dev` to apply",
+            style("Warning:").bold().yellow()
+        );
+    }
+
+    if let Err(e) = server.hmr_context().configure(&new_config.hmr) {
+        println!(
+            "{} Invalid full reload patterns, keeping previous settings: {e}",
+            style("Error:").bold().red()
+        );
+        return None;
+    }
+
+    server.update_config(new_config);
+    status!("{} configuration", style("Reloaded").bold().green());
+
+    Some(ignore)
+}
+
+/// File changes waiting to be rebuilt together
+///
+/// Every change restarts the debounce window, so a burst of saves results in
+/// a single rebuild once the files stop changing.
+#[derive(Debug, Default)]
+struct ChangeBatch {
+    last_change: Option<Instant>,
+}
+
+impl ChangeBatch {
+    fn record(&mut self, now: Instant) {
+        self.last_change = Some(now);
+    }
+
+    /// How long to wait for the next event: until the batch is due, or `idle` without changes
+    fn wait_time(&self, now: Instant, debounce: Duration, idle: Duration) -> Duration {
+        match self.last_change {
+            Some(changed_at) => debounce
+                .saturating_sub(now.saturating_duration_since(changed_at))
+                .min(idle),
+            None => idle,
+        }
+    }
+
+    /// Empty the batch if no change arrived within `debounce`, returning whether it was due
+    fn take_if_due(&mut self, now: Instant, debounce: Duration) -> bool {
+        let due = self
+            .last_change
+            .is_some_and(|changed_at| now.saturating_duration_since(changed_at) >= debounce);
+        if due {
+            self.last_change = None;
+        }
+        due
+    }
+}
+
+/// Rebuild the project and update clients with every change recorded since the last rebuild
+///
+/// Clients receive at most one HMR update or reload per call, covering all
+/// modules changed in the batch. When `newer_change` reports a change during
+/// the build, the build is cancelled without notifying clients; its modules
+/// stay pending for the next rebuild.
+fn rebuild_and_notify(
+    server: &DevServer,
+    project_dir: &Path,
+    load_env: bool,
+    stats: &SessionStats,
+    newer_change: &mut dyn FnMut() -> bool,
+) {
+    let hmr_context = server.hmr_context();
+
+    status!(
+        "{} project due to file changes",
+        style("Rebuilding").bold().yellow()
+    );
+
+    // Send rebuild start notification using dev server method
+    if let Err(e) = server.send_rebuild_status("started") {
+        error!("Failed to send rebuild start status: {e}");
+    }
+
+    // Perform the actual rebuild
+    let config = server.config();
+    let build_config = config.build;
+    let rebuild = rebuild_project(
+        project_dir,
+        &build_config,
+        server.is_using_beta(),
+        load_env,
+        newer_change,
+    );
+    let rebuild_status = rebuild.success;
+
+    // A newer change restarts the build; clients hear about that one instead
+    if rebuild.cancelled {
+        status!(
+            "{} rebuild, files changed while building",
+            style("Cancelled").bold().yellow()
+        );
+        return;
+    }
+
+    // A failed pre-rebuild hook aborts the build; show the error in the browser
+    if rebuild.hook_failed {
+        println!("{} {}", style("Error:").bold().red(), rebuild.output);
+        if let Err(e) = server.send_build_error(&rebuild.output) {
+            error!("Failed to send build error: {e}");
+        }
+        return;
+    }
+    stats.record_rebuild(rebuild.duration, rebuild_status);
+    hmr_context.record_rebuild_duration(rebuild.duration);
+    if let Some(average) = hmr_context.average_rebuild_duration() {
+        debug!("Average rebuild time: {average:.2?}");
+    }
+
+    // Report the rebuild status
+    let status = match rebuild_status {
+        true => "completed",
+        false => "failed",
+    };
+
+    let seconds = rebuild.duration.as_secs_f64();
+    if rebuild_status {
+        status!("{} in {seconds:.1}s", style("Rebuilt").bold().green());
+    } else {
+        println!(
+            "{} {} after {seconds:.1}s",
+            style("Rebuild").bold(),
+            style("failed").red()
+        );
+    }
+    if let Some(warning) = slow_build_warning(
+        rebuild.duration,
+        build_config.slow_build_threshold(),
+        hmr_context.average_rebuild_duration(),
+    ) {
+        println!("{} {warning}", style("Warning:").bold().yellow());
+    }
+
+    // Send the rebuild status, with the compiler output when it failed
+    let output = (!rebuild_status).then_some(rebuild.output.as_str());
+    let sent = server.send_rebuild_finished(status, rebuild.duration, output);
+    if let Err(e) = sent {
+        error!("Failed to send rebuild status: {e}");
+    }
+
+    // Run the post-rebuild hook before clients are updated
+    let mut hook_blocked = false;
+    if let (true, Some(hook)) = (rebuild_status, &build_config.post_rebuild_hook) {
+        if let Err(e) = run_hook(hook, project_dir, &hook_env(project_dir, load_env)) {
+            println!("{} {e:#}", style("Error:").bold().red());
+            hook_blocked = build_config.hook_failure_blocks_reload;
         }
     }
+
+    // If rebuild succeeded, record the rebuild and send HMR updates
+    if hook_blocked {
+        println!(
+            "{} Skipping browser update because the post-rebuild hook failed",
+            style("Warning:").bold().yellow()
+        );
+    } else if rebuild_status {
+        // Get affected modules before the rebuild marks them as updated
+        let affected_modules = hmr_context.get_pending_updates();
+
+        // Record successful rebuild
+        hmr_context.record_rebuild();
+
+        let needs_full_reload = affected_modules
+            .iter()
+            .any(|module| module == FULL_RELOAD_MODULE);
+
+        if !config.hmr.enabled || needs_full_reload {
+            // With HMR disabled or non-module files changed, fall back to a full page reload
+            if let Err(e) = server.send_reload_command() {
+                error!("Failed to send reload command: {e}");
+            }
+        } else if !affected_modules.is_empty() {
+            // Log the modules being updated
+            status!(
+                "{} HMR update for modules: {}",
+                style("Sending").bold().blue(),
+                style(affected_modules.join(", ")).italic()
+            );
+
+            // Send HMR update using dev server method
+            if let Err(e) = server.send_hmr_update(affected_modules) {
+                error!("Failed to send HMR update: {e}");
+            }
+        }
+    } else {
+        // On rebuild failure, send reload command to refresh the page
+        if let Err(e) = server.send_reload_command() {
+            error!("Failed to send reload command: {e}");
+        }
+    }
+}
+
+/// State of the watcher thread between file events
+struct WatchState {
+    /// Changes since the last rebuild, built once they stop arriving
+    batch: ChangeBatch,
+    /// Paths already reported since the last rebuild, so editors that emit
+    /// several events per save only log each file once
+    logged_paths: HashSet<PathBuf>,
+    /// Time of the most recent config file event awaiting a reload
+    pending_config_reload: Option<Instant>,
+    /// Paths whose changes are ignored, from `hmr.ignore_patterns`
+    ignore: globset::GlobSet,
 }
 
-fn setup_file_watching(project_dir: &Path, server: &DevServer) -> Result<()> {
+impl WatchState {
+    /// Run one iteration of the watcher loop with the event it received, if any
+    ///
+    /// The config is reloaded and the project rebuilt once their quiet periods
+    /// have passed, before `event` is recorded. Events arriving on `rx` during
+    /// the rebuild are recorded too, and cancel it when they change a module.
+    fn step(
+        &mut self,
+        event: Option<Event>,
+        rx: &std::sync::mpsc::Receiver<Event>,
+        server: &DevServer,
+        project_dir: &Path,
+        load_env: bool,
+        stats: &SessionStats,
+    ) {
+        // Reload the config once editor save storms have settled
+        if self
+            .pending_config_reload
+            .is_some_and(|changed_at| changed_at.elapsed() >= CONFIG_RELOAD_DEBOUNCE)
+        {
+            self.pending_config_reload = None;
+            if let Some(new_ignore) = reload_config(project_dir, server) {
+                self.ignore = new_ignore;
+            }
+        }
+
+        // Rebuild once for everything that changed during the debounce window
+        let debounce_time = Duration::from_millis(server.config().hmr.debounce_ms);
+        if self.batch.take_if_due(Instant::now(), debounce_time)
+            && server.hmr_context().needs_update()
+        {
+            self.logged_paths.clear();
+            // Events keep being handled during the build; a change cancels it
+            let mut newer_change = || {
+                let mut changed = false;
+                while let Ok(event) = rx.try_recv() {
+                    changed |= self.handle_event(&event, server, project_dir);
+                }
+                changed
+            };
+            rebuild_and_notify(server, project_dir, load_env, stats, &mut newer_change);
+        }
+
+        if let Some(event) = event {
+            self.handle_event(&event, server, project_dir);
+        }
+    }
+
+    /// Broadcast and record a file event, returning whether it changed a module
+    fn handle_event(&mut self, event: &Event, server: &DevServer, project_dir: &Path) -> bool {
+        debug!("File change event: {event:?}");
+
+        if event.paths.iter().any(|path| is_config_file(path)) {
+            self.pending_config_reload = Some(Instant::now());
+            return false;
+        }
+
+        // Skip events that only touch ignored paths such as target/
+        if event
+            .paths
+            .iter()
+            .all(|path| crate::utils::fs::is_ignored(&self.ignore, project_dir, path))
+        {
+            return false;
+        }
+
+        if event.paths.iter().any(|path| is_html_file(path)) {
+            server.invalidate_html_cache();
+        }
+
+        let paths = event
+            .paths
+            .iter()
+            .map(|p| {
+                p.strip_prefix(project_dir)
+                    .unwrap_or(p)
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<_>>();
+
+        // Send the file change event to all connected clients. Clients only
+        // log these, so they go out per event while updates are batched.
+        let message = DevMessage::FileChange {
+            paths,
+            kind: format!("{:?}", event.kind),
+        };
+
+        if let Err(e) = server.broadcast_update(message.to_json()) {
+            error!("Failed to broadcast file change: {e}");
+        }
+
+        // Track changed modules in HMR context for intelligent updates
+        let changes = record_event(server.hmr_context(), event);
+        for (path, module) in &changes {
+            self.batch.record(Instant::now());
+            if !self.logged_paths.insert(path.clone()) {
+                continue;
+            }
+
+            // Log which file triggered the update
+            let changed = if module == FULL_RELOAD_MODULE {
+                path.strip_prefix(project_dir)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .into_owned()
+            } else {
+                module.clone()
+            };
+            status!(
+                "{} {}",
+                style("File changed:").bold().blue(),
+                style(&changed).dim()
+            );
+        }
+        !changes.is_empty()
+    }
+}
+
+fn setup_file_watching(
+    project_dir: &Path,
+    server: &DevServer,
+    load_env: bool,
+    stats: Arc<SessionStats>,
+) -> Result<()> {
     let (tx, rx) = std::sync::mpsc::channel();
     let server = server.clone();
     let project_dir = project_dir.to_path_buf();
     let watcher_dir = project_dir.clone();
     let log_dir = project_dir.clone();
-    let hmr_context = Arc::clone(server.hmr_context());
+    let ignore = crate::utils::fs::glob_set(&server.config().hmr.ignore_patterns)?;
 
     // Create a watcher
     let mut watcher =
@@ -203,134 +966,349 @@ fn setup_file_watching(project_dir: &Path, server: &DevServer) -> Result<()> {
             }
         })?;
 
-    // Watch the project directory
-    watcher.watch(&watcher_dir, RecursiveMode::Recursive)?;
+    // Watch the project directory and any extra directories from hmr.watch_dirs
+    let (watch_dirs, missing_dirs) =
+        crate::utils::fs::resolve_watch_dirs(&watcher_dir, &server.config().hmr.watch_dirs);
+    for dir in missing_dirs {
+        println!(
+            "{} Watch directory does not exist, skipping: {dir:?}",
+            style("Warning:").bold().yellow()
+        );
+    }
+    for dir in &watch_dirs {
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+        debug!("Watching {dir:?}");
+    }
 
     // Keep track of the watcher to prevent it from being dropped
     std::thread::spawn(move || {
         let _watcher = watcher; // Keep watcher alive
         let pdir = project_dir.clone(); // Create a new binding for the project directory
+        let mut state = WatchState {
+            batch: ChangeBatch::default(),
+            logged_paths: HashSet::new(),
+            pending_config_reload: None,
+            ignore,
+        };
 
-        // Debounce mechanism to avoid multiple rebuilds in quick succession
-        let mut last_rebuild = std::time::Instant::now();
-        const DEBOUNCE_TIME: Duration = Duration::from_millis(500);
+        loop {
+            let debounce_time = Duration::from_millis(server.config().hmr.debounce_ms);
+            let timeout =
+                state
+                    .batch
+                    .wait_time(Instant::now(), debounce_time, CONFIG_RELOAD_DEBOUNCE);
+            let event = match rx.recv_timeout(timeout) {
+                Ok(event) => Some(event),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => None,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            };
 
-        for event in rx {
-            debug!("File change event: {event:?}");
+            state.step(event, &rx, &server, &pdir, load_env, &stats);
+        }
+    });
 
-            // Check if enough time has passed since last rebuild for additional debouncing
-            let now = std::time::Instant::now();
-            if now.duration_since(last_rebuild) < DEBOUNCE_TIME {
-                debug!("Skipping event due to debounce (last rebuild too recent)");
-                continue;
-            }
+    info!("File watching set up for {log_dir:?}");
+    Ok(())
+}
 
-            let paths = event
-                .paths
-                .iter()
-                .map(|p| {
-                    p.strip_prefix(&pdir)
-                        .unwrap_or(p)
-                        .to_string_lossy()
-                        .into_owned()
-                })
-                .collect::<Vec<_>>();
-
-            // Send the file change event to all connected clients
-            let message = serde_json::json!({
-                "type": "fileChange",
-                "paths": paths,
-                "kind": format!("{:?}", event.kind)
-            })
-            .to_string();
-
-            if let Err(e) = server.broadcast_update(message) {
-                error!("Failed to broadcast file change: {e}");
-            } // Track changed modules in HMR context for intelligent updates
-            let mut changed_modules = Vec::new();
-            for path in &event.paths {
-                if let Some(module) = hmr_context.record_file_change(path) {
-                    changed_modules.push(module.clone());
-
-                    // Log which file triggered the update
-                    println!(
-                        "{} {}",
-                        style("File changed:").bold().blue(),
-                        style(&module).dim()
-                    );
-                }
-            }
-            // Determine if we should rebuild using HMR context debouncing
-            let should_rebuild = hmr_context.should_rebuild(DEBOUNCE_TIME);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
 
-            if should_rebuild {
-                last_rebuild = now;
+    #[test]
+    #[cfg(unix)]
+    fn test_run_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = [("ORBITON_HOOK_TEST".to_string(), "from-env".to_string())];
 
-                println!(
-                    "{} project due to file changes",
-                    style("Rebuilding").bold().yellow()
-                );
+        run_hook("echo $ORBITON_HOOK_TEST > hook.txt", dir.path(), &env).unwrap();
+        let written = std::fs::read_to_string(dir.path().join("hook.txt")).unwrap();
+        assert_eq!(written.trim(), "from-env");
 
-                // Send rebuild start notification using dev server method
-                if let Err(e) = server.send_rebuild_status("started") {
-                    error!("Failed to send rebuild start status: {e}");
-                }
+        let err = run_hook("exit 3", dir.path(), &[]).unwrap_err();
+        assert!(err.to_string().contains("exit 3"));
+    }
 
-                // Perform the actual rebuild
-                let rebuild_status = rebuild_project(&pdir, server.is_using_beta());
+    #[test]
+    #[cfg(unix)]
+    fn test_failing_pre_rebuild_hook_skips_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let build = BuildConfig {
+            pre_rebuild_hook: Some("exit 1".to_string()),
+            command: Some(vec!["touch".to_string(), "built".to_string()]),
+            ..BuildConfig::default()
+        };
 
-                // Report the rebuild status
-                let status = match rebuild_status {
-                    true => "completed",
-                    false => "failed",
-                };
+        let rebuild = rebuild_project(dir.path(), &build, false, false, &mut || false);
+        assert!(rebuild.hook_failed);
+        assert!(!rebuild.success);
+        assert!(!dir.path().join("built").exists());
+    }
 
-                println!(
-                    "{} {}",
-                    style("Rebuild").bold(),
-                    if rebuild_status {
-                        style("completed successfully").green()
-                    } else {
-                        style("failed").red()
-                    }
-                );
+    #[test]
+    #[cfg(unix)]
+    fn test_newer_change_cancels_rebuild() {
+        let dir = tempfile::tempdir().unwrap();
+        let build = BuildConfig {
+            command: Some(vec!["sleep".to_string(), "10".to_string()]),
+            ..BuildConfig::default()
+        };
 
-                // Send the rebuild status using dev server method
-                if let Err(e) = server.send_rebuild_status(status) {
-                    error!("Failed to send rebuild status: {e}");
-                }
+        let mut polls = 0;
+        let rebuild = rebuild_project(dir.path(), &build, false, false, &mut || {
+            polls += 1;
+            polls == 3
+        });
+        assert!(rebuild.cancelled);
+        assert!(!rebuild.success);
+        assert!(rebuild.duration < Duration::from_secs(5));
+    }
 
-                // If rebuild succeeded, record the rebuild and send HMR updates
-                if rebuild_status {
-                    // Record successful rebuild
-                    hmr_context.record_rebuild();
-
-                    // Get affected modules from HMR context
-                    let affected_modules = hmr_context.get_pending_updates();
-
-                    if !affected_modules.is_empty() {
-                        // Log the modules being updated
-                        println!(
-                            "{} HMR update for modules: {}",
-                            style("Sending").bold().blue(),
-                            style(affected_modules.join(", ")).italic()
-                        );
-
-                        // Send HMR update using dev server method
-                        if let Err(e) = server.send_hmr_update(affected_modules) {
-                            error!("Failed to send HMR update: {e}");
-                        }
-                    }
-                } else {
-                    // On rebuild failure, send reload command to refresh the page
-                    if let Err(e) = server.send_reload_command() {
-                        error!("Failed to send reload command: {e}");
-                    }
+    #[test]
+    fn test_rebuild_command() {
+        let args = |command: &std::process::Command| -> Vec<String> {
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let command = rebuild_command(None, true, false);
+        assert_eq!(command.get_program(), "cargo");
+        assert_eq!(args(&command), ["+beta", "build", "--color=always"]);
+
+        let trunk = ["trunk".to_string(), "build".to_string()];
+        let command = rebuild_command(Some(&trunk), true, false);
+        assert_eq!(command.get_program(), "trunk");
+        assert_eq!(args(&command), ["build"]);
+
+        let cargo = ["cargo".to_string(), "xtask".to_string()];
+        assert_eq!(
+            args(&rebuild_command(Some(&cargo), true, false)),
+            ["+beta", "xtask"]
+        );
+        assert_eq!(
+            args(&rebuild_command(Some(&cargo), false, false)),
+            ["xtask"]
+        );
+
+        // Offline mode only reaches cargo
+        assert_eq!(
+            args(&rebuild_command(None, false, true)),
+            ["--offline", "build", "--color=always"]
+        );
+        assert_eq!(
+            args(&rebuild_command(Some(&cargo), true, true)),
+            ["+beta", "--offline", "xtask"]
+        );
+        assert_eq!(args(&rebuild_command(Some(&trunk), false, true)), ["build"]);
+    }
+
+    #[test]
+    fn test_slow_build_warning() {
+        let build = BuildConfig::default();
+        let threshold = build.slow_build_threshold();
+        assert_eq!(threshold, Some(Duration::from_secs(10)));
+
+        assert!(slow_build_warning(Duration::from_secs(3), threshold, None).is_none());
+        let warning = slow_build_warning(
+            Duration::from_millis(12_500),
+            threshold,
+            Some(Duration::from_secs(11)),
+        )
+        .unwrap();
+        assert!(warning.starts_with("Rebuild took 12.5s (threshold 10.0s, recent average 11.0s)"));
+
+        // A threshold of 0 disables the warning
+        let disabled = BuildConfig {
+            slow_build_warning_ms: Some(0),
+            ..BuildConfig::default()
+        };
+        assert_eq!(disabled.slow_build_threshold(), None);
+        assert!(slow_build_warning(Duration::from_secs(60), None, None).is_none());
+    }
+
+    #[test]
+    fn test_session_stats_average_rebuild() {
+        let stats = SessionStats::default();
+        assert_eq!(stats.average_rebuild(), None);
+
+        stats.record_rebuild(Duration::from_millis(100), true);
+        stats.record_rebuild(Duration::from_millis(300), false);
+        assert_eq!(stats.average_rebuild(), Some(Duration::from_millis(200)));
+        assert_eq!(stats.failed_rebuilds.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_record_event_coalesces_duplicate_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let hmr_context = HmrContext::new(dir.path().to_path_buf());
+
+        // Unreadable files always count as changed, so duplicates would repeat
+        let path = dir.path().join("src/app.rs");
+        let event = Event::new(EventKind::Modify(ModifyKind::Any))
+            .add_path(path.clone())
+            .add_path(path.clone());
+
+        assert_eq!(
+            record_event(&hmr_context, &event),
+            vec![(path, "app".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_tee_output_strips_colors_from_capture() {
+        let captured = Arc::new(std::sync::Mutex::new(String::new()));
+        let pipe =
+            std::io::Cursor::new(b"\x1b[1m\x1b[31merror\x1b[0m: oops\nwarning: unused".to_vec());
+        tee_output(pipe, std::io::sink(), Arc::clone(&captured))
+            .join()
+            .unwrap();
+
+        assert_eq!(*captured.lock().unwrap(), "error: oops\nwarning: unused");
+    }
+
+    #[test]
+    fn test_server_urls() {
+        let lan = || Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)));
+
+        let loopback = ServerUrls::resolve_with("127.0.0.1", 3000, lan);
+        assert_eq!(loopback.local.as_deref(), Some("http://localhost:3000"));
+        assert_eq!(loopback.network, None);
+
+        let wildcard = ServerUrls::resolve_with("0.0.0.0", 3000, lan);
+        assert_eq!(wildcard.local.as_deref(), Some("http://localhost:3000"));
+        assert_eq!(
+            wildcard.network.as_deref(),
+            Some("http://192.168.1.20:3000")
+        );
+
+        let specific = ServerUrls::resolve_with("10.0.0.5", 3000, lan);
+        assert_eq!(specific.local, None);
+        assert_eq!(specific.browser_url(), "http://10.0.0.5:3000");
+    }
+
+    #[test]
+    fn test_join_url_path() {
+        assert_eq!(
+            join_url_path("http://localhost:3000", "/"),
+            "http://localhost:3000/"
+        );
+        assert_eq!(
+            join_url_path("http://localhost:3000", "app"),
+            "http://localhost:3000/app"
+        );
+        assert_eq!(
+            join_url_path("http://localhost:3000", "/app?debug=1"),
+            "http://localhost:3000/app?debug=1"
+        );
+    }
+
+    #[test]
+    fn test_change_batch_waits_for_quiet_period() {
+        let debounce = Duration::from_millis(100);
+        let idle = CONFIG_RELOAD_DEBOUNCE;
+        let start = Instant::now();
+        let mut batch = ChangeBatch::default();
+        assert_eq!(batch.wait_time(start, debounce, idle), idle);
+        assert!(!batch.take_if_due(start + debounce, debounce));
+
+        // Each change restarts the window
+        batch.record(start);
+        batch.record(start + Duration::from_millis(60));
+        assert_eq!(
+            batch.wait_time(start + Duration::from_millis(80), debounce, idle),
+            Duration::from_millis(80)
+        );
+        assert!(!batch.take_if_due(start + debounce, debounce));
+        assert!(batch.take_if_due(start + Duration::from_millis(160), debounce));
+        assert!(!batch.take_if_due(start + Duration::from_millis(500), debounce));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_simultaneous_changes_send_one_hmr_update() {
+        use futures_util::StreamExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        let mut config = OrbitonConfig::default();
+        config.dev_server.host = "127.0.0.1".to_string();
+        config.dev_server.port = 0;
+        config.build.command = Some(vec!["true".to_string()]);
+        let mut server = DevServer::from_config(dir.path(), &config).unwrap();
+        let handle = server.start_detached().unwrap();
+
+        let url = format!("ws://127.0.0.1:{}", handle.port() + 1);
+        // The WebSocket server starts listening shortly after the HTTP server
+        let mut attempts = 0;
+        let (mut client, _) = loop {
+            match tokio_tungstenite::connect_async(&url).await {
+                Ok(connection) => break connection,
+                Err(_) if attempts < 50 => {
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
                 }
+                Err(e) => panic!("Failed to connect to {url}: {e}"),
             }
+        };
+        client.next().await.unwrap().unwrap(); // hello
+
+        // Three files saved within a few milliseconds of each other go
+        // through the watcher loop without triggering a rebuild
+        let mut state = WatchState {
+            batch: ChangeBatch::default(),
+            logged_paths: HashSet::new(),
+            pending_config_reload: None,
+            ignore: crate::utils::fs::glob_set(&config.hmr.ignore_patterns).unwrap(),
+        };
+        let (_tx, rx) = std::sync::mpsc::channel();
+        let stats = SessionStats::default();
+        for name in ["button", "card", "app"] {
+            let path = src.join(format!("{name}.rs"));
+            std::fs::write(&path, format!("// {name}")).unwrap();
+            let event = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(path);
+            state.step(Some(event), &rx, &server, dir.path(), false, &stats);
+            state.step(None, &rx, &server, dir.path(), false, &stats);
         }
-    });
+        assert_eq!(stats.rebuilds.load(Ordering::Relaxed), 0);
 
-    info!("File watching set up for {log_dir:?}");
-    Ok(())
+        // The loop wakes up once the debounce window has passed and rebuilds once
+        let debounce = Duration::from_millis(config.hmr.debounce_ms);
+        let timeout = state
+            .batch
+            .wait_time(Instant::now(), debounce, CONFIG_RELOAD_DEBOUNCE);
+        assert!(timeout > Duration::ZERO && timeout <= debounce);
+        tokio::time::sleep(timeout).await;
+        state.step(None, &rx, &server, dir.path(), false, &stats);
+        state.step(None, &rx, &server, dir.path(), false, &stats);
+        assert_eq!(stats.rebuilds.load(Ordering::Relaxed), 1);
+
+        let mut messages = Vec::new();
+        while let Ok(Some(message)) =
+            tokio::time::timeout(Duration::from_millis(300), client.next()).await
+        {
+            let text = message.unwrap().into_text().unwrap();
+            messages.push(serde_json::from_str::<DevMessage>(&text).unwrap());
+        }
+
+        let updates: Vec<&DevMessage> = messages
+            .iter()
+            .filter(|message| matches!(message, DevMessage::Hmr { .. }))
+            .collect();
+        assert_eq!(
+            updates,
+            [&DevMessage::Hmr {
+                modules: vec!["app".to_string(), "button".to_string(), "card".to_string()],
+                preserve_state: true,
+            }]
+        );
+        assert_eq!(server.hmr_update_count(), 1);
+        assert!(!server.hmr_context().needs_update());
+
+        handle.shutdown().unwrap();
+    }
 }
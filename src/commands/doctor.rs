@@ -0,0 +1,264 @@
+// Command for checking the development environment
+
+use anyhow::Result;
+use clap::Args;
+use console::style;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::OrbitonConfig;
+use crate::templates::project_templates::TemplateManager;
+
+/// Target required for web builds
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Project directory
+    #[arg(short, long)]
+    dir: Option<PathBuf>,
+}
+
+/// Outcome of a single environment check
+struct Check {
+    name: String,
+    passed: bool,
+    critical: bool,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: true,
+            critical: false,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn fail(name: &str, critical: bool, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: false,
+            critical,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+pub fn execute(args: DoctorArgs) -> Result<()> {
+    let project_dir = match args.dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+
+    println!(
+        "{} development environment",
+        style("Checking").bold().green()
+    );
+
+    let checks = run_checks(&project_dir);
+    for check in &checks {
+        let marker = if check.passed {
+            style("✓").green()
+        } else if check.critical {
+            style("✗").red()
+        } else {
+            style("!").yellow()
+        };
+        println!("  {marker} {}: {}", style(&check.name).bold(), check.detail);
+        if let Some(fix) = &check.fix {
+            println!("      {} {fix}", style("Fix:").dim());
+        }
+    }
+
+    let critical_failures = checks
+        .iter()
+        .filter(|check| !check.passed && check.critical)
+        .count();
+    let warnings = checks
+        .iter()
+        .filter(|check| !check.passed && !check.critical)
+        .count();
+
+    if critical_failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{critical_failures} critical check(s) failed"
+        ));
+    }
+
+    if warnings > 0 {
+        println!(
+            "{} {warnings} optional check(s) failed; some features may be unavailable",
+            style("Warning:").bold().yellow()
+        );
+    } else {
+        println!("{} All checks passed", style("Success!").bold().green());
+    }
+
+    Ok(())
+}
+
+/// Run every environment check in display order
+fn run_checks(project_dir: &Path) -> Vec<Check> {
+    let config = OrbitonConfig::find_config_file(project_dir).map(|_| {
+        OrbitonConfig::load_from_project(project_dir).and_then(|c| c.validate().map(|_| c))
+    });
+    let uses_beta = matches!(&config, Some(Ok(c)) if c.build.use_beta_toolchain);
+
+    let mut checks = vec![
+        tool_check(
+            "rustc",
+            "rustc",
+            true,
+            "Install Rust from https://rustup.rs",
+        ),
+        tool_check(
+            "cargo",
+            "cargo",
+            true,
+            "Install Rust from https://rustup.rs",
+        ),
+        wasm_target_check(),
+        tool_check(
+            "wasm-bindgen",
+            "wasm-bindgen",
+            false,
+            "cargo install wasm-bindgen-cli",
+        ),
+        tool_check("wasm-pack", "wasm-pack", false, "cargo install wasm-pack"),
+        beta_toolchain_check(uses_beta),
+    ];
+
+    checks.push(match TemplateManager::new() {
+        Ok(manager) if manager.is_embedded() => {
+            Check::pass("templates", "using the templates built into orbiton")
+        }
+        Ok(_) => Check::pass("templates", "project templates found"),
+        Err(_) => Check::fail(
+            "templates",
+            true,
+            "templates directory not found",
+            "Reinstall orbiton, or set ORBITON_TEMPLATE_DIR to a directory containing the templates",
+        ),
+    });
+
+    checks.push(if crate::utils::is_orbit_project(project_dir) {
+        Check::pass("project", "Orbit project")
+    } else {
+        Check::fail(
+            "project",
+            false,
+            "no .orbiton.toml, orbit.config.json or orbit dependency found",
+            "Run orbiton inside an Orbit project, or create one with `orbiton new`",
+        )
+    });
+
+    checks.push(match config {
+        None => Check::pass(".orbiton.toml", "not present, using defaults"),
+        Some(Ok(_)) => Check::pass(".orbiton.toml", "valid"),
+        Some(Err(e)) => Check::fail(
+            ".orbiton.toml",
+            true,
+            e.to_string(),
+            "Run `orbiton config validate` and correct the reported settings",
+        ),
+    });
+
+    checks
+}
+
+/// Check that a tool is on PATH and report its version
+fn tool_check(name: &str, program: &str, critical: bool, fix: &str) -> Check {
+    match Command::new(program).arg("--version").output() {
+        Ok(output) if output.status.success() => Check::pass(
+            name,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => Check::fail(name, critical, "not found", fix),
+    }
+}
+
+/// Check that the WebAssembly target is installed
+fn wasm_target_check() -> Check {
+    let name = "wasm32 target";
+    match Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            let installed = String::from_utf8_lossy(&output.stdout);
+            if installed.lines().any(|line| line.trim() == WASM_TARGET) {
+                Check::pass(name, format!("{WASM_TARGET} installed"))
+            } else {
+                Check::fail(
+                    name,
+                    false,
+                    format!("{WASM_TARGET} not installed"),
+                    format!("rustup target add {WASM_TARGET}"),
+                )
+            }
+        }
+        _ => Check::fail(
+            name,
+            false,
+            "rustup not available",
+            "Install rustup from https://rustup.rs to manage targets",
+        ),
+    }
+}
+
+/// Check for the beta toolchain, which is only required when enabled in the config
+fn beta_toolchain_check(required: bool) -> Check {
+    let name = "beta toolchain";
+    let installed = Command::new("rustup")
+        .args(["toolchain", "list"])
+        .output()
+        .is_ok_and(|output| {
+            crate::utils::toolchain::is_installed(&String::from_utf8_lossy(&output.stdout), "beta")
+        });
+
+    if installed {
+        Check::pass(name, "installed")
+    } else {
+        Check::fail(
+            name,
+            required,
+            if required {
+                "not installed but build.use_beta_toolchain is enabled"
+            } else {
+                "not installed (only needed for build.use_beta_toolchain)"
+            },
+            "rustup toolchain install beta",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_invalid_config_is_critical() -> Result<()> {
+        let temp_dir = tempdir()?;
+        std::fs::write(
+            temp_dir.path().join(".orbiton.toml"),
+            "[dev_server]\nport = 0\n",
+        )?;
+
+        let checks = run_checks(temp_dir.path());
+        let config_check = checks
+            .iter()
+            .find(|check| check.name == ".orbiton.toml")
+            .expect("config check should run");
+        assert!(!config_check.passed);
+        assert!(config_check.critical);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+// Command for formatting the sections of .orbit components
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config::OrbitonConfig;
+use crate::status;
+use crate::templates::project_templates::{ComponentFormat, ComponentSection, TemplateManager};
+
+#[derive(Args)]
+pub struct FmtArgs {
+    /// Project directory
+    #[arg(short, long)]
+    dir: Option<PathBuf>,
+
+    /// Report files that would change instead of rewriting them
+    #[arg(long)]
+    check: bool,
+
+    /// Only format the Rust sections, leaving templates and styles untouched
+    #[arg(long)]
+    rust_only: bool,
+}
+
+/// How component sections are formatted
+#[derive(Debug, Clone, Copy, Default)]
+struct FmtOptions {
+    rust_only: bool,
+}
+
+pub fn execute(args: FmtArgs) -> Result<()> {
+    let project_dir = match args.dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    let config = OrbitonConfig::load_from_project(&project_dir)?;
+    let exclude = crate::utils::fs::glob_set(&config.build.exclude)?;
+    let files = super::build::find_orbit_files(&project_dir, &exclude)?;
+    let options = FmtOptions {
+        rust_only: args.rust_only,
+    };
+
+    let mut changed = 0;
+    let mut failed = 0;
+    for path in &files {
+        let relative = path.strip_prefix(&project_dir).unwrap_or(path);
+        let formatted = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {path:?}"))
+            .and_then(|content| {
+                let formatted = format_component(path, &content, &project_dir, options)?;
+                Ok((formatted != content).then_some(formatted))
+            });
+
+        match formatted {
+            Ok(None) => {}
+            Ok(Some(_)) if args.check => {
+                changed += 1;
+                println!("Would reformat {}", relative.display());
+            }
+            Ok(Some(formatted)) => {
+                changed += 1;
+                std::fs::write(path, formatted)
+                    .with_context(|| format!("Failed to write {path:?}"))?;
+                status!("Formatted {}", relative.display());
+            }
+            Err(err) => {
+                failed += 1;
+                eprintln!(
+                    "{} {}: {err:#}",
+                    style("Error:").bold().red(),
+                    relative.display()
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!("Failed to format {failed} file(s)"));
+    }
+    if args.check && changed > 0 {
+        return Err(anyhow::anyhow!(
+            "{changed} file(s) would be reformatted; run `orbiton fmt` to fix them"
+        ));
+    }
+
+    if args.check {
+        println!("All {} component files are formatted", files.len());
+    } else {
+        println!(
+            "{} Formatted {changed} of {} component files",
+            style("Success!").bold().green(),
+            files.len()
+        );
+    }
+    Ok(())
+}
+
+/// Format every section of a component, keeping its format, section order and
+/// line endings
+///
+/// `project_dir` is where `rustfmt` runs, so the project's `rustfmt.toml`
+/// applies. Files with content outside their sections are refused, since it
+/// would be lost when the file is rewritten.
+fn format_component(
+    path: &Path,
+    content: &str,
+    project_dir: &Path,
+    options: FmtOptions,
+) -> Result<String> {
+    let line_ending = line_ending(content);
+    let content = &content.replace("\r\n", "\n");
+    let format = ComponentFormat::detect(path, content);
+    let sections = TemplateManager::parse_component_sections(content, format.clone())?;
+    ensure_lossless(content, &sections, &format)?;
+
+    let formatted = sections
+        .into_iter()
+        .map(|section| {
+            let content = match section.lang.as_str() {
+                "rust" => rustfmt(&section.content, project_dir)?,
+                "css" if !options.rust_only => normalize_css(&section.content),
+                "html" if !options.rust_only => normalize_markup(&section.content),
+                _ => section.content,
+            };
+            Ok(ComponentSection { content, ..section })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let rendered = TemplateManager::render_component_sections(&formatted, &format);
+    Ok(rendered.replace('\n', line_ending))
+}
+
+/// Line ending of `content`, so that rewritten components keep it
+pub(super) fn line_ending(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Fail if writing `sections` back out would lose content of the original file
+///
+/// Only whitespace outside the sections may change.
+pub(super) fn ensure_lossless(
+    content: &str,
+    sections: &[ComponentSection],
+    format: &ComponentFormat,
+) -> Result<()> {
+    let without_whitespace =
+        |text: &str| -> String { text.chars().filter(|c| !c.is_whitespace()).collect() };
+    let rendered = TemplateManager::render_component_sections(sections, format);
+    if without_whitespace(&rendered) != without_whitespace(content) {
+        return Err(anyhow::anyhow!(
+            "has content outside its sections that would be lost, leaving it unchanged"
+        ));
+    }
+    Ok(())
+}
+
+/// Format Rust code by piping it through `rustfmt`
+fn rustfmt(code: &str, project_dir: &Path) -> Result<String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .current_dir(project_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => {
+                anyhow::anyhow!("rustfmt not found; install it with `rustup component add rustfmt`")
+            }
+            _ => anyhow::Error::new(err).context("Failed to run rustfmt"),
+        })?;
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(code.as_bytes())
+        .context("Failed to send code to rustfmt")?;
+    let output = child
+        .wait_with_output()
+        .context("Failed to wait for rustfmt")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "rustfmt failed on the Rust section:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    String::from_utf8(output.stdout).context("rustfmt produced invalid UTF-8")
+}
+
+/// Trim trailing whitespace and collapse runs of blank lines
+fn normalize_markup(content: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in content.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Normalize whitespace and indent declarations two spaces per nesting level
+fn normalize_css(content: &str) -> String {
+    let mut depth = 0usize;
+    normalize_markup(content)
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let opens = line.matches('{').count();
+            let mut closes = line.matches('}').count();
+            if line.starts_with('}') {
+                depth = depth.saturating_sub(1);
+                closes -= 1;
+            }
+            let indented = if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("{}{line}\n", "  ".repeat(depth))
+            };
+            depth = (depth + opens).saturating_sub(closes);
+            indented
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const FORMATTED: &str = "<template>
+  <div class=\"card\">
+    <slot />
+  </div>
+</template>
+
+<style>
+.card {
+  display: block;
+}
+</style>
+
+<script>
+use orbit::prelude::*;
+
+pub struct Card;
+</script>
+";
+
+    #[test]
+    fn test_format_legacy_component() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let messy = "<template>\n  <div class=\"card\">   \n    <slot />\n  </div>\n</template>\n\
+            <style>\n.card {\n        display: block;\n}\n\n</style>\n\n\n\
+            <script>\nuse orbit::prelude::*;\n\npub   struct Card ;\n</script>";
+
+        let path = Path::new("card.orbit");
+        let formatted = format_component(path, messy, temp_dir.path(), FmtOptions::default())?;
+        assert_eq!(formatted, FORMATTED);
+
+        // Formatting is stable
+        let again = format_component(path, &formatted, temp_dir.path(), FmtOptions::default())?;
+        assert_eq!(again, FORMATTED);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_keeps_modern_and_markdown_structure() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let modern = FORMATTED
+            .replace("<script>", "<code lang=\"rust\">")
+            .replace("</script>", "</code>");
+        let formatted = format_component(
+            Path::new("card.orbit"),
+            &modern,
+            temp_dir.path(),
+            FmtOptions::default(),
+        )?;
+        assert_eq!(formatted, modern);
+
+        let markdown = "# Card\n\nUsage:\n\n```\n<Card />\n```\n\n\
+            ```rust\nfn  main( ) {}\n```\n\nMore notes.\n";
+        let formatted = format_component(
+            Path::new("card.orbit.md"),
+            markdown,
+            temp_dir.path(),
+            FmtOptions::default(),
+        )?;
+        assert_eq!(
+            formatted,
+            markdown.replace("fn  main( ) {}", "fn main() {}")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_keeps_crlf_line_endings() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let crlf = FORMATTED.replace('\n', "\r\n");
+        let formatted = format_component(
+            Path::new("card.orbit"),
+            &crlf,
+            temp_dir.path(),
+            FmtOptions::default(),
+        )?;
+        assert_eq!(formatted, crlf);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_refuses_content_outside_sections() {
+        let content = format!("<!-- shared card -->\n{FORMATTED}");
+        let result = format_component(
+            Path::new("card.orbit"),
+            &content,
+            Path::new("."),
+            FmtOptions::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rust_only_leaves_markup_alone() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let content = FORMATTED.replace("  display: block;", "display: block;   ");
+        let options = FmtOptions { rust_only: true };
+        let formatted =
+            format_component(Path::new("card.orbit"), &content, temp_dir.path(), options)?;
+        assert_eq!(formatted, content);
+        Ok(())
+    }
+}
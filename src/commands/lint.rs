@@ -0,0 +1,811 @@
+// Command for checking .orbit components for common mistakes
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::{LintConfig, LintSeverity, OrbitonConfig};
+use crate::status;
+use crate::templates::project_templates::{ComponentFormat, ComponentSection, TemplateManager};
+
+/// Check run over the sections of a component, each paired with its first line
+type Check = fn(&[(usize, ComponentSection)], LintSeverity, &mut Vec<Finding>);
+
+/// Rewrite a section to resolve a rule's findings, returning how many were fixed
+type Fix = fn(&mut ComponentSection) -> usize;
+
+/// A built-in rule, configured by name in `lint.rules`
+struct Rule {
+    name: &'static str,
+    default: LintSeverity,
+    check: Check,
+    /// Set for rules whose findings `--fix` resolves
+    fix: Option<Fix>,
+}
+
+const RULES: &[Rule] = &[
+    // Components have a template and a code section
+    Rule {
+        name: "required-sections",
+        default: LintSeverity::Error,
+        check: check_required_sections,
+        fix: None,
+    },
+    // Props are declared only once
+    Rule {
+        name: "duplicate-props",
+        default: LintSeverity::Error,
+        check: check_duplicate_props,
+        fix: None,
+    },
+    // Classes styled by a component are used in its template
+    Rule {
+        name: "unused-style-classes",
+        default: LintSeverity::Warn,
+        check: check_unused_style_classes,
+        fix: None,
+    },
+    // Section lines don't end in whitespace
+    Rule {
+        name: "trailing-whitespace",
+        default: LintSeverity::Warn,
+        check: check_trailing_whitespace,
+        fix: Some(fix_trailing_whitespace),
+    },
+    // The struct implementing `Component` is public
+    Rule {
+        name: "public-component",
+        default: LintSeverity::Warn,
+        check: check_public_component,
+        fix: Some(fix_public_component),
+    },
+    // Template attribute values use double quotes
+    Rule {
+        name: "quote-style",
+        default: LintSeverity::Warn,
+        check: check_quote_style,
+        fix: Some(fix_quote_style),
+    },
+];
+
+impl Rule {
+    /// Severity of the rule under `config`
+    fn severity(&self, config: &LintConfig) -> LintSeverity {
+        config
+            .rules
+            .get(self.name)
+            .map_or(self.default, |setting| setting.severity(self.default))
+    }
+
+    fn is_fixable(name: &str) -> bool {
+        RULES
+            .iter()
+            .any(|rule| rule.name == name && rule.fix.is_some())
+    }
+}
+
+#[derive(Args)]
+pub struct LintArgs {
+    /// Project directory
+    #[arg(short, long)]
+    dir: Option<PathBuf>,
+
+    /// Rewrite components to resolve findings of auto-fixable rules
+    #[arg(long)]
+    fix: bool,
+}
+
+/// A problem found in a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    rule: &'static str,
+    severity: LintSeverity,
+    /// 1-based line in the component file
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.line, self.message, self.rule)
+    }
+}
+
+pub fn execute(args: LintArgs) -> Result<()> {
+    let project_dir = match args.dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    let config = OrbitonConfig::load_from_project(&project_dir)?;
+
+    if !config.lint.enabled {
+        println!(
+            "{} Linting is disabled (lint.enabled = false)",
+            style("Info:").bold().blue()
+        );
+        return Ok(());
+    }
+    for rule in config.lint.rules.keys() {
+        if !RULES.iter().any(|known| known.name == rule) {
+            eprintln!(
+                "{} Unknown lint rule '{rule}' in lint.rules; available rules: {}",
+                style("Warning:").bold().yellow(),
+                RULES
+                    .iter()
+                    .map(|rule| rule.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+    if !config.lint.custom_rules.is_empty() {
+        eprintln!(
+            "{} lint.custom_rules is not supported yet and was ignored",
+            style("Warning:").bold().yellow()
+        );
+    }
+
+    let exclude = crate::utils::fs::glob_set(&config.build.exclude)?;
+    let files = super::build::find_orbit_files(&project_dir, &exclude)?;
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut fixable = 0;
+    let mut fixes = 0;
+    let mut fixed_files = 0;
+    for path in &files {
+        let relative = path.strip_prefix(&project_dir).unwrap_or(path);
+        let mut content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {path:?}"))?;
+
+        if args.fix {
+            match fix_component(path, &content, &config.lint) {
+                Ok(None) => {}
+                Ok(Some((fixed, count))) => {
+                    std::fs::write(path, &fixed)
+                        .with_context(|| format!("Failed to write {path:?}"))?;
+                    status!("Fixed {count} finding(s) in {}", relative.display());
+                    fixes += count;
+                    fixed_files += 1;
+                    content = fixed;
+                }
+                Err(err) => eprintln!(
+                    "{} {}: {err:#}",
+                    style("Warning:").bold().yellow(),
+                    relative.display()
+                ),
+            }
+        }
+
+        for finding in lint_component(path, &content, &config.lint)? {
+            if Rule::is_fixable(finding.rule) {
+                fixable += 1;
+            }
+            let label = match finding.severity {
+                LintSeverity::Error => {
+                    errors += 1;
+                    style("Error:").bold().red()
+                }
+                LintSeverity::Warn | LintSeverity::Off => {
+                    warnings += 1;
+                    style("Warning:").bold().yellow()
+                }
+            };
+            println!("{label} {}:{finding}", relative.display());
+        }
+    }
+
+    if args.fix && fixes > 0 {
+        println!(
+            "{} Applied {fixes} fix(es) to {fixed_files} component files",
+            style("Success!").bold().green()
+        );
+    } else if fixable > 0 {
+        println!(
+            "{} {fixable} finding(s) can be fixed with `orbiton lint --fix`",
+            style("Info:").bold().blue()
+        );
+    }
+
+    if errors > 0 {
+        return Err(anyhow::anyhow!(
+            "Found {errors} error(s) and {warnings} warning(s) in {} component files",
+            files.len()
+        ));
+    }
+    if warnings > 0 {
+        println!(
+            "Found {warnings} warning(s) in {} component files",
+            files.len()
+        );
+    } else {
+        println!(
+            "{} No problems found in {} component files",
+            style("Success!").bold().green(),
+            files.len()
+        );
+    }
+    Ok(())
+}
+
+/// Run the rules enabled in `config` over a component, ordered by line
+fn lint_component(path: &Path, content: &str, config: &LintConfig) -> Result<Vec<Finding>> {
+    // Sections are rebuilt line by line without `\r`, so CRLF files are
+    // normalized for their content to be found again when counting lines
+    let content = &content.replace("\r\n", "\n");
+    let format = ComponentFormat::detect(path, content);
+    let sections = TemplateManager::parse_component_sections(content, format)?;
+    let sections: Vec<(usize, ComponentSection)> = section_lines(content, &sections)
+        .into_iter()
+        .zip(sections)
+        .collect();
+    let mut findings = Vec::new();
+    for rule in RULES {
+        let severity = rule.severity(config);
+        if severity != LintSeverity::Off {
+            (rule.check)(&sections, severity, &mut findings);
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.line);
+    Ok(findings)
+}
+
+/// Apply the fixes of enabled auto-fixable rules to a component
+///
+/// Returns the rewritten file and the number of fixes, or `None` when nothing
+/// needed fixing. The file is rewritten from its sections, so its format and
+/// line endings are kept.
+fn fix_component(
+    path: &Path,
+    content: &str,
+    config: &LintConfig,
+) -> Result<Option<(String, usize)>> {
+    let line_ending = super::fmt::line_ending(content);
+    let content = &content.replace("\r\n", "\n");
+    let format = ComponentFormat::detect(path, content);
+    let mut sections = TemplateManager::parse_component_sections(content, format.clone())?;
+
+    let mut count = 0;
+    for rule in RULES {
+        let Some(fix) = rule.fix else {
+            continue;
+        };
+        if rule.severity(config) != LintSeverity::Off {
+            count += sections.iter_mut().map(fix).sum::<usize>();
+        }
+    }
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let original = TemplateManager::parse_component_sections(content, format.clone())?;
+    super::fmt::ensure_lossless(content, &original, &format)?;
+    let fixed = TemplateManager::render_component_sections(&sections, &format);
+    Ok(Some((fixed.replace('\n', line_ending), count)))
+}
+
+/// Line on which each section's content starts
+fn section_lines(content: &str, sections: &[ComponentSection]) -> Vec<usize> {
+    let mut offset = 0;
+    sections
+        .iter()
+        .map(|section| {
+            let Some(start) = content[offset..].find(&section.content) else {
+                return content[..offset].matches('\n').count() + 1;
+            };
+            offset += start;
+            let line = content[..offset].matches('\n').count() + 1;
+            offset += section.content.len();
+            line
+        })
+        .collect()
+}
+
+fn check_required_sections(
+    sections: &[(usize, ComponentSection)],
+    severity: LintSeverity,
+    findings: &mut Vec<Finding>,
+) {
+    for (name, description) in [("template", "template"), ("code", "Rust code")] {
+        if !sections.iter().any(|(_, section)| section.name == name) {
+            findings.push(Finding {
+                rule: "required-sections",
+                severity,
+                line: 1,
+                message: format!("component has no {description} section"),
+            });
+        }
+    }
+}
+
+/// Report fields declared twice in a `...Props` struct
+fn check_duplicate_props(
+    sections: &[(usize, ComponentSection)],
+    severity: LintSeverity,
+    findings: &mut Vec<Finding>,
+) {
+    for (start, section) in sections.iter().filter(|(_, s)| s.name == "code") {
+        let mut props: Option<HashMap<&str, usize>> = None;
+        for (index, line) in section.content.lines().enumerate() {
+            let line_number = start + index;
+            let trimmed = line.trim();
+
+            let Some(declared) = props.as_mut() else {
+                let is_props_struct = trimmed.ends_with('{')
+                    && trimmed
+                        .split_whitespace()
+                        .skip_while(|word| *word != "struct")
+                        .nth(1)
+                        .is_some_and(|name| name.ends_with("Props"));
+                if is_props_struct {
+                    props = Some(HashMap::new());
+                }
+                continue;
+            };
+
+            if trimmed.starts_with('}') {
+                props = None;
+                continue;
+            }
+            let field = trimmed
+                .trim_start_matches("pub(crate) ")
+                .trim_start_matches("pub ")
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .trim();
+            if !is_identifier(field) || !trimmed.contains(':') {
+                continue;
+            }
+            if let Some(first) = declared.get(field) {
+                findings.push(Finding {
+                    rule: "duplicate-props",
+                    severity,
+                    line: line_number,
+                    message: format!(
+                        "prop '{field}' is declared more than once (first on line {first})"
+                    ),
+                });
+            } else {
+                declared.insert(field, line_number);
+            }
+        }
+    }
+}
+
+/// Report class selectors that never appear in the component's template
+fn check_unused_style_classes(
+    sections: &[(usize, ComponentSection)],
+    severity: LintSeverity,
+    findings: &mut Vec<Finding>,
+) {
+    let template: String = sections
+        .iter()
+        .filter(|(_, section)| section.name == "template")
+        .map(|(_, section)| section.content.as_str())
+        .collect();
+
+    let mut reported = Vec::new();
+    for (start, section) in sections.iter().filter(|(_, s)| s.name == "style") {
+        for (offset, class) in style_classes(&section.content) {
+            if reported.contains(&class) || contains_word(&template, class) {
+                continue;
+            }
+            reported.push(class);
+            findings.push(Finding {
+                rule: "unused-style-classes",
+                severity,
+                line: start + offset,
+                message: format!("class '.{class}' is styled but not used in the template"),
+            });
+        }
+    }
+}
+
+/// Class names in the selectors of a stylesheet, with their line offset
+fn style_classes(css: &str) -> Vec<(usize, &str)> {
+    let mut classes = Vec::new();
+    let mut depth = 0usize;
+    let mut in_comment = false;
+    for (line_offset, line) in css.lines().enumerate() {
+        let mut chars = line.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            if in_comment {
+                if c == '*' && chars.peek().is_some_and(|(_, next)| *next == '/') {
+                    chars.next();
+                    in_comment = false;
+                }
+                continue;
+            }
+            match c {
+                '/' if chars.peek().is_some_and(|(_, next)| *next == '*') => in_comment = true,
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                // Selectors sit outside declaration blocks, or inside `@media` blocks
+                '.' if !in_declarations(line, depth) => {
+                    let rest = &line[index + 1..];
+                    let end = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                        .unwrap_or(rest.len());
+                    let class = &rest[..end];
+                    if class.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '-')
+                    {
+                        classes.push((line_offset, class));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    classes
+}
+
+/// Whether a line at this nesting depth holds declarations (e.g. `margin: 0.5em`)
+///
+/// Inside a block only lines opening a nested block, as in `@media`, hold selectors.
+fn in_declarations(line: &str, depth: usize) -> bool {
+    depth > 0 && !line.contains('{')
+}
+
+/// Whether `word` appears in `text` delimited by characters that can't be part of a class name
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    text.match_indices(word).any(|(index, _)| {
+        let before = text[..index].chars().next_back();
+        let after = text[index + word.len()..].chars().next();
+        !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+    })
+}
+
+fn check_trailing_whitespace(
+    sections: &[(usize, ComponentSection)],
+    severity: LintSeverity,
+    findings: &mut Vec<Finding>,
+) {
+    // Trailing spaces are hard line breaks in Markdown prose
+    for (start, section) in sections.iter().filter(|(_, s)| s.name != "markdown") {
+        for (offset, line) in section.content.lines().enumerate() {
+            if line.len() != line.trim_end().len() {
+                findings.push(Finding {
+                    rule: "trailing-whitespace",
+                    severity,
+                    line: start + offset,
+                    message: "trailing whitespace".to_string(),
+                });
+            }
+        }
+    }
+}
+
+fn fix_trailing_whitespace(section: &mut ComponentSection) -> usize {
+    if section.name == "markdown" {
+        return 0;
+    }
+    let mut count = 0;
+    section.content = section
+        .content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_end();
+            if trimmed.len() != line.len() {
+                count += 1;
+            }
+            format!("{trimmed}\n")
+        })
+        .collect();
+    count
+}
+
+fn check_public_component(
+    sections: &[(usize, ComponentSection)],
+    severity: LintSeverity,
+    findings: &mut Vec<Finding>,
+) {
+    for (start, section) in sections.iter().filter(|(_, s)| s.name == "code") {
+        for (offset, name) in private_component_structs(&section.content) {
+            findings.push(Finding {
+                rule: "public-component",
+                severity,
+                line: start + offset,
+                message: format!("component struct '{name}' is not public"),
+            });
+        }
+    }
+}
+
+fn fix_public_component(section: &mut ComponentSection) -> usize {
+    if section.name != "code" {
+        return 0;
+    }
+    let private: Vec<usize> = private_component_structs(&section.content)
+        .into_iter()
+        .map(|(offset, _)| offset)
+        .collect();
+    let lines: Vec<String> = section
+        .content
+        .lines()
+        .enumerate()
+        .map(|(offset, line)| {
+            if private.contains(&offset) {
+                let code = line.trim_start();
+                let indent = &line[..line.len() - code.len()];
+                format!("{indent}pub {code}\n")
+            } else {
+                format!("{line}\n")
+            }
+        })
+        .collect();
+    section.content = lines.concat();
+    private.len()
+}
+
+/// Lines declaring a struct that implements `Component` without `pub`, with the struct name
+fn private_component_structs(code: &str) -> Vec<(usize, &str)> {
+    let components: Vec<&str> = code
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("impl Component for "))
+        .filter_map(|rest| {
+            rest.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+        })
+        .collect();
+
+    code.lines()
+        .enumerate()
+        .filter_map(|(offset, line)| {
+            let name = line.trim().strip_prefix("struct ")?;
+            let end = name
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(name.len());
+            let name = &name[..end];
+            components.contains(&name).then_some((offset, name))
+        })
+        .collect()
+}
+
+fn check_quote_style(
+    sections: &[(usize, ComponentSection)],
+    severity: LintSeverity,
+    findings: &mut Vec<Finding>,
+) {
+    for (start, section) in sections.iter().filter(|(_, s)| s.name == "template") {
+        for (open, _) in single_quoted_values(&section.content) {
+            findings.push(Finding {
+                rule: "quote-style",
+                severity,
+                line: start + section.content[..open].matches('\n').count(),
+                message: "attribute value uses single quotes".to_string(),
+            });
+        }
+    }
+}
+
+fn fix_quote_style(section: &mut ComponentSection) -> usize {
+    if section.name != "template" {
+        return 0;
+    }
+    let values = single_quoted_values(&section.content);
+    let mut content = section.content.clone().into_bytes();
+    for (open, close) in &values {
+        content[*open] = b'"';
+        content[*close] = b'"';
+    }
+    section.content = String::from_utf8(content).expect("quotes are replaced by quotes");
+    values.len()
+}
+
+/// Byte offsets of the quotes around single-quoted attribute values in a template
+///
+/// Values containing a double quote are skipped, since they can't be requoted.
+fn single_quoted_values(template: &str) -> Vec<(usize, usize)> {
+    let bytes = template.as_bytes();
+    let mut values = Vec::new();
+    let mut in_tag = false;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'<' if bytes.get(index + 1).is_some_and(u8::is_ascii_alphabetic) => in_tag = true,
+            b'>' => in_tag = false,
+            b'=' if in_tag => {
+                let open = index
+                    + 1
+                    + bytes[index + 1..]
+                        .iter()
+                        .take_while(|b| **b == b' ')
+                        .count();
+                if bytes.get(open) == Some(&b'\'') {
+                    let Some(length) = bytes[open + 1..].iter().position(|b| *b == b'\'') else {
+                        break;
+                    };
+                    let close = open + 1 + length;
+                    if !bytes[open + 1..close].contains(&b'"') {
+                        values.push((open, close));
+                    }
+                    index = close;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    values
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuleSetting;
+
+    const CARD: &str = r#"<template>
+  <div class="card card-active">
+    <slot />
+  </div>
+</template>
+
+<style>
+.card {
+  padding: 0.5rem;
+}
+
+.card-active, .card-hidden {
+  display: block;
+}
+</style>
+
+<script>
+#[derive(Props)]
+pub struct CardProps {
+    pub title: String,
+    pub subtitle: String,
+    pub title: String,
+}
+</script>
+"#;
+
+    fn lint(path: &str, content: &str, config: &LintConfig) -> Vec<(&'static str, usize)> {
+        lint_component(Path::new(path), content, config)
+            .unwrap()
+            .into_iter()
+            .map(|finding| (finding.rule, finding.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_reports_findings_with_lines() {
+        let findings =
+            lint_component(Path::new("card.orbit"), CARD, &LintConfig::default()).unwrap();
+        assert_eq!(
+            findings,
+            [
+                Finding {
+                    rule: "unused-style-classes",
+                    severity: LintSeverity::Warn,
+                    line: 12,
+                    message: "class '.card-hidden' is styled but not used in the template"
+                        .to_string(),
+                },
+                Finding {
+                    rule: "duplicate-props",
+                    severity: LintSeverity::Error,
+                    line: 22,
+                    message: "prop 'title' is declared more than once (first on line 20)"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_crlf_component() {
+        let content = CARD
+            .replace("<slot />", "<p>ééééééééééééééééééé</p>")
+            .replace('\n', "\r\n");
+        assert_eq!(
+            lint("card.orbit", &content, &LintConfig::default()),
+            [("unused-style-classes", 12), ("duplicate-props", 22)]
+        );
+    }
+
+    #[test]
+    fn test_lint_missing_sections() {
+        let config = LintConfig::default();
+        assert_eq!(
+            lint("empty.orbit", "<template>\n<div />\n</template>\n", &config),
+            [("required-sections", 1)]
+        );
+
+        let markdown = "# Button\n\n```html\n<button />\n```\n\n```rust\npub struct Button;\n```\n";
+        assert!(lint("button.orbit.md", markdown, &config).is_empty());
+    }
+
+    #[test]
+    fn test_lint_rules_can_be_disabled() {
+        let mut config = LintConfig::default();
+        config
+            .rules
+            .insert("duplicate-props".to_string(), RuleSetting::Enabled(false));
+        config.rules.insert(
+            "unused-style-classes".to_string(),
+            RuleSetting::Severity(LintSeverity::Off),
+        );
+        assert!(lint("card.orbit", CARD, &config).is_empty());
+    }
+
+    #[test]
+    fn test_lint_severity_comes_from_config() {
+        let mut config = LintConfig::default();
+        config.rules.insert(
+            "duplicate-props".to_string(),
+            RuleSetting::Severity(LintSeverity::Warn),
+        );
+        config.rules.insert(
+            "unused-style-classes".to_string(),
+            RuleSetting::Enabled(true),
+        );
+
+        let severities: Vec<_> = lint_component(Path::new("card.orbit"), CARD, &config)
+            .unwrap()
+            .into_iter()
+            .map(|finding| (finding.rule, finding.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            [
+                ("unused-style-classes", LintSeverity::Warn),
+                ("duplicate-props", LintSeverity::Warn),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix_rewrites_fixable_findings() {
+        let content =
+            "<template>\n  <div class='card' title=\"a 'b'\">  \n  </div>\n</template>\n\n\
+            <code lang=\"rust\">\nstruct Card;\t\nconst LABEL: &str = \"class='x'\";\n\n\
+            impl Component for Card {}\n</code>\n";
+        let config = LintConfig::default();
+        let path = Path::new("card.orbit");
+
+        let mut fixable = lint(path.to_str().unwrap(), content, &config);
+        fixable.retain(|(rule, _)| Rule::is_fixable(rule));
+        assert_eq!(
+            fixable,
+            [
+                ("trailing-whitespace", 2),
+                ("quote-style", 2),
+                ("trailing-whitespace", 7),
+                ("public-component", 7),
+            ]
+        );
+
+        let (fixed, count) = fix_component(path, content, &config).unwrap().unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(
+            fixed,
+            "<template>\n  <div class=\"card\" title=\"a 'b'\">\n  </div>\n</template>\n\n\
+             <code lang=\"rust\">\npub struct Card;\nconst LABEL: &str = \"class='x'\";\n\n\
+             impl Component for Card {}\n</code>\n"
+        );
+        assert!(lint("card.orbit", &fixed, &config).is_empty());
+        assert!(fix_component(path, &fixed, &config).unwrap().is_none());
+
+        let crlf = content.replace('\n', "\r\n");
+        let (fixed_crlf, _) = fix_component(path, &crlf, &config).unwrap().unwrap();
+        assert_eq!(fixed_crlf, fixed.replace('\n', "\r\n"));
+    }
+
+    #[test]
+    fn test_style_classes_skip_declarations_and_comments() {
+        let css = "/* .old */\n@media (min-width: 10.5em) {\n  .wide:hover {\n    margin: 0.5em;\n  }\n}\n";
+        assert_eq!(style_classes(css), [(2, "wide")]);
+    }
+}
@@ -6,6 +6,7 @@ use std::env;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::dev_maintenance::DevMaintenance;
 use crate::maintenance::MaintenanceManager;
 
 #[derive(Parser)]
@@ -30,6 +31,19 @@ enum MaintenanceAction {
     Clear,
     /// Show maintenance status
     Status,
+    /// Show detailed HMR statistics
+    Stats {
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+impl MaintenanceArgs {
+    /// Whether the command's stdout is meant to be consumed by scripts
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(self.action, MaintenanceAction::Stats { json: true })
+    }
 }
 
 pub fn execute(args: MaintenanceArgs) -> anyhow::Result<()> {
@@ -55,6 +69,14 @@ pub fn execute(args: MaintenanceArgs) -> anyhow::Result<()> {
         MaintenanceAction::Status => {
             manager.show_status();
         }
+        MaintenanceAction::Stats { json } => {
+            let stats = DevMaintenance::new(project_dir).get_hmr_stats();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                stats.print_stats();
+            }
+        }
     }
 
     Ok(())
@@ -1,8 +1,16 @@
 // Module for commands
+pub mod add;
 pub mod build;
+pub mod completions;
 pub mod config;
 pub mod dev;
+pub mod doctor;
+pub mod fmt;
+pub mod lint;
 pub mod maintenance;
 pub mod new;
+pub mod preview;
 pub mod renderer;
+pub mod templates;
 pub mod test;
+pub mod upgrade;
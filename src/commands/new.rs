@@ -6,9 +6,13 @@ use console::style;
 use dialoguer::{theme::ColorfulTheme, Select};
 use log::debug;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-use crate::templates::project_templates::{TemplateManager, TemplateType};
+use crate::status;
+use crate::templates::project_templates::{
+    GenerationContext, TemplateManager, TemplateType, DEFAULT_GITIGNORE,
+};
 
 #[derive(Args)]
 pub struct NewArgs {
@@ -23,21 +27,45 @@ pub struct NewArgs {
     /// Output directory
     #[arg(short, long)]
     output_dir: Option<PathBuf>,
+
+    /// Initialize a git repository with a .gitignore in the new project
+    #[arg(long)]
+    git: bool,
+
+    /// Also make an initial commit (implies --git)
+    #[arg(long)]
+    git_commit: bool,
+
+    /// Include benchmark scaffolding, if the template provides it
+    #[arg(long)]
+    with_benches: bool,
+
+    /// Enable an optional template feature (can be repeated)
+    #[arg(long = "with", value_name = "FEATURE")]
+    features: Vec<String>,
+
+    /// Directory containing the project templates (default: $ORBITON_TEMPLATE_DIR or auto-detected)
+    #[arg(long, value_name = "DIR")]
+    template_dir: Option<PathBuf>,
 }
 
 pub fn execute(args: NewArgs) -> Result<()> {
-    println!(
+    status!(
         "{} a new Orbit project: {}",
         style("Creating").bold().green(),
         style(&args.name).bold()
     );
 
-    let template_manager =
-        TemplateManager::new().context("Failed to initialize template manager")?;
+    let template_manager = match &args.template_dir {
+        Some(dir) => TemplateManager::with_dir(dir),
+        None => TemplateManager::new(),
+    }
+    .context("Failed to initialize template manager")?;
 
     // Determine the template to use
     let template_type = if let Some(template) = args.template {
-        TemplateType::from_str(&template)
+        template
+            .parse::<TemplateType>()
             .with_context(|| format!("Invalid template type: {template}"))?
     } else {
         // Prompt the user to select a template
@@ -74,20 +102,119 @@ pub fn execute(args: NewArgs) -> Result<()> {
             .with_context(|| format!("Failed to create directory: {output_dir:?}"))?;
     }
 
+    let mut features = args.features;
+    if args.with_benches {
+        features.push("benches".to_string());
+    }
+    let context = GenerationContext::with_features(features);
+
     // Generate the project from the template
-    template_manager
-        .generate_project(&args.name, template_type, &output_dir)
+    let file_count = template_manager
+        .generate_project(&args.name, template_type, &output_dir, &context)
         .with_context(|| format!("Failed to generate project in {output_dir:?}"))?;
 
-    println!(
-        "\n{} project created at {output_dir:?}",
+    status!(
+        "\n{} project created at {output_dir:?} ({file_count} files)",
         style("Successfully").bold().green()
     );
 
+    if args.git || args.git_commit {
+        init_git_repo(&output_dir, args.git_commit)?;
+    }
+
     // Print next steps
-    println!("\n{}", style("Next steps:").bold());
-    println!("  cd {}", args.name);
-    println!("  orbiton dev");
+    status!("\n{}", style("Next steps:").bold());
+    status!("  cd {}", args.name);
+    status!("  orbiton dev");
+
+    Ok(())
+}
+
+/// Initialize a git repository in a new project, optionally with an initial commit
+///
+/// A missing `git` or a failing git command is reported as a warning, since
+/// the project itself was created successfully.
+fn init_git_repo(dir: &Path, commit: bool) -> Result<()> {
+    let git = |args: &[&str]| Command::new("git").args(args).current_dir(dir).output();
+
+    let inside_repo = match git(&["rev-parse", "--is-inside-work-tree"]) {
+        Ok(output) => output.status.success(),
+        Err(_) => {
+            println!(
+                "{} git not found, skipping repository setup",
+                style("Warning:").bold().yellow()
+            );
+            return Ok(());
+        }
+    };
+    if inside_repo {
+        status!(
+            "{} {dir:?} is already inside a git repository, skipping git init",
+            style("Info:").bold().blue()
+        );
+        return Ok(());
+    }
+
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(&gitignore, DEFAULT_GITIGNORE)
+            .with_context(|| format!("Failed to write {gitignore:?}"))?;
+    }
 
+    let mut steps = vec![vec!["init", "--quiet"]];
+    if commit {
+        steps.push(vec!["add", "--all"]);
+        steps.push(vec!["commit", "--quiet", "-m", "Initial commit"]);
+    }
+    for step in steps {
+        let output = git(&step).with_context(|| format!("Failed to run git {}", step[0]))?;
+        if !output.status.success() {
+            println!(
+                "{} git {} failed: {}",
+                style("Warning:").bold().yellow(),
+                step[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(());
+        }
+    }
+
+    status!(
+        "{} git repository{}",
+        style("Initialized").bold().green(),
+        if commit {
+            " with an initial commit"
+        } else {
+            ""
+        }
+    );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_git_repo() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("my-app");
+        fs::create_dir_all(&project).unwrap();
+
+        init_git_repo(&project, false).unwrap();
+        assert!(project.join(".git").is_dir());
+        let gitignore = fs::read_to_string(project.join(".gitignore")).unwrap();
+        assert!(gitignore.contains("/target/"));
+
+        // Projects created inside an existing repository are left alone
+        let nested = project.join("packages/ui");
+        fs::create_dir_all(&nested).unwrap();
+        init_git_repo(&nested, false).unwrap();
+        assert!(!nested.join(".git").exists());
+        assert!(!nested.join(".gitignore").exists());
+    }
+}
@@ -0,0 +1,156 @@
+// Command for previewing a production build
+
+use anyhow::Result;
+use clap::Args;
+use console::style;
+use log::error;
+use std::path::{Path, PathBuf};
+
+use super::dev::{join_url_path, ServerUrls};
+use crate::config::OrbitonConfig;
+use crate::dev_server::DevServer;
+use crate::status;
+
+#[derive(Args)]
+pub struct PreviewArgs {
+    /// Port to serve the build on
+    #[arg(short, long, default_value = "8000")]
+    port: u16,
+
+    /// Host address to bind to (e.g. 0.0.0.0 to allow LAN access)
+    #[arg(long)]
+    host: Option<String>,
+
+    /// Project directory
+    #[arg(short, long)]
+    dir: Option<PathBuf>,
+
+    /// Target platform whose build output is served
+    #[arg(short, long, default_value = "web")]
+    target: String,
+
+    /// Directory to serve instead of the build output
+    #[arg(long)]
+    root: Option<PathBuf>,
+
+    /// Open in browser
+    #[arg(short, long)]
+    open: bool,
+
+    /// Path to open in the browser (e.g. /app)
+    #[arg(long)]
+    open_path: Option<String>,
+}
+
+pub fn execute(args: PreviewArgs) -> Result<()> {
+    // Determine the project directory
+    let project_dir = match args.dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+
+    // Load configuration from .orbiton.toml or use defaults
+    let mut config = OrbitonConfig::load_from_project(&project_dir)?;
+
+    // Override config with command line arguments
+    if args.port != 8000 {
+        config.dev_server.port = args.port;
+    }
+    if let Some(host) = args.host {
+        config.dev_server.host = host;
+    }
+    if let Some(open_path) = args.open_path {
+        config.dev_server.open_path = open_path;
+    }
+
+    // Validate the configuration
+    config.validate()?;
+
+    let root = match args.root {
+        Some(root) => root,
+        None => find_build_output(&project_dir, &args.target, &config)?,
+    };
+    if !root.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Directory to serve does not exist: {root:?}"
+        ));
+    }
+
+    status!(
+        "{} production build at {root:?}",
+        style("Previewing").bold().green()
+    );
+
+    let mut server = DevServer::preview(&root, &config)?;
+    let _server_handle = server.start()?;
+
+    let urls = ServerUrls::resolve(&config.dev_server.host, config.dev_server.port);
+    println!("Preview server running at:");
+    urls.print();
+
+    // Open the browser if requested (use config or CLI args)
+    if args.open || config.dev_server.auto_open {
+        let url = join_url_path(urls.browser_url(), &config.dev_server.open_path);
+        if let Err(e) = open::that(url) {
+            error!("Failed to open browser: {e}");
+        }
+    }
+
+    // Wait for Ctrl+C
+    status!("Press {} to stop the server", style("Ctrl+C").bold());
+    ctrlc::set_handler(move || {
+        status!("\n{} preview server", style("Stopping").bold().red());
+        std::process::exit(0);
+    })?;
+
+    // Serve until Ctrl+C; the server only stops on its own when it fails
+    server.wait()?;
+    Err(anyhow::anyhow!("The preview server stopped unexpectedly"))
+}
+
+/// Locate the output of `orbiton build`, falling back to `project.dist_dir`
+fn find_build_output(project_dir: &Path, target: &str, config: &OrbitonConfig) -> Result<PathBuf> {
+    let candidates = [
+        project_dir.join("build").join(target),
+        project_dir.join(&config.project.dist_dir),
+    ];
+
+    candidates
+        .iter()
+        .find(|dir| dir.is_dir())
+        .cloned()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No build output found in {:?} or {:?}. Run `orbiton build` first.",
+                candidates[0],
+                candidates[1]
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_build_output() {
+        let dir = tempdir().unwrap();
+        let config = OrbitonConfig::default();
+
+        assert!(find_build_output(dir.path(), "web", &config).is_err());
+
+        std::fs::create_dir_all(dir.path().join("dist")).unwrap();
+        assert_eq!(
+            find_build_output(dir.path(), "web", &config).unwrap(),
+            dir.path().join("dist")
+        );
+
+        // The build output for the target wins over dist_dir
+        std::fs::create_dir_all(dir.path().join("build/web")).unwrap();
+        assert_eq!(
+            find_build_output(dir.path(), "web", &config).unwrap(),
+            dir.path().join("build/web")
+        );
+    }
+}
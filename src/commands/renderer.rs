@@ -3,7 +3,10 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::config::{OrbitonConfig, Renderer};
+use crate::status;
 
 #[derive(Args)]
 pub struct RendererArgs {
@@ -14,6 +17,10 @@ pub struct RendererArgs {
     /// Project directory
     #[arg(short, long)]
     dir: Option<PathBuf>,
+
+    /// Configure the renderer even if the build target doesn't support it
+    #[arg(long)]
+    force: bool,
 }
 
 pub fn execute(args: RendererArgs) -> Result<()> {
@@ -23,44 +30,81 @@ pub fn execute(args: RendererArgs) -> Result<()> {
         None => std::env::current_dir()?,
     };
 
-    println!(
+    status!(
         "{} renderer to {}",
         style("Configuring").bold().green(),
         style(&args.config).bold()
     );
 
     // Validate the renderer configuration
-    let renderer_type = match args.config.to_lowercase().as_str() {
-        "skia" => "skia",
-        "wgpu" => "wgpu",
-        "auto" => "auto",
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Invalid renderer configuration: {}. Valid options are: skia, wgpu, auto",
-                args.config
-            ));
+    let renderer: Renderer = args.config.parse()?;
+
+    // Catch renderers the configured build target can't use before a build does
+    let effective = OrbitonConfig::load_from_project(&project_dir)?;
+    if let Some(triple) = effective.build.target.as_deref() {
+        if let Err(message) = check_target_support(renderer, triple) {
+            if !args.force {
+                return Err(anyhow::anyhow!(
+                    "{message}. Pass --force to configure it anyway"
+                ));
+            }
+            println!("{} {message}", style("Warning:").bold().yellow());
         }
-    };
+    }
 
-    // Update the project configuration file
-    let config_file = project_dir.join("orbit.config.json");
+    // Store the renderer in .orbiton.toml alongside the rest of the configuration
+    let (mut config, config_path) = OrbitonConfig::load_for_update(&project_dir)?;
+    config.renderer.backend = renderer;
+    config.save_to_file(&config_path)?;
+
+    // Keep the legacy JSON file in sync for tools that still read it
+    let legacy_config_file = project_dir.join("orbit.config.json");
+    if legacy_config_file.exists() {
+        update_legacy_config(&legacy_config_file, renderer)?;
+    }
+
+    status!(
+        "Renderer configured to {} in {config_path:?}",
+        style(renderer).bold()
+    );
 
-    // If the config file exists, read it; otherwise, create a new one
-    let mut config = if config_file.exists() {
-        let config_str = std::fs::read_to_string(&config_file)
-            .with_context(|| format!("Failed to read config file: {config_file:?}"))?;
+    Ok(())
+}
 
-        serde_json::from_str(&config_str)
-            .with_context(|| format!("Failed to parse config file: {config_file:?}"))?
+/// Check that `renderer` can run on the platform of the target triple `triple`
+fn check_target_support(renderer: Renderer, triple: &str) -> Result<(), String> {
+    let (platform, supported): (&str, &[Renderer]) = if triple.starts_with("wasm32") {
+        ("web", &[Renderer::Wgpu, Renderer::Auto])
+    } else if triple.split('-').any(|part| part == "none") {
+        // Bare-metal targets have no GPU API, only software rasterization
+        ("embedded", &[Renderer::Skia, Renderer::Auto])
     } else {
-        serde_json::json!({})
+        return Ok(());
     };
 
+    if supported.contains(&renderer) {
+        return Ok(());
+    }
+    let options: Vec<String> = supported.iter().map(Renderer::to_string).collect();
+    Err(format!(
+        "The {renderer} renderer is not supported on {platform} target {triple} (supported: {})",
+        options.join(", ")
+    ))
+}
+
+/// Update the renderer in the legacy `orbit.config.json` file
+fn update_legacy_config(config_file: &Path, renderer: Renderer) -> Result<()> {
+    let config_str = std::fs::read_to_string(config_file)
+        .with_context(|| format!("Failed to read config file: {config_file:?}"))?;
+
+    let mut config: serde_json::Value = serde_json::from_str(&config_str)
+        .with_context(|| format!("Failed to parse config file: {config_file:?}"))?;
+
     // Update the renderer configuration
     if let Some(config_obj) = config.as_object_mut() {
         config_obj.insert(
             "renderer".to_string(),
-            serde_json::Value::String(renderer_type.to_string()),
+            serde_json::Value::String(renderer.to_string()),
         );
     }
 
@@ -68,13 +112,87 @@ pub fn execute(args: RendererArgs) -> Result<()> {
     let config_str =
         serde_json::to_string_pretty(&config).with_context(|| "Failed to serialize config")?;
 
-    std::fs::write(&config_file, config_str)
+    std::fs::write(config_file, config_str)
         .with_context(|| format!("Failed to write config file: {config_file:?}"))?;
 
-    println!(
-        "Renderer configured to {} in {config_file:?}",
-        style(renderer_type).bold()
-    );
-
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configure(dir: &Path, renderer: &str) -> Result<()> {
+        execute(RendererArgs {
+            config: renderer.to_string(),
+            dir: Some(dir.to_path_buf()),
+            force: false,
+        })
+    }
+
+    #[test]
+    fn test_renderer_persists_to_orbiton_toml() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join(".orbiton.toml"),
+            "[dev_server]\nport = 4000\n",
+        )?;
+
+        configure(dir.path(), "wgpu")?;
+
+        let config = OrbitonConfig::load_from_file(&dir.path().join(".orbiton.toml"))?;
+        assert_eq!(config.renderer.backend, Renderer::Wgpu);
+        assert_eq!(config.dev_server.port, 4000);
+        assert!(!dir.path().join("orbit.config.json").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_renderer_updates_existing_legacy_json() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let legacy = dir.path().join("orbit.config.json");
+        std::fs::write(&legacy, r#"{"renderer": "auto", "name": "my-app"}"#)?;
+
+        configure(dir.path(), "skia")?;
+
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&legacy)?)?;
+        assert_eq!(json["renderer"], "skia");
+        assert_eq!(json["name"], "my-app");
+        assert!(dir.path().join(".orbiton.toml").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_target_support() {
+        assert!(check_target_support(Renderer::Skia, "x86_64-unknown-linux-gnu").is_ok());
+        assert!(check_target_support(Renderer::Wgpu, "wasm32-unknown-unknown").is_ok());
+        assert!(check_target_support(Renderer::Skia, "wasm32-unknown-unknown").is_err());
+        assert!(check_target_support(Renderer::Skia, "thumbv7em-none-eabihf").is_ok());
+        assert!(check_target_support(Renderer::Wgpu, "riscv32imac-unknown-none-elf").is_err());
+        assert!(check_target_support(Renderer::Auto, "thumbv7em-none-eabihf").is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_renderer_needs_force() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join(".orbiton.toml");
+        std::fs::write(
+            &config_path,
+            "[build]\ntarget = \"wasm32-unknown-unknown\"\n",
+        )?;
+
+        let err = configure(dir.path(), "skia").unwrap_err();
+        assert!(err.to_string().contains("--force"), "{err}");
+        let config = OrbitonConfig::load_from_file(&config_path)?;
+        assert_eq!(config.renderer.backend, Renderer::Auto);
+
+        execute(RendererArgs {
+            config: "skia".to_string(),
+            dir: Some(dir.path().to_path_buf()),
+            force: true,
+        })?;
+        let config = OrbitonConfig::load_from_file(&config_path)?;
+        assert_eq!(config.renderer.backend, Renderer::Skia);
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+// Commands for working with project templates
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use console::style;
+use std::path::PathBuf;
+
+use crate::templates::validation::{self, TEMPLATE_MANIFEST};
+
+#[derive(Args)]
+pub struct TemplatesArgs {
+    #[command(subcommand)]
+    command: TemplatesCommand,
+}
+
+#[derive(Subcommand)]
+enum TemplatesCommand {
+    /// Check a template's template.json for problems
+    Validate {
+        /// Template directory containing template.json
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+pub fn execute(args: TemplatesArgs) -> Result<()> {
+    match args.command {
+        TemplatesCommand::Validate { dir } => validate(dir),
+    }
+}
+
+fn validate(dir: PathBuf) -> Result<()> {
+    let problems = validation::validate_template(&dir)?;
+    let manifest = dir.join(TEMPLATE_MANIFEST);
+
+    if problems.is_empty() {
+        println!(
+            "{} {} is valid",
+            style("Success!").bold().green(),
+            manifest.display()
+        );
+        return Ok(());
+    }
+
+    for problem in &problems {
+        eprintln!("{} {problem}", style("Error:").bold().red());
+    }
+    Err(anyhow::anyhow!(
+        "Found {} problem(s) in {}",
+        problems.len(),
+        manifest.display()
+    ))
+}
@@ -1,8 +1,20 @@
 //! Implementation of the `orbiton test` command.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use std::path::PathBuf;
+use console::style;
+use log::error;
+use notify::{Event, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::config::OrbitonConfig;
+use crate::status;
+
+/// File the coverage run writes lcov data to, relative to the project directory
+const LCOV_OUTPUT: &str = "lcov.info";
 
 /// Command line arguments for the `test` command.
 #[derive(Parser)]
@@ -19,10 +31,18 @@ pub struct TestCommand {
     #[arg(long)]
     pub integration: bool,
 
-    /// Run performance tests to measure rendering speed and memory usage
+    /// Run the project's benchmarks with `cargo bench` and summarize their timings
     #[arg(long)]
     pub performance: bool,
 
+    /// Build and run the tests with optimizations
+    #[arg(long)]
+    pub release: bool,
+
+    /// Space or comma separated list of features to activate, forwarded to cargo
+    #[arg(long, value_name = "FEATURES")]
+    pub features: Option<String>,
+
     /// Generate test coverage information
     #[arg(long)]
     pub coverage: bool,
@@ -31,7 +51,7 @@ pub struct TestCommand {
     #[arg(long)]
     pub report: bool,
 
-    /// Update test snapshots instead of failing on mismatch
+    /// Rewrite snapshots in tests/snapshots instead of failing on mismatch
     #[arg(long = "update-snapshots")]
     pub update_snapshots: bool,
 
@@ -42,100 +62,180 @@ pub struct TestCommand {
     /// Custom path to the project directory
     #[arg(long = "dir", short = 'd')]
     pub project_dir: Option<PathBuf>,
+
+    /// Don't pass variables from the project's .env file to cargo
+    #[arg(long)]
+    pub no_env: bool,
+
+    /// Output format: `json` prints a summary of the run for CI instead of cargo's output
+    #[arg(long, value_enum, default_value_t = TestFormat::Human, conflicts_with_all = ["watch", "performance"])]
+    pub format: TestFormat,
+
+    /// Extra arguments passed to the test binary (e.g. `-- my_test --nocapture`)
+    #[arg(last = true)]
+    pub cargo_args: Vec<String>,
+}
+
+/// How `orbiton test` reports results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum TestFormat {
+    /// Cargo's own output with progress messages
+    #[default]
+    Human,
+    /// A JSON summary of the run on stdout
+    Json,
+}
+
+/// Results of a test run, printed by `--format json`
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct TestSummary {
+    passed: u64,
+    failed: u64,
+    ignored: u64,
+    /// Time spent running tests, summed over all test binaries
+    duration_secs: f64,
+    /// Names of failing tests
+    failures: Vec<String>,
 }
 
 impl TestCommand {
+    /// Whether the command's stdout must stay parseable
+    pub fn is_machine_readable(&self) -> bool {
+        self.format == TestFormat::Json
+    }
+
     /// Execute the test command.
     pub fn execute(&self) -> Result<()> {
-        use console::style;
-        use std::process::Command;
-
         // Get the project directory (current directory if not specified)
         let project_dir = self
             .project_dir
             .clone()
             .unwrap_or_else(|| std::env::current_dir().unwrap());
 
-        println!(
+        if self.format == TestFormat::Json {
+            return self.run_json_summary(&project_dir);
+        }
+
+        status!(
             "{} Looking for tests in {}",
             style("[1/4]").bold().dim(),
             style(project_dir.display()).underlined()
         );
 
-        // Check if this is an Orbit project by looking for specific files
-        let is_orbit_project = std::fs::metadata(project_dir.join("orbit.config.toml")).is_ok()
-            || std::fs::metadata(project_dir.join("Cargo.toml")).is_ok();
-
-        if !is_orbit_project {
+        if !crate::utils::is_orbit_project(&project_dir) {
             println!(
                 "⚠️  {} This directory does not appear to be an Orbit project.",
                 style("Warning:").yellow().bold()
             );
-            println!("   Looking for orbit.config.toml or Cargo.toml...");
+            println!(
+                "   No .orbiton.toml, orbit.config.json or orbit dependency in Cargo.toml was found."
+            );
         }
 
         // Since this is a planned future feature, print a message but also try to run standard Rust tests
-        println!(
+        status!(
             "\n{}",
             style("🚧 The `orbiton test` command is under active development.")
                 .yellow()
                 .bold()
         );
-        println!("Some advanced testing features are planned for future releases.");
-        println!();
-        println!("{}:", style("Planned features").bold());
-        println!(" • Unit testing for components");
-        println!(" • Integration testing for applications");
-        println!(" • Performance testing and benchmarking");
-        println!(" • Coverage reporting");
-        println!(" • Snapshot testing");
-        println!(" • Watch mode for test-driven development");
+        status!("Some advanced testing features are planned for future releases.");
+        status!();
+        status!("{}:", style("Planned features").bold());
+        status!(" • Unit testing for components");
+        status!(" • Integration testing for applications");
+
+        let passed = self.run(&project_dir);
+
+        status!(
+            "\n{} {}",
+            style("[4/4]").bold().dim(),
+            style("For more information on testing strategies, see:").italic()
+        );
+        status!("    https://docs.orbitrs.dev/guides/testing-strategies");
+
+        if self.watch {
+            self.watch_and_rerun(&project_dir)?;
+        } else if !passed {
+            // Exit non-zero so CI notices failures
+            let what = if self.performance {
+                "Benchmarks"
+            } else {
+                "Tests"
+            };
+            return Err(anyhow::anyhow!("{what} failed"));
+        }
 
+        Ok(())
+    }
+
+    /// Run the benchmarks with `--performance`, the tests otherwise
+    fn run(&self, project_dir: &Path) -> bool {
+        if self.performance {
+            self.run_benchmarks(project_dir)
+        } else {
+            self.run_tests(project_dir)
+        }
+    }
+
+    /// Run `cargo test` once, returning whether all tests passed
+    fn run_tests(&self, project_dir: &Path) -> bool {
         // Check for testing flags and run appropriate test commands
-        println!(
+        status!(
             "\n{} Running tests with current implementation:",
             style("[2/4]").bold().dim()
         );
 
-        // Build the cargo test command based on provided flags
-        let mut cmd_args = vec!["test"];
-
-        if self.verbose {
-            cmd_args.push("--verbose");
+        if self.coverage && !is_llvm_cov_installed(project_dir) {
+            println!(
+                "\n{} cargo-llvm-cov is required for coverage reporting.",
+                style("❌ Error:").red().bold()
+            );
+            println!(
+                "   Install it with: {}",
+                style("cargo install cargo-llvm-cov").bold()
+            );
+            return false;
         }
 
-        if self.unit && !self.integration {
-            cmd_args.push("--lib");
-        } else if self.integration && !self.unit {
-            cmd_args.push("--test");
-        }
+        let cmd_args = self.cargo_args();
 
-        println!(
+        status!(
             "{} Executing: cargo {}",
             style("[3/4]").bold().dim(),
             cmd_args.join(" ")
         );
 
         // Execute the cargo test command
-        let status = Command::new("cargo")
-            .args(&cmd_args)
-            .current_dir(&project_dir)
-            .status();
+        let status = self.cargo_command(project_dir).args(&cmd_args).status();
 
         match status {
             Ok(exit_status) => {
                 if exit_status.success() {
-                    println!(
+                    status!(
                         "\n{} {}",
                         style("✅ Success:").green().bold(),
                         style("All tests passed!").bold()
                     );
+
+                    if self.update_snapshots {
+                        status!(
+                            "{} Snapshots in {} were updated",
+                            style("📸 Snapshots:").cyan().bold(),
+                            crate::snapshot::SNAPSHOT_DIR
+                        );
+                    }
+                    if self.coverage {
+                        self.report_coverage(project_dir);
+                    }
+                    true
                 } else {
                     println!(
                         "\n{} {}",
                         style("❌ Error:").red().bold(),
                         style("Some tests failed.").bold()
                     );
+                    false
                 }
             }
             Err(e) => {
@@ -144,21 +244,540 @@ impl TestCommand {
                     style("❌ Error:").red().bold(),
                     e
                 );
+                false
             }
         }
+    }
+
+    /// Run the tests and print a JSON summary, failing when any test failed
+    ///
+    /// Uses libtest's JSON output, which needs a nightly toolchain. On stable
+    /// the summary is read from the regular output instead.
+    fn run_json_summary(&self, project_dir: &Path) -> Result<()> {
+        let json_output = is_nightly_toolchain(project_dir);
+        if !json_output {
+            eprintln!(
+                "{} JSON test output needs a nightly toolchain; reading cargo's regular output instead",
+                style("Warning:").bold().yellow()
+            );
+        }
+
+        let mut args = self.cargo_args();
+        if json_output {
+            if !args.iter().any(|arg| arg == "--") {
+                args.push("--".to_string());
+            }
+            args.extend(["-Z", "unstable-options", "--format", "json"].map(String::from));
+        }
+
+        // Test results go to stdout; compiler output still reaches the terminal
+        let output = self
+            .cargo_command(project_dir)
+            .args(&args)
+            .stderr(Stdio::inherit())
+            .output()
+            .context("Failed to execute cargo test")?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let summary = if json_output {
+            parse_json_test_output(&stdout)
+        } else {
+            parse_test_output(&stdout)
+        };
 
         println!(
-            "\n{} {}",
-            style("[4/4]").bold().dim(),
-            style("For more information on testing strategies, see:").italic()
+            "{}",
+            serde_json::to_string_pretty(&summary).context("Failed to serialize test summary")?
+        );
+
+        if summary.failed > 0 {
+            return Err(anyhow::anyhow!("{} test(s) failed", summary.failed));
+        }
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "cargo test failed ({}); see the output above",
+                output.status
+            ));
+        }
+        Ok(())
+    }
+
+    /// Run `cargo bench` and summarize the timings, returning whether it succeeded
+    fn run_benchmarks(&self, project_dir: &Path) -> bool {
+        status!("\n{} Running benchmarks:", style("[2/4]").bold().dim());
+
+        if !has_benchmarks(project_dir) {
+            println!(
+                "\n{} No benchmarks found in {}",
+                style("Info:").bold().blue(),
+                project_dir.display()
+            );
+            println!(
+                "   Add a {} directory with a benchmark, e.g. benches/rendering.rs,",
+                style("benches/").bold()
+            );
+            println!("   and register it in Cargo.toml to use a harness such as criterion:");
+            println!();
+            println!("   [[bench]]");
+            println!("   name = \"rendering\"");
+            println!("   harness = false");
+            return true;
+        }
+
+        let cmd_args = self.bench_args();
+        status!(
+            "{} Executing: cargo {}",
+            style("[3/4]").bold().dim(),
+            cmd_args.join(" ")
+        );
+
+        // Benchmark results go to stdout; compiler errors still reach the terminal
+        let output = match self
+            .cargo_command(project_dir)
+            .args(&cmd_args)
+            .stderr(Stdio::inherit())
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                println!(
+                    "\n{} Failed to execute cargo bench: {}",
+                    style("❌ Error:").red().bold(),
+                    e
+                );
+                return false;
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if self.verbose {
+            print!("{stdout}");
+        }
+
+        if !output.status.success() {
+            if !self.verbose {
+                print!("{stdout}");
+            }
+            println!(
+                "\n{} {}",
+                style("❌ Error:").red().bold(),
+                style("Benchmarks failed.").bold()
+            );
+            return false;
+        }
+
+        let results = parse_bench_output(&stdout);
+        if results.is_empty() {
+            println!(
+                "\n{} cargo bench reported no timings; run with --verbose to see its output",
+                style("Info:").bold().blue()
+            );
+        } else {
+            println!();
+            print_bench_table(&results);
+        }
+        true
+    }
+
+    /// `cargo` run in `project_dir` with offline mode, snapshot updates and the
+    /// project's `.env` applied; the caller adds the subcommand and its arguments
+    fn cargo_command(&self, project_dir: &Path) -> Command {
+        let mut command = Command::new("cargo");
+        // cargo-llvm-cov picks up offline mode from CARGO_NET_OFFLINE instead
+        if !self.coverage || self.performance {
+            command.args(crate::utils::network::cargo_offline_arg());
+        }
+        command.current_dir(project_dir);
+        if self.update_snapshots {
+            command.env(crate::snapshot::UPDATE_SNAPSHOTS_ENV_VAR, "1");
+        }
+
+        if !self.no_env {
+            match crate::utils::env::load_dotenv(project_dir) {
+                Ok(vars) => {
+                    command.envs(vars);
+                }
+                Err(e) => {
+                    eprintln!("{} Ignoring .env: {e:#}", style("Warning:").bold().yellow());
+                }
+            }
+        }
+        command
+    }
+
+    /// Cargo arguments for the benchmark run
+    fn bench_args(&self) -> Vec<String> {
+        let mut args = vec!["bench".to_string()];
+        if self.verbose {
+            args.push("--verbose".to_string());
+        } else {
+            args.push("--quiet".to_string());
+        }
+        args.extend(self.features_args());
+        args.extend(self.passthrough_args());
+        args
+    }
+
+    /// Build the cargo arguments for the test run based on the provided flags
+    fn cargo_args(&self) -> Vec<String> {
+        let mut args = if self.coverage {
+            vec!["llvm-cov".to_string()]
+        } else {
+            vec!["test".to_string()]
+        };
+
+        if self.verbose {
+            args.push("--verbose".to_string());
+        }
+
+        if self.unit && !self.integration {
+            args.push("--lib".to_string());
+        } else if self.integration && !self.unit {
+            args.push("--test".to_string());
+        }
+
+        if self.release {
+            args.push("--release".to_string());
+        }
+        args.extend(self.features_args());
+
+        if self.coverage {
+            // Emit an lcov file that CI coverage services can consume
+            args.extend(["--lcov", "--output-path", LCOV_OUTPUT].map(String::from));
+        }
+
+        args.extend(self.passthrough_args());
+
+        args
+    }
+
+    /// Arguments given after `--`, kept behind cargo's own `--` so that
+    /// filters and libtest flags like `--nocapture` reach the test binary
+    fn passthrough_args(&self) -> Vec<String> {
+        if self.cargo_args.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once("--".to_string())
+            .chain(self.cargo_args.iter().cloned())
+            .collect()
+    }
+
+    /// `--features` for cargo, when features were requested
+    fn features_args(&self) -> Vec<String> {
+        match &self.features {
+            Some(features) => vec!["--features".to_string(), features.clone()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Point at the generated lcov file and, with `--report`, print the coverage summary
+    fn report_coverage(&self, project_dir: &Path) {
+        println!(
+            "{} Coverage data written to {}",
+            style("📊 Coverage:").cyan().bold(),
+            style(project_dir.join(LCOV_OUTPUT).display()).underlined()
+        );
+
+        if !self.report {
+            return;
+        }
+
+        let status = Command::new("cargo")
+            .args(["llvm-cov", "report", "--summary-only"])
+            .current_dir(project_dir)
+            .status();
+
+        if let Err(e) = status {
+            println!(
+                "{} Failed to generate coverage report: {}",
+                style("❌ Error:").red().bold(),
+                e
+            );
+        }
+    }
+
+    /// Watch `src/` and `tests/` and re-run the tests on every change until Ctrl+C
+    fn watch_and_rerun(&self, project_dir: &Path) -> Result<()> {
+        let config = OrbitonConfig::load_from_project(project_dir)?;
+        let ignore = crate::utils::fs::glob_set(&config.hmr.ignore_patterns)?;
+        let debounce = Duration::from_millis(config.hmr.debounce_ms);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: std::result::Result<Event, notify::Error>| {
+                match res {
+                    Ok(event) => {
+                        let _ = tx.send(event);
+                    }
+                    Err(e) => error!("Watch error: {e}"),
+                }
+            })?;
+
+        let mut watching = false;
+        for dir in ["src", "tests"] {
+            let path = project_dir.join(dir);
+            if path.is_dir() {
+                watcher.watch(&path, RecursiveMode::Recursive)?;
+                watching = true;
+            }
+        }
+
+        if !watching {
+            return Err(anyhow::anyhow!(
+                "Nothing to watch: neither src/ nor tests/ exists in {project_dir:?}"
+            ));
+        }
+
+        status!(
+            "\n{} for changes in src/ and tests/. Press {} to stop",
+            style("Watching").bold().cyan(),
+            style("Ctrl+C").bold()
         );
-        println!("    https://docs.orbitrs.dev/guides/testing-strategies");
 
-        // Return Ok to indicate command executed successfully
+        let mut run = 1;
+        for event in &rx {
+            let relevant = event
+                .paths
+                .iter()
+                .any(|path| !crate::utils::fs::is_ignored(&ignore, project_dir, path));
+            if !relevant {
+                continue;
+            }
+
+            // Let a burst of events from a single save settle before re-running
+            while rx.recv_timeout(debounce).is_ok() {}
+
+            run += 1;
+            status!("\n{}", style("─".repeat(60)).dim());
+            status!(
+                "{} Test run #{run} at {}",
+                style("Re-running").bold().cyan(),
+                current_time_utc()
+            );
+            status!("{}", style("─".repeat(60)).dim());
+
+            self.run(project_dir);
+        }
+
         Ok(())
     }
 }
 
+/// Whether the toolchain used in `project_dir` accepts unstable libtest options
+fn is_nightly_toolchain(project_dir: &Path) -> bool {
+    Command::new("rustc")
+        .arg("--version")
+        .current_dir(project_dir)
+        .output()
+        .is_ok_and(|output| {
+            let version = String::from_utf8_lossy(&output.stdout);
+            version.contains("-nightly") || version.contains("-dev")
+        })
+}
+
+/// Summarize libtest's JSON events (`--format json`)
+fn parse_json_test_output(output: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    for line in output.lines() {
+        let Ok(event) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let count = |key: &str| event[key].as_u64().unwrap_or_default();
+        match (event["type"].as_str(), event["event"].as_str()) {
+            (Some("suite"), Some("ok" | "failed")) => {
+                summary.passed += count("passed");
+                summary.failed += count("failed");
+                summary.ignored += count("ignored");
+                summary.duration_secs += event["exec_time"].as_f64().unwrap_or_default();
+            }
+            (Some("test"), Some("failed")) => {
+                if let Some(name) = event["name"].as_str() {
+                    summary.failures.push(name.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// Summarize libtest's regular output, for stable toolchains
+fn parse_test_output(output: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        {
+            summary.failures.push(name.to_string());
+        }
+
+        let Some(results) = line.strip_prefix("test result: ") else {
+            continue;
+        };
+        // e.g. "ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.50s"
+        let counts = results
+            .split_once(". ")
+            .map_or(results, |(_, counts)| counts);
+        for part in counts.split(';').map(str::trim) {
+            if let Some(secs) = part
+                .strip_prefix("finished in ")
+                .and_then(|time| time.strip_suffix('s'))
+            {
+                summary.duration_secs += secs.parse::<f64>().unwrap_or_default();
+                continue;
+            }
+            let Some((count, label)) = part.split_once(' ') else {
+                continue;
+            };
+            let Ok(count) = count.parse::<u64>() else {
+                continue;
+            };
+            match label {
+                "passed" => summary.passed += count,
+                "failed" => summary.failed += count,
+                "ignored" => summary.ignored += count,
+                _ => {}
+            }
+        }
+    }
+    summary
+}
+
+/// Timing of a single benchmark
+#[derive(Debug, PartialEq)]
+struct BenchResult {
+    name: String,
+    ns_per_iter: f64,
+}
+
+/// Whether the project has a `benches/` directory or declares `[[bench]]` targets
+fn has_benchmarks(project_dir: &Path) -> bool {
+    project_dir.join("benches").is_dir()
+        || std::fs::read_to_string(project_dir.join("Cargo.toml"))
+            .is_ok_and(|manifest| manifest.contains("[[bench]]"))
+}
+
+/// Extract timings from `cargo bench` output
+///
+/// Understands the built-in harness (`test name ... bench: 1,234 ns/iter (+/- 5)`)
+/// and criterion (`name  time: [1.1 µs 1.2 µs 1.3 µs]`, using the middle estimate).
+fn parse_bench_output(output: &str) -> Vec<BenchResult> {
+    let mut results = Vec::new();
+    let mut previous_line = "";
+    for line in output.lines() {
+        if let Some((name, timing)) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.split_once(" ... bench:"))
+        {
+            let ns = timing
+                .split_whitespace()
+                .next()
+                .and_then(|ns| ns.replace(',', "").parse().ok());
+            if let Some(ns_per_iter) = ns {
+                results.push(BenchResult {
+                    name: name.trim().to_string(),
+                    ns_per_iter,
+                });
+            }
+        } else if let Some((name, timing)) = line.split_once("time:") {
+            // Long criterion names are printed on a line of their own
+            let name = match name.trim() {
+                "" => previous_line.trim(),
+                name => name,
+            };
+            let estimate: Vec<&str> = timing
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split_whitespace()
+                .collect();
+            if let [_, _, value, unit, ..] = estimate.as_slice() {
+                if let (Ok(value), Some(scale)) = (value.parse::<f64>(), unit_in_ns(unit)) {
+                    results.push(BenchResult {
+                        name: name.to_string(),
+                        ns_per_iter: value * scale,
+                    });
+                }
+            }
+        }
+        previous_line = line;
+    }
+    results
+}
+
+/// Nanoseconds per unit of a criterion time estimate
+fn unit_in_ns(unit: &str) -> Option<f64> {
+    match unit {
+        "ps" => Some(0.001),
+        "ns" => Some(1.0),
+        "µs" | "us" => Some(1_000.0),
+        "ms" => Some(1_000_000.0),
+        "s" => Some(1_000_000_000.0),
+        _ => None,
+    }
+}
+
+/// Print benchmark timings as a two-column table
+fn print_bench_table(results: &[BenchResult]) {
+    let width = results
+        .iter()
+        .map(|result| result.name.len())
+        .chain(std::iter::once("Benchmark".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{}",
+        style(format!("{:<width$}  {:>14}", "Benchmark", "ns/iter")).bold()
+    );
+    for result in results {
+        println!(
+            "{:<width$}  {:>14}",
+            result.name,
+            format_ns(result.ns_per_iter)
+        );
+    }
+}
+
+/// Format a duration in nanoseconds with thousands separators, e.g. `1,234,567`
+fn format_ns(ns: f64) -> String {
+    if ns < 10.0 {
+        return format!("{ns:.2}");
+    }
+    let digits = format!("{:.0}", ns);
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Check whether the `cargo llvm-cov` subcommand is available
+fn is_llvm_cov_installed(project_dir: &Path) -> bool {
+    Command::new("cargo")
+        .args(["llvm-cov", "--version"])
+        .current_dir(project_dir)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Format the current wall-clock time as `HH:MM:SS UTC`
+fn current_time_utc() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        % 86_400;
+
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,11 +789,16 @@ mod tests {
             unit: false,
             integration: true,
             performance: false,
+            release: false,
+            features: None,
             coverage: true,
             report: true,
             update_snapshots: false,
             verbose: true,
             project_dir: None,
+            no_env: false,
+            format: TestFormat::Human,
+            cargo_args: vec![],
         };
 
         assert!(cmd.watch);
@@ -196,38 +820,163 @@ mod tests {
             unit: true,
             integration: false,
             performance: false,
+            release: true,
+            features: Some("web,hmr".to_string()),
             coverage: false,
             report: false,
             update_snapshots: false,
             verbose: true,
             project_dir: None,
+            no_env: false,
+            format: TestFormat::Human,
+            cargo_args: vec![],
         };
 
-        // This is a way to test the command building without actually running commands
-        // Extract the command args building logic to a separate method to make it testable
-        let args = build_test_command_args(&cmd);
+        // Test the command building without actually running commands
+        let args = cmd.cargo_args();
+
+        assert!(args.contains(&"test".to_string()));
+        assert!(args.contains(&"--verbose".to_string()));
+        assert!(args.contains(&"--lib".to_string()));
+        assert!(!args.contains(&"--test".to_string()));
+        assert_eq!(
+            args,
+            [
+                "test",
+                "--verbose",
+                "--lib",
+                "--release",
+                "--features",
+                "web,hmr"
+            ]
+        );
+    }
 
-        assert!(args.contains(&"test"));
-        assert!(args.contains(&"--verbose"));
-        assert!(args.contains(&"--lib"));
-        assert!(!args.contains(&"--test"));
+    #[test]
+    fn test_parse_bench_output() {
+        let output = "\
+running 3 tests
+test render::bench_layout   ... bench:       1,234 ns/iter (+/- 56)
+test render::bench_paint    ... bench:          12 ns/iter (+/- 1)
+test render::tree_is_valid ... ignored
+
+rendering/button        time:   [1.1002 µs 1.2000 µs 1.3001 µs]
+rendering/a_very_long_benchmark_name
+                        time:   [2.5000 ms 2.7500 ms 3.0000 ms]
+                        change: [-1.2% +0.3% +1.9%] (p = 0.71 > 0.05)
+";
+        let results = parse_bench_output(output);
+        let timings: Vec<(&str, f64)> = results
+            .iter()
+            .map(|result| (result.name.as_str(), result.ns_per_iter))
+            .collect();
+        assert_eq!(
+            timings,
+            [
+                ("render::bench_layout", 1234.0),
+                ("render::bench_paint", 12.0),
+                ("rendering/button", 1200.0),
+                ("rendering/a_very_long_benchmark_name", 2_750_000.0),
+            ]
+        );
+        assert_eq!(format_ns(2_750_000.0), "2,750,000");
+        assert_eq!(format_ns(123.0), "123");
     }
 
-    // Helper function for testing the command args
-    #[cfg(test)]
-    fn build_test_command_args(cmd: &TestCommand) -> Vec<&'static str> {
-        let mut args = vec!["test"];
+    #[test]
+    fn test_parse_json_test_output() {
+        let output = r#"{ "type": "suite", "event": "started", "test_count": 3 }
+{ "type": "test", "event": "started", "name": "tests::renders" }
+{ "type": "test", "name": "tests::renders", "event": "ok" }
+{ "type": "test", "name": "tests::handles_click", "event": "failed", "stdout": "panicked" }
+{ "type": "test", "name": "tests::slow", "event": "ignored" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1, "measured": 0, "filtered_out": 0, "exec_time": 0.25 }
+{ "type": "suite", "event": "ok", "passed": 2, "failed": 0, "ignored": 0, "measured": 0, "filtered_out": 0, "exec_time": 0.5 }
+"#;
+        assert_eq!(
+            parse_json_test_output(output),
+            TestSummary {
+                passed: 3,
+                failed: 1,
+                ignored: 1,
+                duration_secs: 0.75,
+                failures: vec!["tests::handles_click".to_string()],
+            }
+        );
+    }
 
-        if cmd.verbose {
-            args.push("--verbose");
-        }
+    #[test]
+    fn test_parse_test_output() {
+        let output = "
+running 3 tests
+test tests::renders ... ok
+test tests::handles_click ... FAILED
+test tests::slow ... ignored
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.25s
+
+running 2 tests
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.50s
+";
+        assert_eq!(
+            parse_test_output(output),
+            TestSummary {
+                passed: 3,
+                failed: 1,
+                ignored: 1,
+                duration_secs: 0.75,
+                failures: vec!["tests::handles_click".to_string()],
+            }
+        );
+    }
 
-        if cmd.unit && !cmd.integration {
-            args.push("--lib");
-        } else if cmd.integration && !cmd.unit {
-            args.push("--test");
-        }
+    #[test]
+    fn test_has_benchmarks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(!has_benchmarks(temp_dir.path()));
+
+        std::fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[[bench]]\nname = \"rendering\"\nharness = false\n",
+        )
+        .unwrap();
+        assert!(has_benchmarks(temp_dir.path()));
+    }
 
-        args
+    #[test]
+    fn test_cargo_args_passthrough() {
+        let cmd = TestCommand::try_parse_from([
+            "test",
+            "--unit",
+            "--verbose",
+            "--",
+            "my_test",
+            "--nocapture",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cmd.cargo_args(),
+            vec!["test", "--verbose", "--lib", "--", "my_test", "--nocapture"]
+        );
+
+        let cmd = TestCommand::try_parse_from(["test", "--performance", "--", "parse"]).unwrap();
+        assert_eq!(cmd.bench_args(), vec!["bench", "--quiet", "--", "parse"]);
+    }
+
+    #[test]
+    fn test_release_and_features_compose_with_selection() {
+        let cmd = TestCommand::try_parse_from([
+            "test",
+            "--integration",
+            "--release",
+            "--features",
+            "web",
+        ])
+        .unwrap();
+        assert_eq!(
+            cmd.cargo_args(),
+            ["test", "--test", "--release", "--features", "web"]
+        );
     }
 }
@@ -0,0 +1,417 @@
+// Command for upgrading the Orbit dependencies of a project
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::status;
+use crate::utils::DEPENDENCY_TABLES;
+
+/// Crates that make up the Orbit framework
+const ORBIT_CRATES: &[&str] = &["orbit", "orbitrs", "orbiton"];
+
+/// crates.io API endpoint for crate metadata
+const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
+
+#[derive(Args)]
+pub struct UpgradeArgs {
+    /// Project directory
+    #[arg(short, long)]
+    dir: Option<PathBuf>,
+
+    /// Version to upgrade to (defaults to the latest release on crates.io)
+    #[arg(long, value_name = "VERSION")]
+    version: Option<String>,
+
+    /// Show the changes without writing Cargo.toml
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// A dependency whose version requirement was changed
+#[derive(Debug, PartialEq, Eq)]
+struct Upgrade {
+    table: String,
+    name: String,
+    from: String,
+    to: String,
+}
+
+/// Result of upgrading a manifest in memory
+struct ManifestUpgrade {
+    contents: String,
+    upgrades: Vec<Upgrade>,
+    /// Orbit dependencies left alone, with the reason
+    skipped: Vec<(String, String)>,
+}
+
+pub fn execute(args: UpgradeArgs) -> Result<()> {
+    let project_dir = match args.dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    let manifest_path = project_dir.join("Cargo.toml");
+    let contents = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {manifest_path:?}"))?;
+
+    let mut versions: HashMap<String, String> = HashMap::new();
+    let result = upgrade_manifest(&contents, &mut |package| {
+        if let Some(version) = &args.version {
+            return Ok(version.clone());
+        }
+        if let Some(version) = versions.get(package) {
+            return Ok(version.clone());
+        }
+        status!("Looking up the latest {} release...", style(package).cyan());
+        let version = latest_version(package)?;
+        versions.insert(package.to_string(), version.clone());
+        Ok(version)
+    })?;
+
+    for (name, reason) in &result.skipped {
+        status!(
+            "{} Skipping {}: {reason}",
+            style("Info:").bold().blue(),
+            style(name).cyan()
+        );
+    }
+
+    if result.upgrades.is_empty() {
+        if result.skipped.is_empty() {
+            println!(
+                "{} No Orbit dependencies found in {}",
+                style("Info:").bold().blue(),
+                manifest_path.display()
+            );
+        } else {
+            println!("Orbit dependencies are already up to date");
+        }
+        return Ok(());
+    }
+
+    for upgrade in &result.upgrades {
+        println!(
+            "  {} [{}] {} -> {}",
+            style(&upgrade.name).cyan(),
+            upgrade.table,
+            upgrade.from,
+            style(&upgrade.to).green()
+        );
+    }
+    println!();
+    print_diff(&contents, &result.contents);
+
+    if args.dry_run {
+        println!(
+            "\n{} Dry run, {} was not modified",
+            style("Info:").bold().blue(),
+            manifest_path.display()
+        );
+        return Ok(());
+    }
+
+    std::fs::write(&manifest_path, &result.contents)
+        .with_context(|| format!("Failed to write {manifest_path:?}"))?;
+    println!(
+        "\n{} Updated {} dependencies in {}",
+        style("Success!").bold().green(),
+        result.upgrades.len(),
+        manifest_path.display()
+    );
+
+    Ok(())
+}
+
+/// Update every Orbit dependency in `contents` to the version returned by `resolve`
+///
+/// `resolve` receives the crate name (honouring `package` renames). Formatting
+/// and comments of the manifest are preserved.
+fn upgrade_manifest(
+    contents: &str,
+    resolve: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<ManifestUpgrade> {
+    let mut doc: DocumentMut = contents.parse().context("Failed to parse Cargo.toml")?;
+    let mut upgrades = Vec::new();
+    let mut skipped = Vec::new();
+
+    let root = doc.as_table_mut();
+    for table_name in DEPENDENCY_TABLES {
+        if let Some(table) = root.get_mut(table_name).and_then(Item::as_table_like_mut) {
+            upgrade_table(table, table_name, resolve, &mut upgrades, &mut skipped)?;
+        }
+    }
+
+    if let Some(table) = root
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("dependencies"))
+        .and_then(Item::as_table_like_mut)
+    {
+        upgrade_table(
+            table,
+            "workspace.dependencies",
+            resolve,
+            &mut upgrades,
+            &mut skipped,
+        )?;
+    }
+
+    if let Some(targets) = root.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (cfg, target) in targets.iter_mut() {
+            for table_name in DEPENDENCY_TABLES {
+                if let Some(table) = target.get_mut(table_name).and_then(Item::as_table_like_mut) {
+                    let label = format!("target.{}.{table_name}", cfg.get());
+                    upgrade_table(table, &label, resolve, &mut upgrades, &mut skipped)?;
+                }
+            }
+        }
+    }
+
+    Ok(ManifestUpgrade {
+        contents: doc.to_string(),
+        upgrades,
+        skipped,
+    })
+}
+
+/// Upgrade the Orbit entries of a single dependency table
+fn upgrade_table(
+    table: &mut dyn toml_edit::TableLike,
+    label: &str,
+    resolve: &mut dyn FnMut(&str) -> Result<String>,
+    upgrades: &mut Vec<Upgrade>,
+    skipped: &mut Vec<(String, String)>,
+) -> Result<()> {
+    for (key, item) in table.iter_mut() {
+        let name = key.get().to_string();
+        let package = item
+            .get("package")
+            .and_then(Item::as_str)
+            .unwrap_or(&name)
+            .to_string();
+        if !ORBIT_CRATES.contains(&package.as_str()) {
+            continue;
+        }
+
+        let no_requirement = "no version requirement to update".to_string();
+        let Some(version) = version_value(item) else {
+            skipped.push((name, no_requirement));
+            continue;
+        };
+        let Some(current) = version.as_str().map(str::to_string) else {
+            skipped.push((name, no_requirement));
+            continue;
+        };
+        // Only the leading operator would survive, dropping the other bounds
+        if current.contains(',') {
+            skipped.push((
+                name,
+                format!("compound requirement '{current}' has to be updated by hand"),
+            ));
+            continue;
+        }
+
+        let target = resolve(&package)?;
+        if is_older(&target, &current) {
+            skipped.push((
+                name,
+                format!("{target} is older than the current requirement '{current}'"),
+            ));
+            continue;
+        }
+        let requirement = with_operator(&current, &target);
+        if requirement == current {
+            continue;
+        }
+
+        let decor = version.decor().clone();
+        *version = Value::from(requirement.as_str());
+        *version.decor_mut() = decor;
+
+        upgrades.push(Upgrade {
+            table: label.to_string(),
+            name,
+            from: current,
+            to: requirement,
+        });
+    }
+
+    Ok(())
+}
+
+/// The version requirement of a dependency entry in any of its forms
+fn version_value(item: &mut Item) -> Option<&mut Value> {
+    match item {
+        Item::Value(Value::InlineTable(table)) => table.get_mut("version"),
+        Item::Value(value) => Some(value),
+        Item::Table(table) => table.get_mut("version").and_then(Item::as_value_mut),
+        _ => None,
+    }
+}
+
+/// Keep the requirement operator (`=`, `^`, `~`, ...) of `current` in front of `version`
+fn with_operator(current: &str, version: &str) -> String {
+    let operator: String = current
+        .chars()
+        .take_while(|c| matches!(c, '=' | '^' | '~' | '>' | '<' | ' '))
+        .collect();
+    format!("{operator}{version}")
+}
+
+/// Whether `version` is lower than the version in the requirement `current`
+///
+/// Requirements that aren't plain versions, like `*` or `0.1.*`, never compare as newer.
+fn is_older(version: &str, current: &str) -> bool {
+    let parts = |version: &str| -> Option<[u64; 3]> {
+        let version = version.trim_start_matches(['=', '^', '~', '>', '<', ' ']);
+        let release = version.split(['-', '+']).next()?;
+        let mut parts = [0; 3];
+        for (part, number) in parts.iter_mut().zip(release.split('.')) {
+            *part = number.parse().ok()?;
+        }
+        Some(parts)
+    };
+    match (parts(version), parts(current)) {
+        (Some(version), Some(current)) => version < current,
+        _ => false,
+    }
+}
+
+/// Query crates.io for the newest stable release of a crate
+fn latest_version(name: &str) -> Result<String> {
+    crate::utils::network::ensure_online(&format!(
+        "Looking up the latest {name} release on crates.io (pass --version to skip it)"
+    ))?;
+    let url = format!("{CRATES_IO_API}/{name}");
+    let response = reqwest::blocking::Client::new()
+        .get(&url)
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!("orbiton/", env!("CARGO_PKG_VERSION")),
+        )
+        .send()
+        .with_context(|| format!("Failed to query crates.io for {name}"))?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "crates.io returned {} for {name}; pass --version to choose a version explicitly",
+            response.status()
+        ));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .with_context(|| format!("Invalid crates.io response for {name}"))?;
+    let info = &body["crate"];
+    info["max_stable_version"]
+        .as_str()
+        .or_else(|| info["max_version"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("crates.io has no published version of {name}"))
+}
+
+/// Print the changed lines of the manifest
+///
+/// Upgrades only rewrite values in place, so the line structure is unchanged.
+fn print_diff(old: &str, new: &str) {
+    println!("{}", style("--- Cargo.toml").red());
+    println!("{}", style("+++ Cargo.toml").green());
+    for (number, (before, after)) in old.lines().zip(new.lines()).enumerate() {
+        if before != after {
+            println!("@@ line {} @@", number + 1);
+            println!("{}", style(format!("-{before}")).red());
+            println!("{}", style(format!("+{after}")).green());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"[package]
+name = "my-app"
+version = "0.1.0"
+
+[dependencies]
+# UI framework
+orbit = "0.1.0"  # keep in sync with orbiton
+serde = "1.0"
+ui = { package = "orbitrs", version = "^0.1", features = ["web"] }
+
+[dev-dependencies.orbiton]
+version = "=0.1.0"
+
+[target.'cfg(target_arch = "wasm32")'.dependencies]
+orbit = { path = "../orbit" }
+"#;
+
+    #[test]
+    fn test_upgrade_manifest_preserves_formatting() {
+        let mut requested = Vec::new();
+        let result = upgrade_manifest(MANIFEST, &mut |package| {
+            requested.push(package.to_string());
+            Ok("0.2.0".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(requested, ["orbit", "orbitrs", "orbiton"]);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].0, "orbit");
+        assert_eq!(
+            result.upgrades[1],
+            Upgrade {
+                table: "dependencies".to_string(),
+                name: "ui".to_string(),
+                from: "^0.1".to_string(),
+                to: "^0.2.0".to_string(),
+            }
+        );
+
+        let expected = MANIFEST
+            .replace(r#"orbit = "0.1.0""#, r#"orbit = "0.2.0""#)
+            .replace(r#"version = "^0.1""#, r#"version = "^0.2.0""#)
+            .replace(r#"version = "=0.1.0""#, r#"version = "=0.2.0""#);
+        assert_eq!(result.contents, expected);
+    }
+
+    #[test]
+    fn test_upgrade_manifest_up_to_date() {
+        let result = upgrade_manifest(MANIFEST, &mut |_| Ok("0.1.0".to_string())).unwrap();
+        assert_eq!(result.upgrades.len(), 1);
+        assert_eq!(result.upgrades[0].name, "ui");
+        assert!(result
+            .contents
+            .contains(r#"orbit = "0.1.0"  # keep in sync"#));
+    }
+
+    #[test]
+    fn test_upgrade_manifest_skips_compound_requirements_and_downgrades() {
+        let manifest = r#"[dependencies]
+orbit = ">=0.1, <0.2"
+orbitrs = "0.3"
+"#;
+        let result = upgrade_manifest(manifest, &mut |_| Ok("0.2.0".to_string())).unwrap();
+        assert!(result.upgrades.is_empty());
+        assert_eq!(result.contents, manifest);
+
+        let skipped: Vec<&str> = result
+            .skipped
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(skipped, ["orbit", "orbitrs"]);
+        assert!(result.skipped[0].1.contains("compound"));
+        assert!(result.skipped[1].1.contains("older"));
+    }
+
+    #[test]
+    fn test_is_older() {
+        assert!(is_older("0.1.0", "^0.2"));
+        assert!(is_older("0.9.9", "=1.0.0"));
+        assert!(!is_older("0.2.0", "0.2"));
+        assert!(!is_older("0.10.0", "~0.9.3"));
+        assert!(!is_older("0.1.0", "0.1.*"));
+    }
+}
@@ -2,13 +2,20 @@
 // Supports .orbiton.toml configuration files for customizing build and dev behavior
 
 use anyhow::{Context, Result};
+use console::style;
+use log::info;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::output::Verbosity;
+use crate::status;
 
 /// Main configuration structure for Orbiton
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct OrbitonConfig {
     /// Project configuration
     #[serde(default)]
@@ -29,9 +36,89 @@ pub struct OrbitonConfig {
     /// Linting configuration
     #[serde(default)]
     pub lint: LintConfig,
+
+    /// Renderer configuration
+    #[serde(default)]
+    pub renderer: RendererConfig,
+
+    /// Named profiles whose settings override the top-level configuration
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, OrbitonConfig>,
+
+    /// Base config file whose settings this file overrides, relative to this file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Dotted keys that were explicitly present in the parsed file
+    #[serde(skip)]
+    explicit_keys: HashSet<String>,
+}
+
+/// A value that was explicitly set on both sides of a merge with different values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Dotted key of the conflicting field
+    pub key: String,
+    /// Value in the base configuration, which was overwritten
+    pub base: String,
+    /// Value in the overriding configuration, which won
+    pub other: String,
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.key, self.base, self.other)
+    }
+}
+
+/// Applies fields from an overriding config, treating a field as set when it was
+/// present in the source file or differs from its default
+struct FieldMerger<'a> {
+    base_keys: &'a HashSet<String>,
+    other_keys: &'a HashSet<String>,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl FieldMerger<'_> {
+    fn field<T: Clone + PartialEq + std::fmt::Debug>(
+        &mut self,
+        key: &str,
+        base: &mut T,
+        other: &T,
+        default: &T,
+    ) {
+        let other_set = other != default || self.other_keys.contains(key);
+        if !other_set {
+            return;
+        }
+
+        let base_set = *base != *default || self.base_keys.contains(key);
+        if base_set && base != other {
+            self.conflicts.push(MergeConflict {
+                key: key.to_string(),
+                base: format!("{base:?}"),
+                other: format!("{other:?}"),
+            });
+        }
+
+        *base = other.clone();
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Environment variable selecting the active configuration profile
+pub const PROFILE_ENV_VAR: &str = "ORBITON_PROFILE";
+
+/// Environment variable pointing at an explicit configuration file
+pub const CONFIG_ENV_VAR: &str = "ORBITON_CONFIG";
+
+/// Config file selected with `--config` or `ORBITON_CONFIG`, if any
+pub fn config_override() -> Option<PathBuf> {
+    std::env::var_os(CONFIG_ENV_VAR)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectConfig {
     /// Project name
     pub name: Option<String>,
@@ -52,7 +139,7 @@ pub struct ProjectConfig {
     pub entry_point: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DevServerConfig {
     /// Port for the development server (default: 3000)
     #[serde(default = "default_dev_port")]
@@ -73,9 +160,33 @@ pub struct DevServerConfig {
     /// Custom headers to add to responses
     #[serde(default)]
     pub headers: HashMap<String, String>,
+
+    /// Maximum number of concurrent HMR WebSocket clients (default: 32)
+    #[serde(default = "default_max_ws_clients")]
+    pub max_ws_clients: usize,
+
+    /// Seconds between heartbeat pings sent to WebSocket clients (default: 30)
+    #[serde(default = "default_ws_ping_interval")]
+    pub ws_ping_interval_secs: u64,
+
+    /// Seconds to wait for a pong before dropping a WebSocket client (default: 10)
+    #[serde(default = "default_ws_pong_timeout")]
+    pub ws_pong_timeout_secs: u64,
+
+    /// Path appended to the URL opened in the browser (default: "/")
+    #[serde(default = "default_open_path")]
+    pub open_path: String,
+
+    /// Render an index of directories without an index file (default: false)
+    #[serde(default = "default_directory_listing")]
+    pub directory_listing: bool,
+
+    /// File served for the root and other directories (default: "index.html")
+    #[serde(default = "default_index_file")]
+    pub index_file: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HmrConfig {
     /// Whether HMR is enabled (default: true)
     #[serde(default = "default_hmr_enabled")]
@@ -93,16 +204,36 @@ pub struct HmrConfig {
     #[serde(default = "default_preserve_state")]
     pub preserve_state: bool,
 
-    /// Maximum number of HMR retries before full reload (default: 3)
+    /// Maximum number of browser reconnection attempts (default: 3)
     #[serde(default = "default_max_retries")]
     pub max_retries: u32,
 
     /// Whether to show HMR notifications in browser (default: true)
     #[serde(default = "default_show_notifications")]
     pub show_notifications: bool,
+
+    /// Extensions of files under `src/` that produce module updates
+    #[serde(default = "default_watch_extensions")]
+    pub watch_extensions: Vec<String>,
+
+    /// Files that require a full page reload instead of a module update
+    #[serde(default = "default_full_reload_patterns")]
+    pub full_reload_patterns: Vec<String>,
+
+    /// Extra directories to watch, relative to the project or absolute
+    #[serde(default)]
+    pub watch_dirs: Vec<String>,
+
+    /// Embed the HMR client in pages instead of linking /__orbit_hmr_client.js (default: false)
+    #[serde(default = "default_inline_client")]
+    pub inline_client: bool,
+
+    /// CSP nonce added to the inlined HMR client script
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csp_nonce: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct BuildConfig {
     /// Whether to use beta Rust toolchain (default: false)
     #[serde(default)]
@@ -119,25 +250,220 @@ pub struct BuildConfig {
     #[serde(default)]
     pub features: Vec<String>,
 
+    /// Glob patterns of `.orbit` files to leave out of builds, relative to the project
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
     /// Build optimization level (0-3, s, z)
     pub opt_level: Option<String>,
+
+    /// Content-hash web asset filenames for cache-busting (default: on for release builds)
+    pub hash_assets: Option<bool>,
+
+    /// Program and arguments the dev server runs instead of `cargo build`
+    pub command: Option<Vec<String>>,
+
+    /// Shell command run before each dev rebuild; the build is skipped if it fails
+    pub pre_rebuild_hook: Option<String>,
+
+    /// Shell command run after each successful dev rebuild
+    pub post_rebuild_hook: Option<String>,
+
+    /// Skip the browser update when the post-rebuild hook fails (default: false)
+    #[serde(default)]
+    pub hook_failure_blocks_reload: bool,
+
+    /// Warn when a dev rebuild takes longer than this many milliseconds (default: 10000, 0 disables)
+    pub slow_build_warning_ms: Option<u64>,
+}
+
+/// Default for `build.slow_build_warning_ms`
+const DEFAULT_SLOW_BUILD_WARNING_MS: u64 = 10_000;
+
+impl BuildConfig {
+    /// Rebuild duration above which the dev server warns, or `None` when disabled
+    pub fn slow_build_threshold(&self) -> Option<Duration> {
+        match self
+            .slow_build_warning_ms
+            .unwrap_or(DEFAULT_SLOW_BUILD_WARNING_MS)
+        {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LintConfig {
     /// Whether linting is enabled (default: true)
     #[serde(default = "default_lint_enabled")]
     pub enabled: bool,
 
-    /// Lint rules to enable/disable
+    /// Severity of each lint rule: `"off"`, `"warn"` or `"error"`
+    ///
+    /// `true` keeps a rule at its default severity and `false` turns it off.
     #[serde(default)]
-    pub rules: HashMap<String, bool>,
+    pub rules: HashMap<String, RuleSetting>,
 
     /// Custom lint configuration
     #[serde(default)]
     pub custom_rules: Vec<String>,
 }
 
+/// How findings of a lint rule are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Off,
+    Warn,
+    Error,
+}
+
+/// Setting of a rule in `lint.rules`, either a severity or an on/off switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum RuleSetting {
+    Enabled(bool),
+    Severity(LintSeverity),
+}
+
+impl RuleSetting {
+    /// Effective severity of a rule that defaults to `default`
+    ///
+    /// `true` maps to the default and `false` to [`LintSeverity::Off`].
+    pub fn severity(self, default: LintSeverity) -> LintSeverity {
+        match self {
+            Self::Enabled(true) => default,
+            Self::Enabled(false) => LintSeverity::Off,
+            Self::Severity(severity) => severity,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleSetting {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SettingVisitor;
+
+        impl serde::de::Visitor<'_> for SettingVisitor {
+            type Value = RuleSetting;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(r#""off", "warn", "error", true or false"#)
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, enabled: bool) -> Result<Self::Value, E> {
+                Ok(RuleSetting::Enabled(enabled))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                match value {
+                    "off" => Ok(RuleSetting::Severity(LintSeverity::Off)),
+                    "warn" => Ok(RuleSetting::Severity(LintSeverity::Warn)),
+                    "error" => Ok(RuleSetting::Severity(LintSeverity::Error)),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(SettingVisitor)
+    }
+}
+
+/// Dotted keys accepted by `OrbitonConfig::get_value` and `OrbitonConfig::set_value`
+pub const SETTABLE_KEYS: &[&str] = &[
+    "project.name",
+    "project.version",
+    "project.src_dir",
+    "project.dist_dir",
+    "project.entry_point",
+    "dev_server.port",
+    "dev_server.host",
+    "dev_server.auto_open",
+    "dev_server.max_ws_clients",
+    "dev_server.ws_ping_interval_secs",
+    "dev_server.ws_pong_timeout_secs",
+    "dev_server.open_path",
+    "dev_server.directory_listing",
+    "dev_server.index_file",
+    "hmr.enabled",
+    "hmr.debounce_ms",
+    "hmr.preserve_state",
+    "hmr.max_retries",
+    "hmr.show_notifications",
+    "hmr.inline_client",
+    "hmr.csp_nonce",
+    "build.use_beta_toolchain",
+    "build.release",
+    "build.target",
+    "build.opt_level",
+    "build.hash_assets",
+    "build.command",
+    "build.pre_rebuild_hook",
+    "build.post_rebuild_hook",
+    "build.hook_failure_blocks_reload",
+    "build.slow_build_warning_ms",
+    "lint.enabled",
+    "renderer.backend",
+];
+
+/// Parse a config value, reporting the key and expected type on failure
+fn parse_value<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid value for {key}: '{value}' (expected {})",
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+/// Parse an optional string value, treating an empty value as unset
+fn parse_optional(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct RendererConfig {
+    /// Rendering backend (default: auto)
+    #[serde(default)]
+    pub backend: Renderer,
+}
+
+/// Rendering backends supported by Orbit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Renderer {
+    Skia,
+    Wgpu,
+    #[default]
+    Auto,
+}
+
+impl std::str::FromStr for Renderer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "skia" => Ok(Self::Skia),
+            "wgpu" => Ok(Self::Wgpu),
+            "auto" => Ok(Self::Auto),
+            _ => Err(anyhow::anyhow!(
+                "Invalid renderer configuration: {s}. Valid options are: skia, wgpu, auto"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Renderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skia => write!(f, "skia"),
+            Self::Wgpu => write!(f, "wgpu"),
+            Self::Auto => write!(f, "auto"),
+        }
+    }
+}
+
 // Default value functions
 fn default_src_dir() -> String {
     "src".to_string()
@@ -172,6 +498,39 @@ fn default_max_retries() -> u32 {
 fn default_show_notifications() -> bool {
     true
 }
+fn default_watch_extensions() -> Vec<String> {
+    vec!["rs".to_string(), "orbit".to_string()]
+}
+fn default_full_reload_patterns() -> Vec<String> {
+    vec![
+        "Cargo.toml".to_string(),
+        "index.html".to_string(),
+        "web/**".to_string(),
+        "static/**".to_string(),
+        "assets/**".to_string(),
+    ]
+}
+fn default_max_ws_clients() -> usize {
+    32
+}
+fn default_ws_ping_interval() -> u64 {
+    30
+}
+fn default_ws_pong_timeout() -> u64 {
+    10
+}
+fn default_inline_client() -> bool {
+    false
+}
+fn default_open_path() -> String {
+    "/".to_string()
+}
+fn default_directory_listing() -> bool {
+    false
+}
+fn default_index_file() -> String {
+    "index.html".to_string()
+}
 fn default_lint_enabled() -> bool {
     true
 }
@@ -196,6 +555,12 @@ impl Default for DevServerConfig {
             auto_open: default_auto_open(),
             static_dirs: vec![],
             headers: HashMap::new(),
+            max_ws_clients: default_max_ws_clients(),
+            ws_ping_interval_secs: default_ws_ping_interval(),
+            ws_pong_timeout_secs: default_ws_pong_timeout(),
+            open_path: default_open_path(),
+            directory_listing: default_directory_listing(),
+            index_file: default_index_file(),
         }
     }
 }
@@ -214,6 +579,11 @@ impl Default for HmrConfig {
             preserve_state: default_preserve_state(),
             max_retries: default_max_retries(),
             show_notifications: default_show_notifications(),
+            watch_extensions: default_watch_extensions(),
+            full_reload_patterns: default_full_reload_patterns(),
+            watch_dirs: Vec::new(),
+            inline_client: default_inline_client(),
+            csp_nonce: None,
         }
     }
 }
@@ -235,145 +605,922 @@ impl OrbitonConfig {
     /// 1. Current directory
     /// 2. Parent directories (walking up the tree)
     /// 3. Uses default configuration if no file found
+    ///
+    /// If a profile is selected via `--profile` or `ORBITON_PROFILE`, it is
+    /// applied on top of the loaded configuration.
     pub fn load_from_project(project_dir: &Path) -> Result<Self> {
         let config_path = Self::find_config_file(project_dir);
 
-        match config_path {
-            Some(path) => Self::load_from_file(&path),
+        // Only the nearest file applies, which surprises users who expect
+        // settings from a parent directory to be merged in
+        if config_override().is_none() && crate::output::verbosity() == Verbosity::Verbose {
+            let ignored: Vec<String> = Self::find_config_files(project_dir)
+                .iter()
+                .skip(1)
+                .map(|path| path.display().to_string())
+                .collect();
+            if !ignored.is_empty() {
+                eprintln!(
+                    "{} Using {} and ignoring config files in parent directories: {}",
+                    style("Warning:").bold().yellow(),
+                    config_path.as_deref().unwrap_or(project_dir).display(),
+                    ignored.join(", ")
+                );
+            }
+        }
+
+        let config = match config_path {
+            Some(path) => Self::load_from_file(&path)?,
             None => {
-                println!("No .orbiton.toml found, using default configuration");
-                Ok(Self::default())
+                info!("No .orbiton.toml found, using default configuration");
+                Self::default()
             }
+        };
+
+        match std::env::var(PROFILE_ENV_VAR) {
+            Ok(profile) if !profile.is_empty() => config.with_profile(&profile),
+            _ => Ok(config),
         }
     }
 
+    /// Return a copy of this configuration with the named profile merged on top
+    pub fn with_profile(&self, name: &str) -> Result<Self> {
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            let mut available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            available.sort_unstable();
+
+            if available.is_empty() {
+                anyhow::anyhow!("Unknown profile '{name}': no profiles are defined")
+            } else {
+                anyhow::anyhow!(
+                    "Unknown profile '{name}'. Available profiles: {}",
+                    available.join(", ")
+                )
+            }
+        })?;
+
+        let mut config = self.clone();
+        // Profiles are meant to override the base, so conflicts are expected
+        let _ = config.merge_with(profile);
+        Ok(config)
+    }
+
     /// Load configuration from a specific file
+    ///
+    /// If the file `extends` a base config, the chain of base files is loaded
+    /// first and this file is merged on top.
     pub fn load_from_file(path: &Path) -> Result<Self> {
+        Self::load_with_extends(path, &mut Vec::new())
+    }
+
+    /// Load a config file on top of the files it extends
+    ///
+    /// `chain` holds the files currently being loaded, to detect cycles.
+    fn load_with_extends(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Self> {
+        let config = Self::read_file(path)?;
+        let Some(extends) = config.extends.clone() else {
+            return Ok(config);
+        };
+
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve config file: {}", path.display()))?;
+        chain.push(canonical);
+
+        // Each base is resolved relative to the file that extends it
+        let base_path = path.parent().unwrap_or(Path::new(".")).join(&extends);
+        let base_canonical = base_path.canonicalize().with_context(|| {
+            format!(
+                "Failed to find base config '{extends}' extended by {}",
+                path.display()
+            )
+        })?;
+        if chain.contains(&base_canonical) {
+            let cycle: Vec<String> = chain
+                .iter()
+                .chain(std::iter::once(&base_canonical))
+                .map(|path| path.display().to_string())
+                .collect();
+            return Err(anyhow::anyhow!(
+                "Config files extend each other in a cycle: {}",
+                cycle.join(" -> ")
+            ));
+        }
+
+        let mut base = Self::load_with_extends(&base_path, chain)?;
+        chain.pop();
+
+        base.merge_with(&config);
+        base.profiles.extend(config.profiles);
+        base.extends = Some(extends);
+        Ok(base)
+    }
+
+    /// Read and parse a single config file, without resolving `extends`
+    fn read_file(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-        let config: OrbitonConfig = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        let config = Self::parse(&content).map_err(|err| match err.downcast_ref() {
+            Some(toml_err) => anyhow::anyhow!(
+                "Failed to parse config file {} {}",
+                path.display(),
+                describe_toml_error(&content, toml_err)
+            ),
+            None => err.context(format!("Failed to parse config file: {}", path.display())),
+        })?;
 
-        println!("Loaded configuration from: {}", path.display());
+        info!("Loaded configuration from: {}", path.display());
         Ok(config)
     }
 
-    /// Find the nearest .orbiton.toml file by walking up the directory tree
-    pub fn find_config_file(start_dir: &Path) -> Option<PathBuf> {
-        let mut current_dir = start_dir;
+    /// Parse configuration from TOML, remembering which keys were explicitly set
+    ///
+    /// Parse errors are returned as [`toml::de::Error`], which points at the
+    /// offending input.
+    pub fn parse(content: &str) -> Result<Self> {
+        let table: toml::Table = toml::from_str(content)?;
+        // Deserialize from the text rather than the table so type errors keep their location
+        let mut config: OrbitonConfig = toml::from_str(content)?;
+        config.record_explicit_keys(&table);
+        Ok(config)
+    }
 
-        loop {
-            let config_path = current_dir.join(".orbiton.toml");
-            if config_path.exists() {
-                return Some(config_path);
+    /// Record the dotted keys present in a parsed TOML table
+    fn record_explicit_keys(&mut self, table: &toml::Table) {
+        for (section, value) in table {
+            match (section.as_str(), value) {
+                ("profiles", toml::Value::Table(profiles)) => {
+                    for (name, profile_value) in profiles {
+                        if let (Some(profile), toml::Value::Table(profile_table)) =
+                            (self.profiles.get_mut(name), profile_value)
+                        {
+                            profile.record_explicit_keys(profile_table);
+                        }
+                    }
+                }
+                (_, toml::Value::Table(fields)) => {
+                    for key in fields.keys() {
+                        self.explicit_keys.insert(format!("{section}.{key}"));
+                    }
+                }
+                _ => {
+                    self.explicit_keys.insert(section.clone());
+                }
             }
+        }
+    }
 
-            match current_dir.parent() {
-                Some(parent) => current_dir = parent,
-                None => return None,
-            }
+    /// Check whether a dotted key was explicitly present in the parsed file
+    #[allow(dead_code)] // Used in tests and by config tooling
+    pub fn is_explicit(&self, key: &str) -> bool {
+        self.explicit_keys.contains(key)
+    }
+
+    /// Find the nearest .orbiton.toml file by walking up the directory tree
+    ///
+    /// A file selected with `--config` is returned instead of searching.
+    pub fn find_config_file(start_dir: &Path) -> Option<PathBuf> {
+        if let Some(path) = config_override() {
+            return Some(path);
         }
+
+        Self::find_config_files(start_dir).into_iter().next()
+    }
+
+    /// Find every .orbiton.toml from `start_dir` up to the root, nearest first
+    pub fn find_config_files(start_dir: &Path) -> Vec<PathBuf> {
+        start_dir
+            .ancestors()
+            .map(|dir| dir.join(".orbiton.toml"))
+            .filter(|path| path.exists())
+            .collect()
     }
 
     /// Save configuration to a file
+    ///
+    /// An existing file is edited in place: only values that changed since it
+    /// was parsed are rewritten, so comments and formatting survive.
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
-        let content = toml::to_string_pretty(self).context("Failed to serialize configuration")?;
+        let content = if path.exists() {
+            let existing = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+            self.update_toml(&existing)
+                .with_context(|| format!("Failed to update config file: {}", path.display()))?
+        } else {
+            toml::to_string_pretty(self).context("Failed to serialize configuration")?
+        };
 
         fs::write(path, content)
             .with_context(|| format!("Failed to write config file: {}", path.display()))?;
 
-        println!("Configuration saved to: {}", path.display());
+        status!("Configuration saved to: {}", path.display());
         Ok(())
     }
 
+    /// Apply this configuration to the TOML document `existing`, keeping its formatting
+    fn update_toml(&self, existing: &str) -> Result<String> {
+        let mut doc: toml_edit::DocumentMut = existing.parse()?;
+
+        // Compare against what the file parses to, so defaults it leaves out stay out
+        let raw: toml::Table = toml::from_str(existing)?;
+        let old = match toml::Value::try_from(Self::parse(existing)?)? {
+            toml::Value::Table(table) => table,
+            _ => raw,
+        };
+        let toml::Value::Table(new) = toml::Value::try_from(self)? else {
+            return Err(anyhow::anyhow!(
+                "Configuration did not serialize to a table"
+            ));
+        };
+
+        update_table(doc.as_table_mut(), &old, &new, false)?;
+        Ok(doc.to_string())
+    }
+
+    /// Load the configuration that edits in `project_dir` should apply to
+    ///
+    /// Returns the nearest existing config file, or a default configuration
+    /// paired with the path where a new `.orbiton.toml` should be created.
+    pub fn load_for_update(project_dir: &Path) -> Result<(Self, PathBuf)> {
+        match Self::find_config_file(project_dir) {
+            // Edits are written back to this file alone, so don't flatten its bases into it
+            Some(path) => Ok((Self::read_file(&path)?, path)),
+            None => Ok((Self::default(), project_dir.join(".orbiton.toml"))),
+        }
+    }
+
     /// Create a default configuration file in the specified directory
     pub fn create_default_config(project_dir: &Path) -> Result<PathBuf> {
         let config_path = project_dir.join(".orbiton.toml");
-        let default_config = Self::default();
+        let content = toml::to_string_pretty(&Self::default())
+            .context("Failed to serialize configuration")?;
 
-        default_config.save_to_file(&config_path)?;
+        // Start from scratch rather than editing an existing file in place
+        fs::write(&config_path, content)
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
         Ok(config_path)
     }
     /// Merge with another configuration (other takes precedence)
-    #[allow(dead_code)] // Used in tests and maintenance operations
-    pub fn merge_with(&mut self, other: &OrbitonConfig) {
+    ///
+    /// A field in `other` is applied when it was explicitly present in its
+    /// source file or differs from the default, so an explicit `port = 3000`
+    /// still overrides. Returns every field that was set on both sides with
+    /// different values.
+    pub fn merge_with(&mut self, other: &OrbitonConfig) -> Vec<MergeConflict> {
+        let defaults = OrbitonConfig::default();
+        let base_keys = self.explicit_keys.clone();
+        let mut merger = FieldMerger {
+            base_keys: &base_keys,
+            other_keys: &other.explicit_keys,
+            conflicts: Vec::new(),
+        };
+
         // Merge project config
-        if other.project.name.is_some() {
-            self.project.name = other.project.name.clone();
-        }
-        if other.project.version.is_some() {
-            self.project.version = other.project.version.clone();
-        }
+        merger.field(
+            "project.name",
+            &mut self.project.name,
+            &other.project.name,
+            &defaults.project.name,
+        );
+        merger.field(
+            "project.version",
+            &mut self.project.version,
+            &other.project.version,
+            &defaults.project.version,
+        );
+        merger.field(
+            "project.src_dir",
+            &mut self.project.src_dir,
+            &other.project.src_dir,
+            &defaults.project.src_dir,
+        );
+        merger.field(
+            "project.dist_dir",
+            &mut self.project.dist_dir,
+            &other.project.dist_dir,
+            &defaults.project.dist_dir,
+        );
+        merger.field(
+            "project.entry_point",
+            &mut self.project.entry_point,
+            &other.project.entry_point,
+            &defaults.project.entry_point,
+        );
 
         // Merge dev server config
-        if other.dev_server.port != default_dev_port() {
-            self.dev_server.port = other.dev_server.port;
-        }
-        if other.dev_server.host != default_dev_host() {
-            self.dev_server.host = other.dev_server.host.clone();
+        merger.field(
+            "dev_server.port",
+            &mut self.dev_server.port,
+            &other.dev_server.port,
+            &defaults.dev_server.port,
+        );
+        merger.field(
+            "dev_server.host",
+            &mut self.dev_server.host,
+            &other.dev_server.host,
+            &defaults.dev_server.host,
+        );
+        merger.field(
+            "dev_server.auto_open",
+            &mut self.dev_server.auto_open,
+            &other.dev_server.auto_open,
+            &defaults.dev_server.auto_open,
+        );
+        merger.field(
+            "dev_server.static_dirs",
+            &mut self.dev_server.static_dirs,
+            &other.dev_server.static_dirs,
+            &defaults.dev_server.static_dirs,
+        );
+        merger.field(
+            "dev_server.max_ws_clients",
+            &mut self.dev_server.max_ws_clients,
+            &other.dev_server.max_ws_clients,
+            &defaults.dev_server.max_ws_clients,
+        );
+        merger.field(
+            "dev_server.ws_ping_interval_secs",
+            &mut self.dev_server.ws_ping_interval_secs,
+            &other.dev_server.ws_ping_interval_secs,
+            &defaults.dev_server.ws_ping_interval_secs,
+        );
+        merger.field(
+            "dev_server.ws_pong_timeout_secs",
+            &mut self.dev_server.ws_pong_timeout_secs,
+            &other.dev_server.ws_pong_timeout_secs,
+            &defaults.dev_server.ws_pong_timeout_secs,
+        );
+        merger.field(
+            "dev_server.open_path",
+            &mut self.dev_server.open_path,
+            &other.dev_server.open_path,
+            &defaults.dev_server.open_path,
+        );
+        merger.field(
+            "dev_server.directory_listing",
+            &mut self.dev_server.directory_listing,
+            &other.dev_server.directory_listing,
+            &defaults.dev_server.directory_listing,
+        );
+        merger.field(
+            "dev_server.index_file",
+            &mut self.dev_server.index_file,
+            &other.dev_server.index_file,
+            &defaults.dev_server.index_file,
+        );
+        for (name, value) in &other.dev_server.headers {
+            self.dev_server.headers.insert(name.clone(), value.clone());
         }
 
         // Merge HMR config
-        if !other.hmr.enabled {
-            self.hmr.enabled = other.hmr.enabled;
-        }
-        if other.hmr.debounce_ms != default_hmr_debounce() {
-            self.hmr.debounce_ms = other.hmr.debounce_ms;
-        }
+        merger.field(
+            "hmr.enabled",
+            &mut self.hmr.enabled,
+            &other.hmr.enabled,
+            &defaults.hmr.enabled,
+        );
+        merger.field(
+            "hmr.debounce_ms",
+            &mut self.hmr.debounce_ms,
+            &other.hmr.debounce_ms,
+            &defaults.hmr.debounce_ms,
+        );
+        merger.field(
+            "hmr.preserve_state",
+            &mut self.hmr.preserve_state,
+            &other.hmr.preserve_state,
+            &defaults.hmr.preserve_state,
+        );
+        merger.field(
+            "hmr.max_retries",
+            &mut self.hmr.max_retries,
+            &other.hmr.max_retries,
+            &defaults.hmr.max_retries,
+        );
+        merger.field(
+            "hmr.show_notifications",
+            &mut self.hmr.show_notifications,
+            &other.hmr.show_notifications,
+            &defaults.hmr.show_notifications,
+        );
+        merger.field(
+            "hmr.watch_extensions",
+            &mut self.hmr.watch_extensions,
+            &other.hmr.watch_extensions,
+            &defaults.hmr.watch_extensions,
+        );
+        merger.field(
+            "hmr.full_reload_patterns",
+            &mut self.hmr.full_reload_patterns,
+            &other.hmr.full_reload_patterns,
+            &defaults.hmr.full_reload_patterns,
+        );
+        merger.field(
+            "hmr.watch_dirs",
+            &mut self.hmr.watch_dirs,
+            &other.hmr.watch_dirs,
+            &defaults.hmr.watch_dirs,
+        );
+        merger.field(
+            "hmr.inline_client",
+            &mut self.hmr.inline_client,
+            &other.hmr.inline_client,
+            &defaults.hmr.inline_client,
+        );
+        merger.field(
+            "hmr.csp_nonce",
+            &mut self.hmr.csp_nonce,
+            &other.hmr.csp_nonce,
+            &defaults.hmr.csp_nonce,
+        );
 
         // Merge ignore patterns
-        if !other.hmr.ignore_patterns.is_empty() {
-            self.hmr
-                .ignore_patterns
-                .extend(other.hmr.ignore_patterns.clone());
+        for pattern in &other.hmr.ignore_patterns {
+            if !self.hmr.ignore_patterns.contains(pattern) {
+                self.hmr.ignore_patterns.push(pattern.clone());
+            }
         }
 
         // Merge build config
-        if other.build.use_beta_toolchain {
-            self.build.use_beta_toolchain = other.build.use_beta_toolchain;
-        }
-        if other.build.release {
-            self.build.release = other.build.release;
-        }
+        merger.field(
+            "build.use_beta_toolchain",
+            &mut self.build.use_beta_toolchain,
+            &other.build.use_beta_toolchain,
+            &defaults.build.use_beta_toolchain,
+        );
+        merger.field(
+            "build.release",
+            &mut self.build.release,
+            &other.build.release,
+            &defaults.build.release,
+        );
+        merger.field(
+            "build.target",
+            &mut self.build.target,
+            &other.build.target,
+            &defaults.build.target,
+        );
+        merger.field(
+            "build.features",
+            &mut self.build.features,
+            &other.build.features,
+            &defaults.build.features,
+        );
+        merger.field(
+            "build.exclude",
+            &mut self.build.exclude,
+            &other.build.exclude,
+            &defaults.build.exclude,
+        );
+        merger.field(
+            "build.opt_level",
+            &mut self.build.opt_level,
+            &other.build.opt_level,
+            &defaults.build.opt_level,
+        );
+        merger.field(
+            "build.hash_assets",
+            &mut self.build.hash_assets,
+            &other.build.hash_assets,
+            &defaults.build.hash_assets,
+        );
+        merger.field(
+            "build.command",
+            &mut self.build.command,
+            &other.build.command,
+            &defaults.build.command,
+        );
+        merger.field(
+            "build.pre_rebuild_hook",
+            &mut self.build.pre_rebuild_hook,
+            &other.build.pre_rebuild_hook,
+            &defaults.build.pre_rebuild_hook,
+        );
+        merger.field(
+            "build.post_rebuild_hook",
+            &mut self.build.post_rebuild_hook,
+            &other.build.post_rebuild_hook,
+            &defaults.build.post_rebuild_hook,
+        );
+        merger.field(
+            "build.hook_failure_blocks_reload",
+            &mut self.build.hook_failure_blocks_reload,
+            &other.build.hook_failure_blocks_reload,
+            &defaults.build.hook_failure_blocks_reload,
+        );
+        merger.field(
+            "build.slow_build_warning_ms",
+            &mut self.build.slow_build_warning_ms,
+            &other.build.slow_build_warning_ms,
+            &defaults.build.slow_build_warning_ms,
+        );
 
         // Merge lint config
-        if !other.lint.enabled {
-            self.lint.enabled = other.lint.enabled;
-        }
+        merger.field(
+            "lint.enabled",
+            &mut self.lint.enabled,
+            &other.lint.enabled,
+            &defaults.lint.enabled,
+        );
         for (rule, enabled) in &other.lint.rules {
             self.lint.rules.insert(rule.clone(), *enabled);
         }
+
+        // Merge renderer config
+        merger.field(
+            "renderer.backend",
+            &mut self.renderer.backend,
+            &other.renderer.backend,
+            &defaults.renderer.backend,
+        );
+
+        let conflicts = merger.conflicts;
+
+        // The merged config has every key that either side set explicitly
+        self.explicit_keys
+            .extend(other.explicit_keys.iter().cloned());
+
+        conflicts
+    }
+
+    /// Set a single value addressed by a dotted key (e.g. `dev_server.port`)
+    ///
+    /// The value is parsed according to the type of the addressed field.
+    /// An empty value clears optional fields.
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "project.name" => self.project.name = parse_optional(value),
+            "project.version" => self.project.version = parse_optional(value),
+            "project.src_dir" => self.project.src_dir = value.to_string(),
+            "project.dist_dir" => self.project.dist_dir = value.to_string(),
+            "project.entry_point" => self.project.entry_point = value.to_string(),
+            "dev_server.port" => self.dev_server.port = parse_value(key, value)?,
+            "dev_server.host" => self.dev_server.host = value.to_string(),
+            "dev_server.auto_open" => self.dev_server.auto_open = parse_value(key, value)?,
+            "dev_server.open_path" => self.dev_server.open_path = value.to_string(),
+            "dev_server.directory_listing" => {
+                self.dev_server.directory_listing = parse_value(key, value)?
+            }
+            "dev_server.index_file" => self.dev_server.index_file = value.to_string(),
+            "dev_server.ws_ping_interval_secs" => {
+                self.dev_server.ws_ping_interval_secs = parse_value(key, value)?
+            }
+            "dev_server.ws_pong_timeout_secs" => {
+                self.dev_server.ws_pong_timeout_secs = parse_value(key, value)?
+            }
+            "dev_server.max_ws_clients" => {
+                self.dev_server.max_ws_clients = parse_value(key, value)?
+            }
+            "hmr.enabled" => self.hmr.enabled = parse_value(key, value)?,
+            "hmr.debounce_ms" => self.hmr.debounce_ms = parse_value(key, value)?,
+            "hmr.preserve_state" => self.hmr.preserve_state = parse_value(key, value)?,
+            "hmr.max_retries" => self.hmr.max_retries = parse_value(key, value)?,
+            "hmr.show_notifications" => self.hmr.show_notifications = parse_value(key, value)?,
+            "hmr.inline_client" => self.hmr.inline_client = parse_value(key, value)?,
+            "hmr.csp_nonce" => self.hmr.csp_nonce = parse_optional(value),
+            "build.use_beta_toolchain" => self.build.use_beta_toolchain = parse_value(key, value)?,
+            "build.release" => self.build.release = parse_value(key, value)?,
+            "build.target" => self.build.target = parse_optional(value),
+            "build.opt_level" => self.build.opt_level = parse_optional(value),
+            "build.hash_assets" => {
+                self.build.hash_assets = match parse_optional(value) {
+                    Some(v) => Some(parse_value(key, &v)?),
+                    None => None,
+                }
+            }
+            "build.command" => {
+                self.build.command = parse_optional(value)
+                    .map(|command| shell_words::split(&command))
+                    .transpose()
+                    .with_context(|| format!("Invalid value for {key}: {value}"))?
+            }
+            "build.pre_rebuild_hook" => self.build.pre_rebuild_hook = parse_optional(value),
+            "build.post_rebuild_hook" => self.build.post_rebuild_hook = parse_optional(value),
+            "build.hook_failure_blocks_reload" => {
+                self.build.hook_failure_blocks_reload = parse_value(key, value)?
+            }
+            "build.slow_build_warning_ms" => {
+                self.build.slow_build_warning_ms = match parse_optional(value) {
+                    Some(v) => Some(parse_value(key, &v)?),
+                    None => None,
+                }
+            }
+            "lint.enabled" => self.lint.enabled = parse_value(key, value)?,
+            "renderer.backend" => self.renderer.backend = value.parse()?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown configuration key: {key}. Supported keys are:\n  {}",
+                    SETTABLE_KEYS.join("\n  ")
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a single value addressed by a dotted key, formatted for scripting
+    ///
+    /// Unset optional values are returned as an empty string.
+    pub fn get_value(&self, key: &str) -> Result<String> {
+        let value = match key {
+            "project.name" => self.project.name.clone().unwrap_or_default(),
+            "project.version" => self.project.version.clone().unwrap_or_default(),
+            "project.src_dir" => self.project.src_dir.clone(),
+            "project.dist_dir" => self.project.dist_dir.clone(),
+            "project.entry_point" => self.project.entry_point.clone(),
+            "dev_server.port" => self.dev_server.port.to_string(),
+            "dev_server.host" => self.dev_server.host.clone(),
+            "dev_server.auto_open" => self.dev_server.auto_open.to_string(),
+            "dev_server.max_ws_clients" => self.dev_server.max_ws_clients.to_string(),
+            "dev_server.ws_ping_interval_secs" => self.dev_server.ws_ping_interval_secs.to_string(),
+            "dev_server.ws_pong_timeout_secs" => self.dev_server.ws_pong_timeout_secs.to_string(),
+            "dev_server.open_path" => self.dev_server.open_path.to_string(),
+            "dev_server.directory_listing" => self.dev_server.directory_listing.to_string(),
+            "dev_server.index_file" => self.dev_server.index_file.clone(),
+            "hmr.enabled" => self.hmr.enabled.to_string(),
+            "hmr.debounce_ms" => self.hmr.debounce_ms.to_string(),
+            "hmr.preserve_state" => self.hmr.preserve_state.to_string(),
+            "hmr.max_retries" => self.hmr.max_retries.to_string(),
+            "hmr.show_notifications" => self.hmr.show_notifications.to_string(),
+            "hmr.inline_client" => self.hmr.inline_client.to_string(),
+            "hmr.csp_nonce" => self.hmr.csp_nonce.clone().unwrap_or_default(),
+            "build.use_beta_toolchain" => self.build.use_beta_toolchain.to_string(),
+            "build.release" => self.build.release.to_string(),
+            "build.target" => self.build.target.clone().unwrap_or_default(),
+            "build.opt_level" => self.build.opt_level.clone().unwrap_or_default(),
+            "build.hash_assets" => self
+                .build
+                .hash_assets
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            "build.command" => self
+                .build
+                .command
+                .as_ref()
+                .map(shell_words::join)
+                .unwrap_or_default(),
+            "build.pre_rebuild_hook" => self.build.pre_rebuild_hook.clone().unwrap_or_default(),
+            "build.post_rebuild_hook" => self.build.post_rebuild_hook.clone().unwrap_or_default(),
+            "build.hook_failure_blocks_reload" => self.build.hook_failure_blocks_reload.to_string(),
+            "build.slow_build_warning_ms" => self
+                .build
+                .slow_build_warning_ms
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            "lint.enabled" => self.lint.enabled.to_string(),
+            "renderer.backend" => self.renderer.backend.to_string(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown configuration key: {key}. Supported keys are:\n  {}",
+                    SETTABLE_KEYS.join("\n  ")
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// JSON Schema describing `.orbiton.toml`, for editor validation
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(OrbitonConfig))
+            .expect("Config schema is always serializable")
     }
 
     /// Validate configuration and return any errors
+    ///
+    /// All problems are collected and reported together rather than stopping
+    /// at the first one.
     pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
         // Validate port range
         if self.dev_server.port == 0 {
-            return Err(anyhow::anyhow!("Dev server port cannot be 0"));
+            errors.push("Dev server port cannot be 0".to_string());
+        }
+
+        if self.dev_server.max_ws_clients == 0 {
+            errors.push("Dev server max_ws_clients must be at least 1".to_string());
+        }
+
+        if self.dev_server.ws_ping_interval_secs == 0 {
+            errors.push("Dev server ws_ping_interval_secs must be at least 1".to_string());
+        }
+
+        // The index file is looked up inside each directory, so it must be a plain name
+        let index_file = &self.dev_server.index_file;
+        if matches!(index_file.as_str(), "" | "." | "..") || index_file.contains(['/', '\\']) {
+            errors.push(format!(
+                "Invalid dev server index_file: '{index_file}' (expected a file name like index.html)"
+            ));
         }
 
         // Validate paths exist
         let src_path = Path::new(&self.project.src_dir);
         if !src_path.exists() && self.project.src_dir != "src" {
-            return Err(anyhow::anyhow!(
+            errors.push(format!(
                 "Source directory does not exist: {}",
                 self.project.src_dir
             ));
         }
 
+        if normalize_dir(&self.project.dist_dir) == normalize_dir(&self.project.src_dir) {
+            errors.push(format!(
+                "Output directory cannot be the same as the source directory: {}",
+                self.project.dist_dir
+            ));
+        }
+
         // Validate HMR settings
         if self.hmr.debounce_ms > 5000 {
-            return Err(anyhow::anyhow!(
+            errors.push(format!(
                 "HMR debounce time too high (max 5000ms): {}",
                 self.hmr.debounce_ms
             ));
         }
 
-        Ok(())
+        if self.hmr.max_retries == 0 {
+            errors.push("HMR max_retries must be at least 1".to_string());
+        }
+
+        if let Some(nonce) = &self.hmr.csp_nonce {
+            let valid = !nonce.is_empty()
+                && nonce
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_'));
+            if !valid {
+                errors.push(format!(
+                    "Invalid HMR csp_nonce: {nonce} (expected base64 characters)"
+                ));
+            }
+        }
+
+        if let Err(e) = crate::utils::fs::glob_set(&self.hmr.full_reload_patterns) {
+            errors.push(format!("Invalid HMR full_reload_patterns: {e:#}"));
+        }
+
+        // Validate build settings
+        if let Some(opt_level) = &self.build.opt_level {
+            if !VALID_OPT_LEVELS.contains(&opt_level.as_str()) {
+                errors.push(format!(
+                    "Invalid build opt_level: {opt_level} (expected one of {})",
+                    VALID_OPT_LEVELS.join(", ")
+                ));
+            }
+        }
+
+        if let Some(target) = &self.build.target {
+            if !is_valid_target_triple(target) {
+                errors.push(format!(
+                    "Invalid build target: {target} (expected a target triple like wasm32-unknown-unknown)"
+                ));
+            }
+        }
+
+        if let Err(e) = crate::utils::fs::glob_set(&self.build.exclude) {
+            errors.push(format!("Invalid build exclude: {e:#}"));
+        }
+
+        if self
+            .build
+            .command
+            .as_ref()
+            .is_some_and(|command| command.is_empty())
+        {
+            errors.push("build.command must name a program to run".to_string());
+        }
+
+        // Validate static directories
+        let mut seen_dirs = std::collections::HashSet::new();
+        for dir in &self.dev_server.static_dirs {
+            if !seen_dirs.insert(normalize_dir(dir)) {
+                errors.push(format!("Duplicate static directory: {dir}"));
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(anyhow::anyhow!(errors.remove(0))),
+            n => Err(anyhow::anyhow!(
+                "{n} configuration errors:\n  - {}",
+                errors.join("\n  - ")
+            )),
+        }
     }
 }
 
+/// Optimization levels accepted by cargo's `opt-level`
+const VALID_OPT_LEVELS: &[&str] = &["0", "1", "2", "3", "s", "z"];
+
+/// Strip `./` prefixes and trailing slashes so equivalent directory names compare equal
+fn normalize_dir(dir: &str) -> &str {
+    dir.trim_start_matches("./").trim_end_matches('/')
+}
+
+/// Check that a target looks like `arch-vendor-os[-env]`
+fn is_valid_target_triple(target: &str) -> bool {
+    let parts: Vec<&str> = target.split('-').collect();
+    (2..=4).contains(&parts.len())
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        })
+}
+
+/// Describe a TOML error as `at line L, column C: message` followed by the offending line
+fn describe_toml_error(content: &str, err: &toml::de::Error) -> String {
+    let message = err.message().trim_end();
+    let Some(span) = err.span() else {
+        return format!(": {message}");
+    };
+
+    let start = span.start.min(content.len());
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |i| start + i);
+    let line_number = content[..start].matches('\n').count() + 1;
+    let column = content[line_start..start].chars().count() + 1;
+
+    let text = content[line_start..line_end].trim_end_matches('\r');
+    let width = content[start..span.end.clamp(start, line_end)]
+        .chars()
+        .count()
+        .max(1);
+    let gutter = " ".repeat(line_number.to_string().len());
+    format!(
+        "at line {line_number}, column {column}: {message}\n {gutter} |\n {line_number} | {text}\n {gutter} | {}{}",
+        " ".repeat(column - 1),
+        "^".repeat(width)
+    )
+}
+
+/// Rewrite the entries of `table` that differ between `old` and `new`
+///
+/// Entries the document has but `old` doesn't know about are left alone.
+fn update_table(
+    table: &mut dyn toml_edit::TableLike,
+    old: &toml::Table,
+    new: &toml::Table,
+    inline: bool,
+) -> Result<()> {
+    for (key, value) in new {
+        let previous = old.get(key);
+        if previous == Some(value) {
+            continue;
+        }
+
+        if let toml::Value::Table(new_child) = value {
+            let empty = toml::Table::new();
+            let old_child = match previous {
+                Some(toml::Value::Table(old_child)) => old_child,
+                _ => &empty,
+            };
+            if table.get(key).is_none() {
+                let child = if inline {
+                    toml_edit::Item::Value(toml_edit::InlineTable::new().into())
+                } else {
+                    toml_edit::Item::Table(toml_edit::Table::new())
+                };
+                table.insert(key, child);
+            }
+            match table.get_mut(key) {
+                Some(toml_edit::Item::Table(child)) => {
+                    update_table(child, old_child, new_child, false)?;
+                    continue;
+                }
+                Some(toml_edit::Item::Value(toml_edit::Value::InlineTable(child))) => {
+                    update_table(child, old_child, new_child, true)?;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let mut replacement: toml_edit::Value = value
+            .to_string()
+            .parse()
+            .with_context(|| format!("Failed to convert value of {key}"))?;
+        match table.get_mut(key) {
+            Some(toml_edit::Item::Value(current)) => {
+                *replacement.decor_mut() = current.decor().clone();
+                *current = replacement;
+            }
+            _ => {
+                table.insert(key, toml_edit::Item::Value(replacement));
+            }
+        }
+    }
+
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        table.remove(key);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -388,6 +1535,72 @@ mod tests {
         assert_eq!(config.hmr.debounce_ms, 100);
     }
 
+    #[test]
+    fn test_find_config_files_nearest_first() {
+        let temp_dir = tempdir().unwrap();
+        let outer = temp_dir.path().join("repo");
+        let inner = outer.join("apps/web");
+        fs::create_dir_all(inner.join("src")).unwrap();
+        fs::write(outer.join(".orbiton.toml"), "").unwrap();
+        fs::write(inner.join(".orbiton.toml"), "").unwrap();
+
+        let found = OrbitonConfig::find_config_files(&inner.join("src"));
+        assert_eq!(
+            found[..2],
+            [inner.join(".orbiton.toml"), outer.join(".orbiton.toml")]
+        );
+        assert_eq!(
+            OrbitonConfig::find_config_file(&inner.join("src")),
+            Some(inner.join(".orbiton.toml"))
+        );
+    }
+
+    #[test]
+    fn test_extends_chain() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let package_dir = root.join("packages/web");
+        fs::create_dir_all(&package_dir).unwrap();
+
+        fs::write(
+            root.join(".orbiton.base.toml"),
+            "[dev_server]\nport = 4000\n\n[hmr]\ndebounce_ms = 250\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("packages/.orbiton.shared.toml"),
+            "extends = \"../.orbiton.base.toml\"\n\n[dev_server]\nport = 5000\n",
+        )
+        .unwrap();
+        fs::write(
+            package_dir.join(".orbiton.toml"),
+            "extends = \"../.orbiton.shared.toml\"\n\n[dev_server]\nhost = \"0.0.0.0\"\n",
+        )
+        .unwrap();
+
+        let config = OrbitonConfig::load_from_file(&package_dir.join(".orbiton.toml")).unwrap();
+        assert_eq!(config.dev_server.host, "0.0.0.0");
+        assert_eq!(config.dev_server.port, 5000);
+        assert_eq!(config.hmr.debounce_ms, 250);
+
+        // Edits only see the package's own file
+        let (own, _) = OrbitonConfig::load_for_update(&package_dir).unwrap();
+        assert_eq!(own.dev_server.port, DevServerConfig::default().port);
+        assert_eq!(own.extends.as_deref(), Some("../.orbiton.shared.toml"));
+    }
+
+    #[test]
+    fn test_extends_cycle_is_rejected() {
+        let temp_dir = tempdir().unwrap();
+        let first = temp_dir.path().join("first.toml");
+        let second = temp_dir.path().join("second.toml");
+        fs::write(&first, "extends = \"second.toml\"\n").unwrap();
+        fs::write(&second, "extends = \"first.toml\"\n").unwrap();
+
+        let err = OrbitonConfig::load_from_file(&first).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = OrbitonConfig::default();
@@ -412,6 +1625,90 @@ mod tests {
         assert_eq!(config.dev_server.port, loaded_config.dev_server.port);
     }
 
+    #[test]
+    fn test_save_to_file_preserves_comments() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join(".orbiton.toml");
+        let original = r#"# Team settings, see the wiki
+[dev_server]
+port = 4000 # staging proxy expects this
+host = "0.0.0.0"
+
+[build]
+target = "wasm32-unknown-unknown"
+"#;
+        fs::write(&config_path, original).unwrap();
+
+        let mut config = OrbitonConfig::load_from_file(&config_path).unwrap();
+        config.dev_server.port = 5000;
+        config.build.target = None;
+        config.renderer.backend = Renderer::Wgpu;
+        config.save_to_file(&config_path).unwrap();
+
+        let saved = fs::read_to_string(&config_path).unwrap();
+        assert_eq!(
+            saved,
+            r#"# Team settings, see the wiki
+[dev_server]
+port = 5000 # staging proxy expects this
+host = "0.0.0.0"
+
+[build]
+
+[renderer]
+backend = "wgpu"
+"#
+        );
+
+        let loaded = OrbitonConfig::load_from_file(&config_path).unwrap();
+        assert_eq!(loaded.dev_server.port, 5000);
+        assert_eq!(loaded.renderer.backend, Renderer::Wgpu);
+        assert_eq!(loaded.build.target, None);
+    }
+
+    #[test]
+    fn test_lint_rules_accept_severities_and_booleans() {
+        let config = OrbitonConfig::parse(
+            r#"
+[lint.rules]
+required-sections = true
+duplicate-props = "warn"
+unused-style-classes = false
+"#,
+        )
+        .unwrap();
+        let severity = |rule: &str| config.lint.rules[rule].severity(LintSeverity::Error);
+        assert_eq!(severity("required-sections"), LintSeverity::Error);
+        assert_eq!(severity("duplicate-props"), LintSeverity::Warn);
+        assert_eq!(severity("unused-style-classes"), LintSeverity::Off);
+
+        let err = OrbitonConfig::parse("[lint.rules]\nduplicate-props = \"fatal\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains(r#"expected "off", "warn", "error", true or false"#));
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_line() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join(".orbiton.toml");
+
+        fs::write(
+            &config_path,
+            "[dev_server]\nhost = \"localhost\"\nport = = 3000\n",
+        )
+        .unwrap();
+        let err = OrbitonConfig::load_from_file(&config_path).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("at line 3, column 8"), "{message}");
+        assert!(message.contains("3 | port = = 3000"), "{message}");
+
+        // Type errors carry the location of the value too
+        fs::write(&config_path, "[dev_server]\nport = \"abc\"\n").unwrap();
+        let err = OrbitonConfig::load_from_file(&config_path).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("at line 2, column 8"), "{message}");
+        assert!(message.contains("       ^^^^^"), "{message}");
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = OrbitonConfig::default();
@@ -425,6 +1722,41 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_config_validation_accumulates_errors() {
+        let mut config = OrbitonConfig::default();
+        config.project.dist_dir = "./src/".to_string();
+        config.build.opt_level = Some("4".to_string());
+        config.build.target = Some("not a triple".to_string());
+        config.hmr.max_retries = 0;
+        config.dev_server.static_dirs = vec!["public".to_string(), "public/".to_string()];
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.starts_with("5 configuration errors"));
+        assert!(message.contains("same as the source directory"));
+        assert!(message.contains("Invalid build opt_level: 4"));
+        assert!(message.contains("Invalid build target: not a triple"));
+        assert!(message.contains("max_retries"));
+        assert!(message.contains("Duplicate static directory: public/"));
+
+        let mut config = OrbitonConfig::default();
+        config.build.opt_level = Some("z".to_string());
+        config.build.target = Some("thumbv7em-none-eabihf".to_string());
+        assert!(config.validate().is_ok());
+
+        config.build.target = Some("x86_64-unknown-linux-gnu".to_string());
+        assert!(config.validate().is_ok());
+
+        config.dev_server.index_file = "index.htm".to_string();
+        assert!(config.validate().is_ok());
+        config.dev_server.index_file = "../index.html".to_string();
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid dev server index_file"));
+    }
+
     #[test]
     fn test_config_merge() {
         let mut base_config = OrbitonConfig::default();
@@ -438,4 +1770,141 @@ mod tests {
         assert_eq!(base_config.dev_server.port, 8080);
         assert!(!base_config.hmr.enabled);
     }
+
+    #[test]
+    fn test_config_merge_explicit_default_value() {
+        let mut base = OrbitonConfig::parse("[dev_server]\nport = 8080").unwrap();
+        let other = OrbitonConfig::parse("[dev_server]\nport = 3000").unwrap();
+        assert!(other.is_explicit("dev_server.port"));
+
+        let conflicts = base.merge_with(&other);
+
+        // An explicit `port = 3000` overrides even though it equals the default
+        assert_eq!(base.dev_server.port, 3000);
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict {
+                key: "dev_server.port".to_string(),
+                base: "8080".to_string(),
+                other: "3000".to_string(),
+            }]
+        );
+
+        // Fields absent from the overriding file are left alone
+        let mut base = OrbitonConfig::parse("[dev_server]\nport = 8080").unwrap();
+        let other = OrbitonConfig::parse("[hmr]\nenabled = true").unwrap();
+        assert!(base.merge_with(&other).is_empty());
+        assert_eq!(base.dev_server.port, 8080);
+    }
+
+    #[test]
+    fn test_config_profiles() {
+        let config: OrbitonConfig = toml::from_str(
+            r#"
+            [dev_server]
+            port = 4000
+
+            [profiles.staging.dev_server]
+            port = 9000
+            host = "0.0.0.0"
+
+            [profiles.staging.build]
+            release = true
+            "#,
+        )
+        .unwrap();
+
+        let staging = config.with_profile("staging").unwrap();
+        assert_eq!(staging.dev_server.port, 9000);
+        assert_eq!(staging.dev_server.host, "0.0.0.0");
+        assert!(staging.build.release);
+
+        // The base configuration is left untouched
+        assert_eq!(config.dev_server.port, 4000);
+
+        let err = config.with_profile("production").unwrap_err();
+        assert!(err.to_string().contains("Available profiles: staging"));
+    }
+
+    #[test]
+    fn test_config_set_value() {
+        let mut config = OrbitonConfig::default();
+
+        config.set_value("dev_server.port", "8080").unwrap();
+        config.set_value("hmr.enabled", "false").unwrap();
+        config
+            .set_value("build.target", "wasm32-unknown-unknown")
+            .unwrap();
+        assert_eq!(config.dev_server.port, 8080);
+        assert!(!config.hmr.enabled);
+        assert_eq!(
+            config.build.target.as_deref(),
+            Some("wasm32-unknown-unknown")
+        );
+
+        config.set_value("build.target", "").unwrap();
+        assert_eq!(config.build.target, None);
+
+        // Quoted arguments stay together and read back the same way
+        config
+            .set_value("build.command", "sh -c 'npm run build'")
+            .unwrap();
+        assert_eq!(
+            config.build.command.as_deref(),
+            Some(["sh", "-c", "npm run build"].map(String::from).as_slice())
+        );
+        let command = config.get_value("build.command").unwrap();
+        config.set_value("build.command", &command).unwrap();
+        assert_eq!(config.build.command.as_ref().unwrap()[2], "npm run build");
+        assert!(config
+            .set_value("build.command", "sh -c 'unclosed")
+            .is_err());
+
+        assert!(config.set_value("dev_server.port", "not-a-port").is_err());
+        assert!(config.set_value("hmr.enabled", "yes please").is_err());
+        assert!(config.set_value("dev_server.nonexistent", "1").is_err());
+    }
+
+    #[test]
+    fn test_renderer_parsing() {
+        assert_eq!("skia".parse::<Renderer>().unwrap(), Renderer::Skia);
+        assert_eq!("WGPU".parse::<Renderer>().unwrap(), Renderer::Wgpu);
+        assert_eq!("auto".parse::<Renderer>().unwrap(), Renderer::Auto);
+        assert!("opengl".parse::<Renderer>().is_err());
+
+        let config: OrbitonConfig = toml::from_str("[renderer]\nbackend = \"wgpu\"").unwrap();
+        assert_eq!(config.renderer.backend, Renderer::Wgpu);
+        assert!(toml::from_str::<OrbitonConfig>("[renderer]\nbackend = \"opengl\"").is_err());
+
+        assert_eq!(OrbitonConfig::default().renderer.backend, Renderer::Auto);
+    }
+
+    #[test]
+    fn test_config_get_value() {
+        let mut config = OrbitonConfig::default();
+        assert_eq!(config.get_value("dev_server.port").unwrap(), "3000");
+        assert_eq!(config.get_value("hmr.enabled").unwrap(), "true");
+        assert_eq!(config.get_value("build.target").unwrap(), "");
+
+        // Every settable key can be read back
+        for key in SETTABLE_KEYS {
+            let value = config.get_value(key).unwrap();
+            config.set_value(key, &value).unwrap();
+        }
+
+        assert!(config.get_value("dev_server").is_err());
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = OrbitonConfig::json_schema();
+        assert!(schema["properties"]["dev_server"].is_object());
+
+        let port = &schema["definitions"]["DevServerConfig"]["properties"]["port"];
+        assert_eq!(port["type"], "integer");
+        assert_eq!(port["default"], 3000);
+
+        // Internal bookkeeping isn't part of the file format
+        assert!(schema["properties"].get("explicit_keys").is_none());
+    }
 }
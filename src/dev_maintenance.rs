@@ -1,10 +1,11 @@
 // Development server maintenance and monitoring utilities
 
 use crate::hmr::HmrContext;
-use std::path::PathBuf;
-use std::time::Duration;
 use console::style;
 use log::info;
+use serde::{Serialize, Serializer};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Maintenance utilities for the development server
 pub struct DevMaintenance {
@@ -19,10 +20,11 @@ impl DevMaintenance {
     }
 
     /// Perform maintenance cleanup on HMR context
+    #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn cleanup_stale_updates(&self, max_age: Duration) -> usize {
         let stale_modules = self.hmr_context.get_stale_updates(max_age);
         let count = stale_modules.len();
-        
+
         if count > 0 {
             println!(
                 "{} {} stale HMR modules older than {:?}",
@@ -30,18 +32,19 @@ impl DevMaintenance {
                 count,
                 max_age
             );
-            
+
             for module in &stale_modules {
                 info!("Cleaning stale module: {}", module);
             }
-            
+
             self.hmr_context.clear_stale_updates(max_age);
         }
-        
+
         count
     }
 
     /// Get development server status
+    #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn get_status(&self) -> DevStatus {
         let pending_updates = self.hmr_context.get_pending_updates();
         let oldest_update_age = self.hmr_context.get_oldest_update_age();
@@ -55,6 +58,7 @@ impl DevMaintenance {
     }
 
     /// Clear all pending updates (useful for reset)
+    #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn reset_hmr_state(&self) {
         println!("{} HMR state", style("Resetting").bold().blue());
         self.hmr_context.clear();
@@ -66,7 +70,7 @@ impl DevMaintenance {
         let pending = self.hmr_context.get_pending_updates();
         let stale_5min = self.hmr_context.get_stale_updates(Duration::from_secs(300));
         let stale_1min = self.hmr_context.get_stale_updates(Duration::from_secs(60));
-        
+
         HmrStats {
             total_pending: pending.len(),
             stale_1min: stale_1min.len(),
@@ -78,6 +82,7 @@ impl DevMaintenance {
 }
 
 /// Development server status
+#[allow(dead_code)] // Used in tests and maintenance operations
 #[derive(Debug)]
 pub struct DevStatus {
     pub pending_modules: Vec<String>,
@@ -86,33 +91,46 @@ pub struct DevStatus {
 }
 
 /// HMR statistics
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct HmrStats {
     pub total_pending: usize,
     pub stale_1min: usize,
     pub stale_5min: usize,
+    #[serde(rename = "oldest_age_secs", serialize_with = "serialize_age")]
     pub oldest_age: Option<Duration>,
     pub needs_rebuild: bool,
 }
 
+/// Serialize an optional age as fractional seconds
+fn serialize_age<S: Serializer>(age: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    age.map(|age| age.as_secs_f64()).serialize(serializer)
+}
+
+#[allow(dead_code)] // Used in tests and maintenance operations
 impl DevStatus {
     pub fn print_status(&self) {
-        println!("\n{}", style("Development Server Status").bold().underlined());
-        
+        println!(
+            "\n{}",
+            style("Development Server Status").bold().underlined()
+        );
+
         if self.needs_update {
-            println!("  Status: {} (needs rebuild)", style("Pending changes").yellow());
+            println!(
+                "  Status: {} (needs rebuild)",
+                style("Pending changes").yellow()
+            );
         } else {
             println!("  Status: {}", style("Up to date").green());
         }
-        
+
         println!("  Pending modules: {}", self.pending_modules.len());
-        
+
         if !self.pending_modules.is_empty() {
             for module in &self.pending_modules {
                 println!("    - {}", style(module).cyan());
             }
         }
-        
+
         if let Some(age) = self.oldest_update_age {
             println!("  Oldest change: {:?} ago", age);
         }
@@ -122,56 +140,65 @@ impl DevStatus {
 impl HmrStats {
     pub fn print_stats(&self) {
         println!("\n{}", style("HMR Statistics").bold().underlined());
-        println!("  Total pending modules: {}", style(self.total_pending).cyan());
+        println!(
+            "  Total pending modules: {}",
+            style(self.total_pending).cyan()
+        );
         println!("  Stale (1 min): {}", style(self.stale_1min).yellow());
         println!("  Stale (5 min): {}", style(self.stale_5min).red());
-        
+
         if let Some(age) = self.oldest_age {
             println!("  Oldest update: {:?} ago", age);
         }
-        
-        println!("  Needs rebuild: {}", 
-                if self.needs_rebuild { 
-                    style("Yes").red() 
-                } else { 
-                    style("No").green() 
-                });
+
+        println!(
+            "  Needs rebuild: {}",
+            if self.needs_rebuild {
+                style("Yes").red()
+            } else {
+                style("No").green()
+            }
+        );
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::tempdir;
     use std::fs;
+    use tempfile::tempdir;
 
     #[test]
     fn test_dev_maintenance() {
         let temp_dir = tempdir().unwrap();
         let project_root = temp_dir.path().to_path_buf();
-        
+
         // Create project structure
         let src_dir = project_root.join("src");
         fs::create_dir_all(&src_dir).unwrap();
-        
+
         let test_file = src_dir.join("test.rs");
         fs::write(&test_file, "// test content").unwrap();
-        
+
         let maintenance = DevMaintenance::new(project_root);
-        
+
         // Simulate some file changes
         maintenance.hmr_context.record_file_change(&test_file);
-        
+
         // Test status
         let status = maintenance.get_status();
         assert!(status.needs_update);
         assert_eq!(status.pending_modules.len(), 1);
-        
+
         // Test stats
         let stats = maintenance.get_hmr_stats();
         assert_eq!(stats.total_pending, 1);
         assert!(stats.needs_rebuild);
-        
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["total_pending"], 1);
+        assert!(json["oldest_age_secs"].is_f64());
+
         // Test reset
         maintenance.reset_hmr_state();
         let status_after_reset = maintenance.get_status();
@@ -183,23 +210,24 @@ mod tests {
     fn test_stale_cleanup() {
         let temp_dir = tempdir().unwrap();
         let project_root = temp_dir.path().to_path_buf();
-        
+
         let src_dir = project_root.join("src");
         fs::create_dir_all(&src_dir).unwrap();
-        
+
         let test_file = src_dir.join("test.rs");
         fs::write(&test_file, "// test content").unwrap();
-        
+
         let maintenance = DevMaintenance::new(project_root);
-        
+
         // Record file change
         maintenance.hmr_context.record_file_change(&test_file);
-        
+
         // Cleanup stale updates (should be 0 since update is fresh)
         let cleaned = maintenance.cleanup_stale_updates(Duration::from_secs(1));
         assert_eq!(cleaned, 0);
-        
+
         // Cleanup very fresh updates
+        std::thread::sleep(Duration::from_millis(5));
         let cleaned = maintenance.cleanup_stale_updates(Duration::from_millis(1));
         assert_eq!(cleaned, 1);
     }
@@ -1,47 +1,211 @@
 // Development server for the Orbit UI framework
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use futures_util::{future, SinkExt, StreamExt};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    collections::{HashMap, HashSet},
+    io::Read,
+    net::SocketAddr,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
     thread,
+    time::{Duration, Instant, SystemTime},
 };
+use tiny_http::Method;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::broadcast;
-use tokio_tungstenite::{accept_async, tungstenite::protocol::Message};
+use tokio::sync::{broadcast, Semaphore};
+use tokio_tungstenite::{
+    accept_async,
+    tungstenite::protocol::{frame::coding::CloseCode, CloseFrame, Message},
+};
 
+use crate::config::OrbitonConfig;
+use crate::hmr::protocol::{self, DevMessage};
 use crate::hmr::HmrContext;
-use crate::hmr_inject::{get_hmr_client_js, is_html_file, process_html_file};
+use crate::hmr_inject::{
+    get_hmr_client_js, inject_hmr_client_with_options, is_html_file, InjectOptions, HMR_CONFIG_PATH,
+};
+
+/// Message sent to WebSocket clients, optionally limited to some client ids
+#[derive(Debug, Clone)]
+struct ClientMessage {
+    text: String,
+    targets: Option<HashSet<u64>>,
+}
+
+impl ClientMessage {
+    fn is_for(&self, client_id: u64) -> bool {
+        self.targets
+            .as_ref()
+            .is_none_or(|targets| targets.contains(&client_id))
+    }
+}
+
+/// Connected WebSocket clients and the page URL each one registered
+#[derive(Debug, Default)]
+struct ClientRegistry {
+    next_id: AtomicU64,
+    urls: Mutex<HashMap<u64, Option<String>>>,
+}
+
+impl ClientRegistry {
+    fn connect(&self) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.urls.lock().unwrap().insert(id, None);
+        id
+    }
+
+    fn register(&self, id: u64, url: &str) {
+        if let Some(entry) = self.urls.lock().unwrap().get_mut(&id) {
+            *entry = Some(url.to_string());
+        }
+    }
+
+    fn disconnect(&self, id: u64) {
+        self.urls.lock().unwrap().remove(&id);
+    }
+
+    /// Number of clients that connected since the server started
+    fn total_connections(&self) -> u64 {
+        self.next_id.load(Ordering::Relaxed)
+    }
+
+    /// Ids of clients whose registered URL matches, plus clients that never registered
+    fn matching(&self, predicate: impl Fn(&str) -> bool) -> HashSet<u64> {
+        self.urls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, url)| url.as_deref().is_none_or(&predicate))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// Limits applied to HMR WebSocket connections
+#[derive(Debug, Clone, Copy)]
+struct WebSocketSettings {
+    /// Maximum number of concurrent clients
+    max_clients: usize,
+    /// Time between heartbeat pings
+    ping_interval: Duration,
+    /// Time a client has to answer a ping
+    pong_timeout: Duration,
+}
+
+impl WebSocketSettings {
+    fn from_config(config: &OrbitonConfig) -> Self {
+        Self {
+            max_clients: config.dev_server.max_ws_clients,
+            ping_interval: Duration::from_secs(config.dev_server.ws_ping_interval_secs),
+            pong_timeout: Duration::from_secs(config.dev_server.ws_pong_timeout_secs),
+        }
+    }
+}
+
+/// How the server treats incoming requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServeMode {
+    /// Inject the HMR client and push updates over WebSocket
+    Dev,
+    /// Serve a production build with production-like headers
+    Preview,
+}
+
+/// State shared by the HTTP request handler
+struct RequestContext {
+    /// Directory static files are served from
+    root: PathBuf,
+    /// HTTP port, used to locate the WebSocket server
+    port: u16,
+    mode: ServeMode,
+    config: Arc<RwLock<OrbitonConfig>>,
+    html_cache: Arc<HtmlCache>,
+}
+
+/// Most pages kept in the [`HtmlCache`] before it starts over
+const HTML_CACHE_CAPACITY: usize = 64;
+
+/// HTML pages with the HMR client injected, keyed by path and modification time
+///
+/// Pages are requested on every reload during HMR cycles, so the injection is
+/// only redone when the file or the configuration changes.
+#[derive(Debug, Default)]
+struct HtmlCache {
+    pages: Mutex<HashMap<PathBuf, (SystemTime, Vec<u8>)>>,
+}
+
+impl HtmlCache {
+    /// The cached page for `path`, if it was injected from the file as modified at `modified`
+    fn get(&self, path: &Path, modified: SystemTime) -> Option<Vec<u8>> {
+        let pages = self.pages.lock().unwrap();
+        let (cached_at, body) = pages.get(path)?;
+        (*cached_at == modified).then(|| body.clone())
+    }
+
+    fn insert(&self, path: PathBuf, modified: SystemTime, body: Vec<u8>) {
+        let mut pages = self.pages.lock().unwrap();
+        if pages.len() >= HTML_CACHE_CAPACITY && !pages.contains_key(&path) {
+            pages.clear();
+        }
+        pages.insert(path, (modified, body));
+    }
+
+    fn clear(&self) {
+        self.pages.lock().unwrap().clear();
+    }
+}
 
 /// Development server
 pub struct DevServer {
+    /// Host address to bind to
+    host: String,
     /// Port to use for the server
     port: u16,
     /// Project directory
     project_dir: PathBuf,
-    /// Server thread handle
-    #[allow(dead_code)]
-    thread_handle: Option<thread::JoinHandle<()>>,
+    /// Server thread handle, yielding the error that stopped the server
+    thread_handle: Option<ServerThread>,
     /// Broadcast channel for sending updates to connected clients
-    tx: Option<broadcast::Sender<String>>,
+    tx: Option<broadcast::Sender<ClientMessage>>,
+    /// Connected WebSocket clients
+    clients: Arc<ClientRegistry>,
     /// Use beta toolchain for building and testing
     use_beta: bool,
     /// HMR context for tracking changed modules
     hmr_context: Arc<HmrContext>,
+    /// Live configuration, updated when .orbiton.toml changes
+    config: Arc<RwLock<OrbitonConfig>>,
+    /// Whether to serve with HMR or as a production preview
+    mode: ServeMode,
+    /// Total number of HTTP requests received
+    requests: Arc<AtomicU64>,
+    /// Total number of HMR updates sent
+    hmr_updates: Arc<AtomicU64>,
+    /// HTML pages with the HMR client injected
+    html_cache: Arc<HtmlCache>,
 }
 
 impl Clone for DevServer {
     fn clone(&self) -> Self {
         Self {
+            host: self.host.clone(),
             port: self.port,
             project_dir: self.project_dir.clone(),
             thread_handle: None, // Don't clone the thread handle
             tx: self.tx.clone(),
+            clients: Arc::clone(&self.clients),
             use_beta: self.use_beta,
             hmr_context: Arc::clone(&self.hmr_context),
+            config: Arc::clone(&self.config),
+            mode: self.mode,
+            requests: Arc::clone(&self.requests),
+            hmr_updates: Arc::clone(&self.hmr_updates),
+            html_cache: Arc::clone(&self.html_cache),
         }
     }
 }
@@ -50,153 +214,278 @@ impl DevServer {
     /// Create a new development server
     #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn new(port: u16, project_dir: &Path) -> Result<Self> {
-        let (tx, _) = broadcast::channel(16);
-        let hmr_context = Arc::new(HmrContext::new(project_dir.to_owned()));
-
-        Ok(Self {
-            port,
-            project_dir: project_dir.to_owned(),
-            thread_handle: None,
-            tx: Some(tx),
-            use_beta: false,
-            hmr_context,
-        })
+        Self::new_with_options(port, project_dir, false)
     }
 
     /// Create a new development server with optional beta toolchain support
+    #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn new_with_options(port: u16, project_dir: &Path, use_beta: bool) -> Result<Self> {
+        let mut config = OrbitonConfig::default();
+        config.dev_server.port = port;
+        config.build.use_beta_toolchain = use_beta;
+
+        Self::from_config(project_dir, &config)
+    }
+
+    /// Create a new development server from a loaded configuration
+    pub fn from_config(project_dir: &Path, config: &OrbitonConfig) -> Result<Self> {
         let (tx, _) = broadcast::channel(16);
         let hmr_context = Arc::new(HmrContext::new(project_dir.to_owned()));
+        hmr_context.configure(&config.hmr)?;
 
         Ok(Self {
-            port,
+            host: config.dev_server.host.clone(),
+            port: config.dev_server.port,
             project_dir: project_dir.to_owned(),
             thread_handle: None,
             tx: Some(tx),
-            use_beta,
+            clients: Arc::default(),
+            use_beta: config.build.use_beta_toolchain,
             hmr_context,
+            config: Arc::new(RwLock::new(config.clone())),
+            mode: ServeMode::Dev,
+            requests: Arc::default(),
+            hmr_updates: Arc::default(),
+            html_cache: Arc::default(),
         })
     }
 
+    /// Create a server that previews a production build in `root`
+    ///
+    /// Files are served as-is: no WebSocket server is started and no HMR
+    /// client is injected into HTML pages.
+    pub fn preview(root: &Path, config: &OrbitonConfig) -> Result<Self> {
+        let mut config = config.clone();
+        config.hmr.enabled = false;
+
+        let mut server = Self::from_config(root, &config)?;
+        server.mode = ServeMode::Preview;
+        Ok(server)
+    }
+
+    /// Get a snapshot of the live configuration
+    pub fn config(&self) -> OrbitonConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Replace the live configuration
+    ///
+    /// Settings such as custom headers, ignore patterns, debounce time and
+    /// whether HMR is enabled take effect immediately. The listening port and
+    /// host are only read at startup.
+    pub fn update_config(&self, config: OrbitonConfig) {
+        *self.config.write().unwrap() = config;
+        // Injection options may have changed
+        self.html_cache.clear();
+    }
+
+    /// Forget the HTML pages served with the HMR client injected
+    ///
+    /// Pages are also re-injected when their modification time changes; this
+    /// covers edits that land within the file system's timestamp resolution.
+    pub fn invalidate_html_cache(&self) {
+        self.html_cache.clear();
+    }
+
     /// Check if the dev server is using beta toolchain
     pub fn is_using_beta(&self) -> bool {
         self.use_beta
     }
 
+    /// Get the host the server binds to
+    #[allow(dead_code)] // Used in tests and maintenance operations
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
     /// Get the server port
+    ///
+    /// A configured port of 0 is replaced by the actual port once started.
     #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn port(&self) -> u16 {
         self.port
     }
 
+    /// Get the total number of HTTP requests received
+    pub fn request_count(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Get the total number of HMR updates sent
+    pub fn hmr_update_count(&self) -> u64 {
+        self.hmr_updates.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of WebSocket clients that connected since the server started
+    pub fn client_connection_count(&self) -> u64 {
+        self.clients.total_connections()
+    }
+
     /// Get the HMR context
     pub fn hmr_context(&self) -> &Arc<HmrContext> {
         &self.hmr_context
     }
 
     /// Start the development server
-    pub fn start(&mut self) -> Result<&thread::JoinHandle<()>> {
+    pub fn start(&mut self) -> Result<&ServerThread> {
+        let (handle, _http) = self.spawn()?;
+        self.thread_handle = Some(handle);
+        Ok(self.thread_handle.as_ref().unwrap())
+    }
+
+    /// Block until the server started with [`DevServer::start`] stops
+    ///
+    /// The server only stops on its own when it fails, e.g. because the
+    /// WebSocket server could not bind, so an error is returned in that case.
+    pub fn wait(&mut self) -> Result<()> {
+        match self.thread_handle.take() {
+            Some(thread) => join_server_thread(thread),
+            None => Ok(()),
+        }
+    }
+
+    /// Start the server and return a handle that stops it
+    ///
+    /// Unlike [`DevServer::start`], the server can be shut down cleanly, which
+    /// makes it usable from tests and tools embedding orbiton.
+    pub fn start_detached(&mut self) -> Result<DevServerHandle> {
+        let (thread, http) = self.spawn()?;
+        Ok(DevServerHandle {
+            thread: Some(thread),
+            http,
+            port: self.port,
+        })
+    }
+
+    /// Bind the servers and spawn the thread running them
+    ///
+    /// Returns the thread along with the HTTP server, which stops the thread
+    /// when unblocked.
+    fn spawn(&mut self) -> Result<(ServerThread, Arc<tiny_http::Server>)> {
+        // Port 0 picks any free port, like binding a socket would
+        if self.port == 0 {
+            self.port = find_free_port(&self.host, self.mode)?;
+        }
+        check_ports_available(&self.host, self.port, self.mode)?;
+        self.spawn_on_port()
+    }
+
+    /// Bind the HTTP server on `self.port` and spawn the thread running the servers
+    ///
+    /// Failures inside the thread, including panics, end it with an error
+    /// instead of leaving the CLI running without a server.
+    fn spawn_on_port(&mut self) -> Result<(ServerThread, Arc<tiny_http::Server>)> {
+        let host = self.host.clone();
+        let mode = self.mode;
         let port = self.port;
-        let project_dir = self.project_dir.clone();
-        let tx = self.tx.take().expect("Missing broadcast channel");
+
+        // Keep our own sender so updates can still be broadcast after starting
+        let tx = self.tx.clone().expect("Missing broadcast channel");
+        let clients = Arc::clone(&self.clients);
+        let requests = Arc::clone(&self.requests);
+        let ws_settings = WebSocketSettings::from_config(&self.config.read().unwrap());
+        let context = RequestContext {
+            root: self.project_dir.clone(),
+            port,
+            mode,
+            config: Arc::clone(&self.config),
+            html_cache: Arc::clone(&self.html_cache),
+        };
+
+        let address = bind_address(&host, port);
+        let server = tiny_http::Server::http(&address)
+            .map_err(|e| anyhow::anyhow!("Failed to start HTTP server on {address}: {e}"))?;
+        let server = Arc::new(server);
+        let http = Arc::clone(&server);
 
         let handle = thread::spawn(move || {
-            // Set up the Tokio runtime
-            let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
-
-            rt.block_on(async {
-                // Start WebSocket server
-                let ws_rx = tx.subscribe();
-                let ws_handle = tokio::spawn(Self::run_websocket_server(port, ws_rx));
-
-                // Start HTTP server
-                let server = tiny_http::Server::http(format!("0.0.0.0:{port}"))
-                    .expect("Failed to start HTTP server");
-
-                info!("Development server started on port {port}");
-                info!("WebSocket server started on port {}", port + 1);
-
-                let _broadcast_tx = tx; // Keep tx alive
-
-                for request in server.incoming_requests() {
-                    debug!("Received request: {url:?}", url = request.url());
-
-                    // Special handling for HMR client script
-                    if request.url() == "/__orbit_hmr_client.js" {
-                        debug!("Serving HMR client script");
-                        let response = tiny_http::Response::from_string(get_hmr_client_js())
-                            .with_header(
-                                tiny_http::Header::from_bytes(
-                                    &b"Content-Type"[..],
-                                    &b"application/javascript"[..],
-                                )
-                                .unwrap(),
-                            );
-                        let _ = request.respond(response);
-                        continue;
-                    }
+            let run = || -> Result<()> {
+                // Set up the Tokio runtime
+                let rt =
+                    tokio::runtime::Runtime::new().context("Failed to create Tokio runtime")?;
+                rt.block_on(async {
+                    // A failed WebSocket server stops the HTTP server too, so the
+                    // failure is reported rather than leaving HMR silently broken
+                    let (ws_failure_tx, ws_failure) = std::sync::mpsc::channel();
 
-                    // Handle static files
-                    let url = request.url().trim_start_matches('/');
-                    let file_path = if url.is_empty() {
-                        project_dir.join("index.html")
-                    } else {
-                        project_dir.join(url)
-                    };
-
-                    if file_path.exists() && file_path.is_file() {
-                        // Special handling for HTML files to inject HMR client
-                        if is_html_file(&file_path) {
-                            debug!("Processing HTML file: {:?}", file_path);
-                            match process_html_file(&file_path, port) {
-                                Ok(content) => {
-                                    let response = tiny_http::Response::from_data(content)
-                                        .with_header(
-                                            tiny_http::Header::from_bytes(
-                                                &b"Content-Type"[..],
-                                                &b"text/html"[..],
-                                            )
-                                            .unwrap(),
-                                        );
-                                    let _ = request.respond(response);
-                                }
-                                Err(e) => {
-                                    error!("Failed to process HTML file: {}", e);
-                                    // Fall back to serving the file without injection
-                                    let file = std::fs::File::open(&file_path)
-                                        .expect("Failed to open file");
-                                    let response = tiny_http::Response::from_file(file);
-                                    let _ = request.respond(response);
-                                }
+                    // Start WebSocket server (previews don't hot reload)
+                    let ws_handle = (mode == ServeMode::Dev).then(|| {
+                        let rx = tx.subscribe();
+                        let http = Arc::clone(&server);
+                        tokio::spawn(async move {
+                            let result =
+                                Self::run_websocket_server(host, port, rx, ws_settings, clients)
+                                    .await;
+                            if let Err(e) = result {
+                                let _ = ws_failure_tx.send(e);
+                                http.unblock();
                             }
-                        } else {
-                            // Serve non-HTML files normally
-                            let file =
-                                std::fs::File::open(&file_path).expect("Failed to open file");
-                            let response = tiny_http::Response::from_file(file);
-                            let _ = request.respond(response);
-                        }
-                    } else {
-                        // File not found, return 404
-                        let response = tiny_http::Response::from_string("File not found")
-                            .with_status_code(404);
-                        let _ = request.respond(response);
+                        })
+                    });
+
+                    info!(
+                        "{} server started on {}",
+                        match mode {
+                            ServeMode::Dev => "Development",
+                            ServeMode::Preview => "Preview",
+                        },
+                        address
+                    );
+
+                    let _broadcast_tx = tx; // Keep tx alive
+
+                    for request in server.incoming_requests() {
+                        let started = Instant::now();
+                        let method = request.method().clone();
+                        let url = request.url().to_string();
+
+                        let count = requests.fetch_add(1, Ordering::Relaxed) + 1;
+                        // A panicking request drops its connection but keeps the server up
+                        let served = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            serve_request(request, &context)
+                        }));
+                        let Ok(status) = served else {
+                            error!("Request {method} {url} panicked; the connection was dropped");
+                            continue;
+                        };
+                        debug!(
+                            "{method} {url} {status} {:.1?} (request #{count})",
+                            started.elapsed()
+                        );
                     }
-                }
 
-                // Wait for WebSocket server to finish
-                let _ = ws_handle.await;
-            });
+                    // The HTTP server only stops when shut down; take the WebSocket
+                    // server with it. Open connections end when the runtime drops.
+                    if let Some(ws_handle) = ws_handle {
+                        ws_handle.abort();
+                    }
+                    match ws_failure.try_recv() {
+                        Ok(e) => Err(e),
+                        Err(_) => Ok(()),
+                    }
+                })
+            };
+
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(run)).unwrap_or_else(|panic| {
+                Err(anyhow::anyhow!(
+                    "Dev server thread panicked: {}",
+                    panic_message(panic.as_ref())
+                ))
+            })
         });
 
-        self.thread_handle = Some(handle);
-        Ok(self.thread_handle.as_ref().unwrap())
+        Ok((handle, http))
     }
 
     /// Send an update to all connected WebSocket clients
     pub fn broadcast_update(&self, message: String) -> Result<()> {
+        self.send_to_clients(message, None)
+    }
+
+    /// Send a message to the given clients, or to every client when `targets` is `None`
+    fn send_to_clients(&self, text: String, targets: Option<HashSet<u64>>) -> Result<()> {
         if let Some(tx) = &self.tx {
-            tx.send(message)
+            tx.send(ClientMessage { text, targets })
                 .map_err(|e| anyhow::anyhow!("Failed to broadcast message: {}", e))?;
         }
         Ok(())
@@ -204,65 +493,148 @@ impl DevServer {
 
     /// Trigger an HMR update for specific modules
     pub fn send_hmr_update(&self, modules: Vec<String>) -> Result<()> {
-        let message = serde_json::json!({
-            "type": "hmr",
-            "modules": modules
-        })
-        .to_string();
+        let message = self.hmr_message(modules);
 
+        self.hmr_updates.fetch_add(1, Ordering::Relaxed);
         self.broadcast_update(message)
     }
 
+    /// Trigger an HMR update only for clients whose registered page URL matches
+    ///
+    /// Clients that have not registered a URL yet always receive the update.
+    #[allow(dead_code)] // Used in tests and maintenance operations
+    pub fn send_hmr_update_to(
+        &self,
+        modules: Vec<String>,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        let message = self.hmr_message(modules);
+
+        let targets = self.clients.matching(predicate);
+        self.hmr_updates.fetch_add(1, Ordering::Relaxed);
+        self.send_to_clients(message, Some(targets))
+    }
+
+    /// An HMR update for `modules`, telling clients whether to keep component state
+    fn hmr_message(&self, modules: Vec<String>) -> String {
+        DevMessage::Hmr {
+            modules,
+            preserve_state: self.config.read().unwrap().hmr.preserve_state,
+        }
+        .to_json()
+    }
+
     /// Trigger a full page reload for all clients
     pub fn send_reload_command(&self) -> Result<()> {
-        let message = serde_json::json!({
-            "type": "reload"
-        })
-        .to_string();
-
-        self.broadcast_update(message)
+        self.broadcast_update(DevMessage::Reload.to_json())
     }
 
     /// Send rebuild status to all clients
     pub fn send_rebuild_status(&self, status: &str) -> Result<()> {
-        let message = serde_json::json!({
-            "type": "rebuild",
-            "status": status
-        })
-        .to_string();
+        let message = DevMessage::Rebuild {
+            status: status.to_string(),
+            duration_ms: None,
+            output: None,
+        };
 
-        self.broadcast_update(message)
+        self.broadcast_update(message.to_json())
+    }
+
+    /// Send the final rebuild status with the build duration and, optionally, its output
+    ///
+    /// The output should already be stripped of ANSI escape codes.
+    pub fn send_rebuild_finished(
+        &self,
+        status: &str,
+        duration: Duration,
+        output: Option<&str>,
+    ) -> Result<()> {
+        let message = DevMessage::Rebuild {
+            status: status.to_string(),
+            duration_ms: Some(duration.as_millis() as u64),
+            output: output.map(str::to_string),
+        };
+
+        self.broadcast_update(message.to_json())
+    }
+
+    /// Tell clients the build could not run, e.g. because a hook failed
+    pub fn send_build_error(&self, error: &str) -> Result<()> {
+        let message = DevMessage::BuildError {
+            message: error.to_string(),
+        };
+
+        self.broadcast_update(message.to_json())
     }
 
     async fn handle_websocket_connection(
         ws_stream: tokio_tungstenite::WebSocketStream<TcpStream>,
         addr: SocketAddr,
-        mut rx: broadcast::Receiver<String>,
+        mut rx: broadcast::Receiver<ClientMessage>,
+        clients: Arc<ClientRegistry>,
+        settings: WebSocketSettings,
     ) {
-        info!("WebSocket connection established: {addr}");
+        let client_id = clients.connect();
+        info!("WebSocket connection established: {addr} (client {client_id})");
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+        let last_pong = Arc::new(Mutex::new(Instant::now()));
 
         // Send initial connection acknowledgment
-        let hello_msg = serde_json::json!({
-            "type": "hello",
-            "message": "Orbit HMR connected"
-        })
-        .to_string();
+        let hello_msg = DevMessage::hello().to_json();
 
         if let Err(e) = ws_sender.send(Message::Text(hello_msg)).await {
             error!("Error sending hello message: {e}");
+            clients.disconnect(client_id);
             return;
         }
 
+        // Shared with the heartbeat task, which sends pings on the same socket
+        let ws_sender = Arc::new(tokio::sync::Mutex::new(ws_sender));
+
+        let sender = Arc::clone(&ws_sender);
         let send_task = tokio::spawn(async move {
             while let Ok(msg) = rx.recv().await {
-                ws_sender
-                    .send(Message::Text(msg))
+                if !msg.is_for(client_id) {
+                    continue;
+                }
+                sender
+                    .lock()
+                    .await
+                    .send(Message::Text(msg.text))
                     .await
                     .unwrap_or_else(|e| error!("Error sending message: {e}"));
             }
         });
 
+        // Ping periodically and give up on clients that stop answering
+        let pong_received = Arc::clone(&last_pong);
+        let heartbeat_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(settings.ping_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval.tick().await; // The first tick completes immediately
+
+            loop {
+                interval.tick().await;
+                let ping_sent = Instant::now();
+                if ws_sender
+                    .lock()
+                    .await
+                    .send(Message::Ping(Vec::new()))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+
+                tokio::time::sleep(settings.pong_timeout).await;
+                if *pong_received.lock().unwrap() < ping_sent {
+                    warn!("WebSocket client {addr} did not answer ping, closing connection");
+                    break;
+                }
+            }
+        });
+
+        let registry = Arc::clone(&clients);
         let recv_task = tokio::spawn(async move {
             while let Some(msg) = ws_receiver.next().await {
                 if let Ok(msg) = msg {
@@ -270,48 +642,1061 @@ impl DevServer {
                         break;
                     }
 
+                    if msg.is_pong() {
+                        *last_pong.lock().unwrap() = Instant::now();
+                        continue;
+                    }
+
                     // Handle incoming messages from client
                     if let Message::Text(text) = msg {
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                            if let Some(msg_type) = json.get("type").and_then(|t| t.as_str()) {
-                                match msg_type {
-                                    "register" => {
-                                        if let Some(path) = json.get("url").and_then(|p| p.as_str())
-                                        {
-                                            debug!("Client registered for path: {}", path);
-                                            // Could store client info in a map for targeted updates
-                                        }
-                                    }
-                                    "hmr_ready" => {
-                                        debug!("Client reported HMR ready state");
-                                    }
-                                    _ => debug!("Received unknown message type: {}", msg_type),
-                                }
+                        match serde_json::from_str::<protocol::ClientMessage>(&text) {
+                            Ok(protocol::ClientMessage::Register { url }) => {
+                                debug!("Client registered for path: {}", url);
+                                registry.register(client_id, &url);
+                            }
+                            Ok(protocol::ClientMessage::HmrReady) => {
+                                debug!("Client reported HMR ready state");
                             }
+                            Err(e) => debug!("Received unknown client message: {e}"),
                         }
                     }
                 }
             }
         });
 
-        future::select(send_task, recv_task).await;
+        // Whichever task finishes first ends the connection; stop the others
+        let (_, _, remaining) = future::select_all([send_task, recv_task, heartbeat_task]).await;
+        for task in remaining {
+            task.abort();
+        }
+        clients.disconnect(client_id);
         info!("WebSocket connection closed: {addr}");
     }
 
     /// Start the WebSocket server
-    async fn run_websocket_server(port: u16, rx: broadcast::Receiver<String>) -> Result<()> {
-        let addr = (IpAddr::V4(Ipv4Addr::LOCALHOST), port + 1);
-        let listener = TcpListener::bind(addr).await?;
-        info!("WebSocket server listening on: localhost:{}", port + 1);
+    async fn run_websocket_server(
+        host: String,
+        port: u16,
+        rx: broadcast::Receiver<ClientMessage>,
+        settings: WebSocketSettings,
+        clients: Arc<ClientRegistry>,
+    ) -> Result<()> {
+        let addr = bind_address(&host, port + 1);
+        let listener = TcpListener::bind(&addr)
+            .await
+            .with_context(|| format!("Failed to start HMR WebSocket server on {addr}"))?;
+        info!("WebSocket server listening on: {addr}");
+
+        Self::accept_websocket_connections(listener, rx, settings, clients).await
+    }
+
+    /// Accept WebSocket clients, rejecting any beyond `max_clients` active connections
+    async fn accept_websocket_connections(
+        listener: TcpListener,
+        rx: broadcast::Receiver<ClientMessage>,
+        settings: WebSocketSettings,
+        clients: Arc<ClientRegistry>,
+    ) -> Result<()> {
+        let max_clients = settings.max_clients;
+        let slots = Arc::new(Semaphore::new(max_clients));
 
         while let Ok((stream, addr)) = listener.accept().await {
-            let ws_stream = accept_async(stream).await?;
+            let mut ws_stream = match accept_async(stream).await {
+                Ok(ws_stream) => ws_stream,
+                Err(e) => {
+                    error!("WebSocket handshake with {addr} failed: {e}");
+                    continue;
+                }
+            };
+
+            // The permit is held for the lifetime of the connection
+            let Ok(permit) = Arc::clone(&slots).try_acquire_owned() else {
+                warn!("Rejecting WebSocket connection from {addr}: {max_clients} clients already connected");
+                let close_frame = CloseFrame {
+                    code: CloseCode::Again,
+                    reason: "Too many HMR clients connected".into(),
+                };
+                let _ = ws_stream.close(Some(close_frame)).await;
+                continue;
+            };
+
+            debug!(
+                "Active WebSocket clients: {}",
+                max_clients - slots.available_permits()
+            );
             let rx = rx.resubscribe();
+            let clients = Arc::clone(&clients);
 
             tokio::spawn(async move {
-                Self::handle_websocket_connection(ws_stream, addr, rx).await;
+                Self::handle_websocket_connection(ws_stream, addr, rx, clients, settings).await;
+                drop(permit);
             });
         }
         Ok(())
     }
 }
+
+/// Thread running the HTTP and WebSocket servers
+pub type ServerThread = thread::JoinHandle<Result<()>>;
+
+/// Wait for the server thread, returning the error that stopped it
+fn join_server_thread(thread: ServerThread) -> Result<()> {
+    thread
+        .join()
+        .map_err(|_| anyhow::anyhow!("Dev server thread panicked"))?
+}
+
+/// Text of a panic payload, which is a string for `panic!` and `expect`
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Handle to a server started with [`DevServer::start_detached`]
+///
+/// Dropping the handle also shuts the server down.
+pub struct DevServerHandle {
+    /// Thread running the HTTP and WebSocket servers
+    thread: Option<ServerThread>,
+    /// HTTP server, unblocked to end the server loop
+    http: Arc<tiny_http::Server>,
+    /// Port the HTTP server listens on
+    port: u16,
+}
+
+impl DevServerHandle {
+    /// Get the port the HTTP server listens on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Stop the HTTP and WebSocket servers and wait for the server thread to exit
+    pub fn shutdown(mut self) -> Result<()> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.http.unblock();
+        match self.thread.take() {
+            Some(thread) => join_server_thread(thread),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for DevServerHandle {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop() {
+            error!("Failed to stop dev server: {e}");
+        }
+    }
+}
+
+/// Format a host and port for binding, bracketing IPv6 addresses
+fn bind_address(host: &str, port: u16) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+/// Check that the HTTP port and, in dev mode, the WebSocket port are free
+///
+/// The WebSocket server listens on `port + 1`, so two servers on adjacent
+/// ports collide even though their HTTP ports differ.
+fn check_ports_available(host: &str, port: u16, mode: ServeMode) -> Result<()> {
+    let mut required = vec![(port, "HTTP server")];
+    if mode == ServeMode::Dev {
+        let ws_port = port.checked_add(1).ok_or_else(|| {
+            anyhow::anyhow!("Port {port} leaves no room for the WebSocket server on port + 1")
+        })?;
+        required.push((ws_port, "HMR WebSocket server"));
+    }
+
+    for (candidate, purpose) in required {
+        if let Err(e) = std::net::TcpListener::bind(bind_address(host, candidate)) {
+            let suggestion = port.saturating_add(2);
+            return Err(anyhow::anyhow!(
+                "Port {candidate} is needed for the {purpose} but is unavailable on {host} ({e}). \
+                 Is another server running? Try `--port {suggestion}`{}",
+                if mode == ServeMode::Dev {
+                    " (the dev server uses both --port and --port + 1)"
+                } else {
+                    ""
+                }
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Find a free HTTP port, with `port + 1` also free for the WebSocket server in dev mode
+fn find_free_port(host: &str, mode: ServeMode) -> Result<u16> {
+    for _ in 0..32 {
+        let listener = std::net::TcpListener::bind(bind_address(host, 0))?;
+        let port = listener.local_addr()?.port();
+        drop(listener);
+
+        if check_ports_available(host, port, mode).is_ok() {
+            return Ok(port);
+        }
+    }
+    Err(anyhow::anyhow!("Could not find a free port on {host}"))
+}
+
+/// Answer a single HTTP request
+fn serve_request(request: tiny_http::Request, context: &RequestContext) -> u16 {
+    debug!("Received request: {url:?}", url = request.url());
+    let (headers, inject_options, directory_listing, index_file) = {
+        let config = context.config.read().unwrap();
+        (
+            config.dev_server.headers.clone(),
+            InjectOptions::from_config(&config.hmr),
+            config.dev_server.directory_listing,
+            config.dev_server.index_file.clone(),
+        )
+    };
+
+    // Only reads are supported; tiny_http leaves the body out of HEAD responses
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        let response = tiny_http::Response::from_string("Method Not Allowed")
+            .with_status_code(405)
+            .with_header(header("Allow", "GET, HEAD"));
+        return respond(request, response, &headers);
+    }
+
+    // Ignore query strings and fragments when resolving files
+    let url = request.url();
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+
+    // Special handling for HMR client script
+    if context.mode == ServeMode::Dev && path == "/__orbit_hmr_client.js" {
+        debug!("Serving HMR client script");
+        let response = tiny_http::Response::from_string(get_hmr_client_js())
+            .with_header(content_type_header("application/javascript"));
+        return respond(request, response, &headers);
+    }
+
+    // Client settings are read from the live config so edits apply on reconnect
+    if context.mode == ServeMode::Dev && path == HMR_CONFIG_PATH {
+        debug!("Serving HMR client config");
+        let response =
+            tiny_http::Response::from_string(inject_options.client.to_json(context.port))
+                .with_header(content_type_header("application/json"))
+                .with_header(header("Cache-Control", "no-store"));
+        return respond(request, response, &headers);
+    }
+
+    // Handle static files
+    let path = path.trim_start_matches('/');
+    let mut file_path = context.root.join(path);
+
+    // Directories are served through their index file, or listed when enabled
+    if file_path.is_dir() {
+        let index = file_path.join(&index_file);
+        if index.is_file() {
+            file_path = index;
+        } else if directory_listing {
+            if let Some(page) = directory_listing_page(&context.root, &file_path, path) {
+                let response = tiny_http::Response::from_string(page)
+                    .with_header(content_type_header("text/html; charset=utf-8"));
+                return respond(request, response, &headers);
+            }
+        }
+    }
+
+    if !file_path.is_file() {
+        // File not found, return 404
+        let accepts_html = request.headers().iter().any(|header| {
+            header.field.equiv("Accept") && header.value.as_str().contains("text/html")
+        });
+        let response = if accepts_html {
+            tiny_http::Response::from_string(not_found_page(&context.root, path))
+                .with_header(content_type_header("text/html; charset=utf-8"))
+        } else {
+            tiny_http::Response::from_string("File not found")
+        };
+        return respond(request, response.with_status_code(404), &headers);
+    }
+
+    let mime = content_type(&file_path);
+    let inject_hmr = context.mode == ServeMode::Dev && is_html_file(&file_path);
+    let accept_encoding = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Accept-Encoding"))
+        .map(|header| header.value.as_str().to_string())
+        .unwrap_or_default();
+
+    // Serve precompressed siblings (app.wasm.br, app.wasm.gz) like a CDN would
+    let precompressed = if inject_hmr {
+        None
+    } else {
+        precompressed_variant(&file_path, &accept_encoding)
+    };
+
+    let body = if inject_hmr {
+        // Special handling for HTML files to inject HMR client
+        injected_html(&file_path, context, &inject_options)
+    } else if let Some((compressed_path, _)) = &precompressed {
+        debug!("Serving precompressed {compressed_path:?}");
+        std::fs::read(compressed_path)
+    } else {
+        std::fs::read(&file_path)
+    };
+
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to read {}: {}", file_path.display(), e);
+            let response =
+                tiny_http::Response::from_string("Failed to read file").with_status_code(500);
+            return respond(request, response, &headers);
+        }
+    };
+
+    // Unchanged assets are revalidated instead of downloaded again. Pages with
+    // the HMR client injected are left uncached.
+    let etag = (!inject_hmr).then(|| weak_etag(&body));
+    if let Some(etag) = &etag {
+        let if_none_match = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("If-None-Match"))
+            .map(|header| header.value.as_str());
+        if if_none_match.is_some_and(|value| etag_matches(value, etag)) {
+            let response = tiny_http::Response::empty(304).with_header(header("ETag", etag));
+            return respond(request, response, &headers);
+        }
+    }
+
+    let mut response = match context.mode {
+        ServeMode::Dev => {
+            tiny_http::Response::from_data(body).with_header(content_type_header(mime))
+        }
+        ServeMode::Preview => {
+            let accepts_gzip =
+                precompressed.is_none() && accepts_encoding(&accept_encoding, "gzip");
+            preview_response(body, mime, accepts_gzip)
+        }
+    };
+    if let Some((_, encoding)) = precompressed {
+        response.add_header(header("Content-Encoding", encoding));
+        response.add_header(header("Vary", "Accept-Encoding"));
+    }
+    if let Some(etag) = &etag {
+        response.add_header(header("ETag", etag));
+    }
+    respond(request, response, &headers)
+}
+
+/// Precompressed encodings looked up next to static files, in order of preference
+const PRECOMPRESSED_ENCODINGS: &[(&str, &str)] = &[("br", "br"), ("gzip", "gz")];
+
+/// Read an HTML page with the HMR client injected, reusing the cached page when unchanged
+fn injected_html(
+    path: &Path,
+    context: &RequestContext,
+    options: &InjectOptions,
+) -> std::io::Result<Vec<u8>> {
+    let modified = std::fs::metadata(path)?.modified()?;
+    if let Some(body) = context.html_cache.get(path, modified) {
+        debug!("Serving cached HTML file: {path:?}");
+        return Ok(body);
+    }
+
+    debug!("Processing HTML file: {path:?}");
+    let content = std::fs::read(path)?;
+    let body = match std::str::from_utf8(&content)
+        .map_err(anyhow::Error::from)
+        .and_then(|html| inject_hmr_client_with_options(html, context.port, options))
+    {
+        Ok(injected) => injected.into_bytes(),
+        Err(e) => {
+            error!("Failed to process HTML file: {e}");
+            // Fall back to serving the file without injection
+            content
+        }
+    };
+
+    context
+        .html_cache
+        .insert(path.to_owned(), modified, body.clone());
+    Ok(body)
+}
+
+/// Find a precompressed sibling of `file_path` that the client accepts
+///
+/// Returns the sibling's path and its `Content-Encoding`.
+fn precompressed_variant(
+    file_path: &Path,
+    accept_encoding: &str,
+) -> Option<(PathBuf, &'static str)> {
+    PRECOMPRESSED_ENCODINGS
+        .iter()
+        .filter(|(encoding, _)| accepts_encoding(accept_encoding, encoding))
+        .map(|(encoding, extension)| {
+            let mut path = file_path.as_os_str().to_owned();
+            path.push(format!(".{extension}"));
+            (PathBuf::from(path), *encoding)
+        })
+        .find(|(path, _)| path.is_file())
+}
+
+/// Check whether an `Accept-Encoding` header value allows an encoding
+///
+/// Encodings listed with `q=0` are refused.
+fn accepts_encoding(accept_encoding: &str, encoding: &str) -> bool {
+    accept_encoding.split(',').any(|candidate| {
+        let mut parts = candidate.split(';').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let refused = parts.any(|param| {
+            param
+                .strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        name.eq_ignore_ascii_case(encoding) && !refused
+    })
+}
+
+/// Weak ETag derived from the content hash of a response body
+fn weak_etag(body: &[u8]) -> String {
+    let hash = crate::utils::crypto::content_hash(body);
+    format!("W/\"{}\"", &hash[..16])
+}
+
+/// Check an `If-None-Match` header value against an ETag, using weak comparison
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// List the visible entries of a directory as `(name, is_dir)` pairs
+///
+/// Directories come first, then files, each sorted alphabetically.
+fn list_directory(dir: &Path) -> Vec<(String, bool)> {
+    let mut entries: Vec<(String, bool)> = std::fs::read_dir(dir)
+        .map(|dir| {
+            dir.filter_map(|entry| entry.ok())
+                .map(|entry| {
+                    let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                    (entry.file_name().to_string_lossy().into_owned(), is_dir)
+                })
+                .filter(|(name, _)| !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries
+}
+
+/// Render directory entries as HTML list items linking below `base`
+fn entry_links(entries: &[(String, bool)], base: &str) -> String {
+    entries
+        .iter()
+        .map(|(name, is_dir)| {
+            let name = escape_html(name);
+            let suffix = if *is_dir { "/" } else { "" };
+            format!("      <li><a href=\"{base}{name}{suffix}\">{name}{suffix}</a></li>\n")
+        })
+        .collect()
+}
+
+/// Render an HTML index of `dir`, requested as `path` below `root`
+///
+/// Returns `None` when the directory resolves to somewhere outside `root`.
+fn directory_listing_page(root: &Path, dir: &Path, path: &str) -> Option<String> {
+    let root = root.canonicalize().ok()?;
+    let dir = dir.canonicalize().ok()?;
+    if !dir.starts_with(&root) {
+        warn!("Refusing to list directory outside the project: {dir:?}");
+        return None;
+    }
+
+    let path = path.trim_matches('/');
+    let base = if path.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{path}/")
+    };
+    let mut items = String::new();
+    if dir != root {
+        items.push_str("      <li><a href=\"../\">../</a></li>\n");
+    }
+    items.push_str(&entry_links(&list_directory(&dir), &escape_html(&base)));
+
+    Some(format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>Index of {base}</title>
+    <style>
+      body {{ font-family: sans-serif; margin: 3em auto; max-width: 40em; color: #333; }}
+      li {{ line-height: 1.6; }}
+    </style>
+  </head>
+  <body>
+    <h1>Index of {base}</h1>
+    <ul>
+{items}    </ul>
+  </body>
+</html>
+"#,
+        base = escape_html(&base),
+    ))
+}
+
+/// Render an HTML 404 page listing the top-level entries of `root`
+fn not_found_page(root: &Path, path: &str) -> String {
+    let items = entry_links(&list_directory(root), "/");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>404 Not Found</title>
+    <style>
+      body {{ font-family: sans-serif; margin: 3em auto; max-width: 40em; color: #333; }}
+      h1 {{ color: #e74c3c; }}
+      code {{ background: #f4f4f4; padding: 0.1em 0.3em; border-radius: 3px; }}
+      li {{ line-height: 1.6; }}
+    </style>
+  </head>
+  <body>
+    <h1>404 Not Found</h1>
+    <p>Nothing is served at <code>/{path}</code>.</p>
+    <p>The project root contains:</p>
+    <ul>
+{items}    </ul>
+  </body>
+</html>
+"#,
+        path = escape_html(path),
+    )
+}
+
+/// Escape text for use in HTML content and attributes
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Build a production-like response: cache headers, nosniff and gzip
+fn preview_response(
+    body: Vec<u8>,
+    mime: &str,
+    accepts_gzip: bool,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    // HTML must be revalidated so new deployments are picked up; other
+    // assets are usually fingerprinted and can be cached
+    let cache_control = if mime.starts_with("text/html") {
+        "no-cache"
+    } else {
+        "public, max-age=31536000"
+    };
+
+    let mut headers = vec![
+        content_type_header(mime),
+        header("Cache-Control", cache_control),
+        header("X-Content-Type-Options", "nosniff"),
+    ];
+
+    let body = if accepts_gzip && is_compressible(mime) && body.len() >= MIN_COMPRESS_SIZE {
+        match gzip(&body) {
+            Ok(compressed) => {
+                headers.push(header("Content-Encoding", "gzip"));
+                headers.push(header("Vary", "Accept-Encoding"));
+                compressed
+            }
+            Err(e) => {
+                error!("Failed to compress response: {}", e);
+                body
+            }
+        }
+    } else {
+        body
+    };
+
+    headers
+        .into_iter()
+        .fold(tiny_http::Response::from_data(body), |response, h| {
+            response.with_header(h)
+        })
+}
+
+/// Responses smaller than this are not worth compressing
+const MIN_COMPRESS_SIZE: usize = 1024;
+
+/// Compress a response body with gzip
+fn gzip(body: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(body)?;
+    encoder.finish()
+}
+
+/// Whether a content type benefits from compression
+fn is_compressible(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.starts_with("application/javascript")
+        || mime.starts_with("application/json")
+        || mime.starts_with("application/wasm")
+        || mime.starts_with("image/svg+xml")
+}
+
+/// Guess the content type of a file from its extension
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "application/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "wasm" => "application/wasm",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Create a response header from static parts
+fn header(name: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+}
+
+/// Create a `Content-Type` header
+fn content_type_header(mime: &str) -> tiny_http::Header {
+    header("Content-Type", mime)
+}
+
+/// Send a response with the configured custom headers, returning its status code
+fn respond<R: Read>(
+    request: tiny_http::Request,
+    response: tiny_http::Response<R>,
+    headers: &HashMap<String, String>,
+) -> u16 {
+    let status = response.status_code().0;
+    if let Err(e) = request.respond(with_custom_headers(response, headers)) {
+        debug!("Failed to send response: {e}");
+    }
+    status
+}
+
+/// Add the user-configured `dev_server.headers` to a response
+fn with_custom_headers<R: Read>(
+    mut response: tiny_http::Response<R>,
+    headers: &HashMap<String, String>,
+) -> tiny_http::Response<R> {
+    for (name, value) in headers {
+        match tiny_http::Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+            Ok(header) => response.add_header(header),
+            Err(()) => error!("Invalid custom header: {name}: {value}"),
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_address() {
+        assert_eq!(bind_address("127.0.0.1", 3000), "127.0.0.1:3000");
+        assert_eq!(bind_address("0.0.0.0", 3001), "0.0.0.0:3001");
+        assert_eq!(bind_address("::1", 3000), "[::1]:3000");
+    }
+
+    #[test]
+    fn test_port_conflict_is_reported() {
+        // Find a free pair of ports, then occupy the WebSocket one
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_port = listener.local_addr().unwrap().port();
+        let Some(port) = ws_port.checked_sub(1) else {
+            return;
+        };
+        if std::net::TcpListener::bind(("127.0.0.1", port)).is_err() {
+            return;
+        }
+
+        let err = check_ports_available("127.0.0.1", port, ServeMode::Dev)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&format!("Port {ws_port}")));
+        assert!(err.contains("WebSocket"));
+
+        // Previews don't need the WebSocket port
+        assert!(check_ports_available("127.0.0.1", port, ServeMode::Preview).is_ok());
+    }
+
+    #[test]
+    fn test_detached_server_shutdown() {
+        // Find a free pair of ports for the HTTP and WebSocket servers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_port = listener.local_addr().unwrap().port();
+        let Some(port) = ws_port.checked_sub(1) else {
+            return;
+        };
+        drop(listener);
+        if std::net::TcpListener::bind(("127.0.0.1", port)).is_err() {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = OrbitonConfig::default();
+        config.dev_server.host = "127.0.0.1".to_string();
+        config.dev_server.port = port;
+
+        let mut server = DevServer::from_config(dir.path(), &config).unwrap();
+        let handle = server.start_detached().unwrap();
+        assert!(std::net::TcpListener::bind(("127.0.0.1", port)).is_err());
+
+        // Both ports are released once the handle is shut down. tiny_http
+        // closes its listener on a background thread, so allow it a moment.
+        handle.shutdown().unwrap();
+        let released = (0..100).any(|_| {
+            let available = check_ports_available("127.0.0.1", port, ServeMode::Dev).is_ok();
+            if !available {
+                thread::sleep(Duration::from_millis(10));
+            }
+            available
+        });
+        assert!(released);
+    }
+
+    #[test]
+    fn test_websocket_bind_failure_stops_server() {
+        // Find a free pair of ports, then occupy the WebSocket one
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_port = listener.local_addr().unwrap().port();
+        let Some(port) = ws_port.checked_sub(1) else {
+            return;
+        };
+        if std::net::TcpListener::bind(("127.0.0.1", port)).is_err() {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = OrbitonConfig::default();
+        config.dev_server.host = "127.0.0.1".to_string();
+        config.dev_server.port = port;
+
+        // Skip the up-front port check, as if the port was taken after it ran
+        let mut server = DevServer::from_config(dir.path(), &config).unwrap();
+        let (thread, _http) = server.spawn_on_port().unwrap();
+        let err = join_server_thread(thread).unwrap_err();
+        assert!(
+            format!("{err:#}").contains(&format!("HMR WebSocket server on 127.0.0.1:{ws_port}")),
+            "{err:#}"
+        );
+        drop(listener);
+    }
+
+    #[test]
+    fn test_panic_message() {
+        assert_eq!(panic_message(&"boom"), "boom");
+        assert_eq!(panic_message(&"boom".to_string()), "boom");
+        assert_eq!(panic_message(&42), "unknown panic");
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let etag = weak_etag(b"body {}");
+        assert!(etag.starts_with("W/\""));
+        assert_ne!(etag, weak_etag(b"body { margin: 0 }"));
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&etag.replace("W/", ""), &etag));
+        assert!(etag_matches(&format!("\"other\", {etag}"), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("W/\"other\"", &etag));
+    }
+
+    #[test]
+    fn test_html_cache_follows_modification_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("index.html");
+        std::fs::write(&page, "<html><body>v1</body></html>").unwrap();
+
+        let server = DevServer::new(3000, dir.path()).unwrap();
+        let context = RequestContext {
+            root: dir.path().to_owned(),
+            port: 3000,
+            mode: ServeMode::Dev,
+            config: Arc::clone(&server.config),
+            html_cache: Arc::clone(&server.html_cache),
+        };
+        let options = InjectOptions::default();
+
+        let first = injected_html(&page, &context, &options).unwrap();
+        assert!(String::from_utf8_lossy(&first).contains("__orbit_hmr_client.js"));
+
+        // Unchanged pages come from the cache
+        let modified = std::fs::metadata(&page).unwrap().modified().unwrap();
+        server
+            .html_cache
+            .insert(page.clone(), modified, b"cached".to_vec());
+        assert_eq!(injected_html(&page, &context, &options).unwrap(), b"cached");
+
+        // A new modification time re-injects the page
+        let file = std::fs::File::options().write(true).open(&page).unwrap();
+        file.set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(injected_html(&page, &context, &options).unwrap(), first);
+
+        // So does invalidating the cache
+        server.html_cache.insert(
+            page.clone(),
+            modified + Duration::from_secs(1),
+            b"cached".to_vec(),
+        );
+        server.invalidate_html_cache();
+        assert_eq!(injected_html(&page, &context, &options).unwrap(), first);
+    }
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(
+            content_type(Path::new("index.html")),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(content_type(Path::new("app_bg.WASM")), "application/wasm");
+        assert_eq!(content_type(Path::new("logo.svg")), "image/svg+xml");
+        assert_eq!(
+            content_type(Path::new("LICENSE")),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_not_found_page_lists_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("index.html"), "").unwrap();
+        std::fs::write(dir.path().join("a<b>.txt"), "").unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+
+        let page = not_found_page(dir.path(), "indx.html");
+        assert!(page.contains("<code>/indx.html</code>"));
+        assert!(page.contains("<a href=\"/src/\">src/</a>"));
+        assert!(page.contains("a&lt;b&gt;.txt"));
+        assert!(!page.contains(".env"));
+        // Directories are listed before files
+        assert!(page.find("src/").unwrap() < page.find("index.html").unwrap());
+    }
+
+    #[test]
+    fn test_directory_listing_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("build/web/assets")).unwrap();
+        std::fs::write(root.join("build/web/app.wasm"), "").unwrap();
+
+        let page = directory_listing_page(&root, &root.join("build/web"), "build/web/").unwrap();
+        assert!(page.contains("<h1>Index of /build/web/</h1>"));
+        assert!(page.contains("<a href=\"../\">../</a>"));
+        assert!(page.contains("<a href=\"/build/web/assets/\">assets/</a>"));
+        assert!(page.contains("<a href=\"/build/web/app.wasm\">app.wasm</a>"));
+
+        // The project root has no parent link
+        let page = directory_listing_page(&root, &root, "").unwrap();
+        assert!(!page.contains("../"));
+
+        // Directories outside the root are never listed
+        assert!(directory_listing_page(&root, &root.join(".."), "..").is_none());
+    }
+
+    #[test]
+    fn test_preview_response_compression() {
+        let body = "console.log('orbit');\n".repeat(100).into_bytes();
+        fn has_header<R: Read>(
+            response: &tiny_http::Response<R>,
+            name: &'static str,
+            value: &str,
+        ) -> bool {
+            response
+                .headers()
+                .iter()
+                .any(|h| h.field.equiv(name) && h.value.as_str() == value)
+        }
+
+        let response = preview_response(body.clone(), "application/javascript", true);
+        assert!(has_header(&response, "Content-Encoding", "gzip"));
+        assert!(has_header(&response, "X-Content-Type-Options", "nosniff"));
+
+        // Clients that don't accept gzip get the original bytes
+        let response = preview_response(body.clone(), "application/javascript", false);
+        assert!(!has_header(&response, "Content-Encoding", "gzip"));
+        assert_eq!(response.data_length(), Some(body.len()));
+
+        // Images are already compressed
+        let response = preview_response(body, "image/png", true);
+        assert!(!has_header(&response, "Content-Encoding", "gzip"));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_client_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (tx, rx) = broadcast::channel(16);
+        let settings = WebSocketSettings {
+            max_clients: 1,
+            ..WebSocketSettings::from_config(&OrbitonConfig::default())
+        };
+        tokio::spawn(DevServer::accept_websocket_connections(
+            listener,
+            rx,
+            settings,
+            Arc::default(),
+        ));
+
+        // The first client fits within the limit and receives the greeting
+        let (mut first, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let hello = first.next().await.unwrap().unwrap();
+        assert!(hello.to_text().unwrap().contains("hello"));
+
+        // The second client is closed straight away
+        let (mut second, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        match second.next().await.unwrap().unwrap() {
+            Message::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Again),
+            other => panic!("expected close frame, got {other:?}"),
+        }
+
+        drop(tx);
+    }
+
+    #[test]
+    fn test_hmr_message_follows_preserve_state() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = OrbitonConfig::default();
+        let server = DevServer::from_config(temp_dir.path(), &config).unwrap();
+        let message = server.hmr_message(vec!["app".to_string()]);
+        assert!(message.contains(r#""preserveState":true"#), "{message}");
+
+        config.hmr.preserve_state = false;
+        server.update_config(config);
+        let message = server.hmr_message(vec!["app".to_string()]);
+        assert!(message.contains(r#""preserveState":false"#), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_targeted_hmr_update() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let server = DevServer::new(0, temp_dir.path()).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let rx = server.tx.as_ref().unwrap().subscribe();
+        tokio::spawn(DevServer::accept_websocket_connections(
+            listener,
+            rx,
+            WebSocketSettings::from_config(&server.config()),
+            Arc::clone(&server.clients),
+        ));
+
+        let mut pages = Vec::new();
+        for path in ["/about", "/"] {
+            let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+            client.next().await.unwrap().unwrap(); // hello
+            let register = serde_json::to_string(&protocol::ClientMessage::Register {
+                url: path.to_string(),
+            })
+            .unwrap();
+            client.send(Message::Text(register)).await.unwrap();
+            pages.push(client);
+        }
+
+        // Wait until both registrations have been processed
+        let registered = |path: &str| server.clients.matching(|url| url == path).len();
+        let mut attempts = 0;
+        while registered("/about") != 1 || registered("/") != 1 {
+            attempts += 1;
+            assert!(
+                attempts < 200,
+                "clients did not register: {} on /about, {} on /",
+                registered("/about"),
+                registered("/")
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        server
+            .send_hmr_update_to(vec!["about".to_string()], |url| url == "/about")
+            .unwrap();
+        server.send_reload_command().unwrap();
+
+        // The matching page sees the HMR update first, the other only the reload
+        let about = pages[0].next().await.unwrap().unwrap();
+        assert!(about.to_text().unwrap().contains("\"hmr\""));
+        let index = pages[1].next().await.unwrap().unwrap();
+        assert!(index.to_text().unwrap().contains("\"reload\""));
+
+        // Both connections and the single HMR update count towards the session totals
+        assert_eq!(server.client_connection_count(), 2);
+        assert_eq!(server.hmr_update_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_client_is_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (_tx, rx) = broadcast::channel(16);
+        let clients = Arc::new(ClientRegistry::default());
+        let settings = WebSocketSettings {
+            max_clients: 4,
+            ping_interval: Duration::from_millis(50),
+            pong_timeout: Duration::from_millis(50),
+        };
+        tokio::spawn(DevServer::accept_websocket_connections(
+            listener,
+            rx,
+            settings,
+            Arc::clone(&clients),
+        ));
+
+        // A client that never reads again never answers pings
+        let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        client.next().await.unwrap().unwrap(); // hello
+        assert_eq!(clients.matching(|_| true).len(), 1);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !clients.matching(|_| true).is_empty() {
+            assert!(Instant::now() < deadline, "client was not dropped");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
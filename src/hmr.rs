@@ -1,10 +1,20 @@
 // Hot Module Replacement (HMR) support for the Orbit UI framework
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use crate::config::HmrConfig;
+
+pub mod protocol;
+
+/// Module name reported for changes that require a full page reload
+pub const FULL_RELOAD_MODULE: &str = "__full_reload__";
+
+/// Number of recent rebuilds the rolling average covers
+const REBUILD_HISTORY: usize = 10;
+
 /// HMR update data
 #[derive(Debug, Clone)]
 pub struct HmrUpdate {
@@ -14,6 +24,33 @@ pub struct HmrUpdate {
     pub timestamp: Instant,
     /// Whether the module has been updated
     pub is_updated: bool,
+    /// Content hash of the file when the change was recorded
+    pub content_hash: Option<String>,
+}
+
+/// Rules deciding which changed files the HMR system reacts to
+#[derive(Debug, Clone)]
+struct WatchRules {
+    /// Extensions of source files that map to modules
+    extensions: Vec<String>,
+    /// Files that force a full reload
+    full_reload: globset::GlobSet,
+    /// Watched directories outside the project, from `hmr.watch_dirs`
+    extra_dirs: Vec<PathBuf>,
+}
+
+impl WatchRules {
+    fn from_config(config: &HmrConfig, project_root: &Path) -> anyhow::Result<Self> {
+        Ok(Self {
+            extensions: config.watch_extensions.clone(),
+            full_reload: crate::utils::fs::glob_set(&config.full_reload_patterns)?,
+            extra_dirs: config
+                .watch_dirs
+                .iter()
+                .map(|dir| project_root.join(dir))
+                .collect(),
+        })
+    }
 }
 
 /// HMR context manager
@@ -23,8 +60,17 @@ pub struct HmrContext {
     modules: Arc<Mutex<HashMap<String, HmrUpdate>>>,
     /// Last full rebuild time
     last_rebuild: Arc<Mutex<Option<Instant>>>,
+    /// Durations of the most recent rebuilds, oldest first
+    rebuild_durations: Arc<Mutex<VecDeque<Duration>>>,
     /// Project root directory
     project_root: PathBuf,
+    /// Watched extensions and full reload patterns
+    rules: Arc<Mutex<WatchRules>>,
+}
+
+/// Tracking key of the full reload requested when the file at `path` is removed
+fn removal_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
 }
 
 impl Default for HmrContext {
@@ -36,54 +82,144 @@ impl Default for HmrContext {
 impl HmrContext {
     /// Create a new HMR context
     pub fn new(project_root: PathBuf) -> Self {
+        let rules = WatchRules::from_config(&HmrConfig::default(), &project_root)
+            .expect("default full reload patterns are valid globs");
+
         Self {
             modules: Arc::new(Mutex::new(HashMap::new())),
             last_rebuild: Arc::new(Mutex::new(None)),
+            rebuild_durations: Arc::new(Mutex::new(VecDeque::with_capacity(REBUILD_HISTORY))),
             project_root,
+            rules: Arc::new(Mutex::new(rules)),
         }
     }
 
-    /// Record a file change
+    /// Apply the watched extensions and full reload patterns from the config
+    pub fn configure(&self, config: &HmrConfig) -> anyhow::Result<()> {
+        *self.rules.lock().unwrap() = WatchRules::from_config(config, &self.project_root)?;
+        Ok(())
+    }
+
+    /// Record a file change, ignoring events where the content is unchanged
+    ///
+    /// Returns the changed module, or [`FULL_RELOAD_MODULE`] for files such as
+    /// `Cargo.toml` that require a full rebuild and page reload.
     pub fn record_file_change(&self, path: &Path) -> Option<String> {
-        let rel_path = path.strip_prefix(&self.project_root).ok()?;
-        let path_str = rel_path.to_string_lossy().replace('\\', "/");
-
-        // Extract module path for Rust and Orbit files
-        if let Some(ext) = path.extension() {
-            let ext_str = ext.to_string_lossy();
-
-            let module = if ext_str == "rs" || ext_str == "orbit" {
-                if path_str.starts_with("src/") {
-                    Some(
-                        path_str
-                            .replace("src/", "")
-                            .replace(".rs", "")
-                            .replace(".orbit", ""),
-                    )
-                } else {
-                    // Not in src directory, might be lib or other code
-                    None
-                }
-            } else {
-                // Not a Rust or Orbit file
-                None
-            };
-
-            if let Some(module_path) = module {
-                let mut modules = self.modules.lock().unwrap();
-                modules.insert(
-                    module_path.clone(),
-                    HmrUpdate {
-                        module: module_path.clone(),
-                        timestamp: Instant::now(),
-                        is_updated: false,
-                    },
-                );
-                return Some(module_path);
+        let (key, module) = self.module_key(path)?;
+
+        // Deleted or unreadable files have no hash and always count as changed
+        let content_hash = std::fs::read(path)
+            .ok()
+            .map(|bytes| crate::utils::crypto::content_hash(&bytes));
+
+        let mut modules = self.modules.lock().unwrap();
+
+        // Editors that save by replacing the file report a removal first. The
+        // file is back, so the reload requested for the removal is dropped.
+        let removal = removal_key(path);
+        if content_hash.is_some() && removal != key {
+            modules.remove(&removal);
+        }
+
+        if let Some(previous) = modules.get(&key) {
+            if content_hash.is_some() && previous.content_hash == content_hash {
+                return None;
             }
         }
 
-        None
+        modules.insert(
+            key,
+            HmrUpdate {
+                module: module.clone(),
+                timestamp: Instant::now(),
+                is_updated: false,
+                content_hash,
+            },
+        );
+
+        Some(module)
+    }
+
+    /// Record that a file was removed
+    ///
+    /// Drops the pending update for the file so its module is no longer
+    /// reported, and requests a full reload since the module is gone.
+    /// Returns the removed module.
+    pub fn record_file_removed(&self, path: &Path) -> Option<String> {
+        let (key, module) = self.module_key(path)?;
+
+        let mut modules = self.modules.lock().unwrap();
+        modules.remove(&key);
+        modules.insert(
+            removal_key(path),
+            HmrUpdate {
+                module: FULL_RELOAD_MODULE.to_string(),
+                timestamp: Instant::now(),
+                is_updated: false,
+                content_hash: None,
+            },
+        );
+
+        Some(module)
+    }
+
+    /// Record that a file was renamed
+    ///
+    /// Moves the tracked entry from the old path to the new one and returns
+    /// the module at the new path. Renaming a file out of the watched sources
+    /// is treated as a removal.
+    pub fn record_file_renamed(&self, from: &Path, to: &Path) -> Option<String> {
+        if self.module_key(to).is_none() {
+            self.record_file_removed(from);
+            return None;
+        }
+
+        if let Some((key, _)) = self.module_key(from) {
+            self.modules.lock().unwrap().remove(&key);
+        }
+        self.record_file_change(to)
+    }
+
+    /// Map a path to its tracking key and module, if it is watched
+    fn module_key(&self, path: &Path) -> Option<(String, String)> {
+        let rules = self.rules.lock().unwrap();
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy())
+            .unwrap_or_default();
+        let watched_ext = !ext.is_empty() && rules.extensions.iter().any(|w| *w == ext);
+
+        // Paths such as `app/../shared` are outside the project too
+        let rel_path = path
+            .strip_prefix(&self.project_root)
+            .ok()
+            .filter(|rel_path| !rel_path.starts_with(".."));
+
+        match rel_path {
+            Some(rel_path) if rules.full_reload.is_match(rel_path) => Some((
+                rel_path.to_string_lossy().replace('\\', "/"),
+                FULL_RELOAD_MODULE.to_string(),
+            )),
+            Some(rel_path) if watched_ext => {
+                // Extract module path for watched source files under src/
+                let module = rel_path
+                    .to_string_lossy()
+                    .replace('\\', "/")
+                    .strip_prefix("src/")?
+                    .strip_suffix(&format!(".{ext}"))?
+                    .to_string();
+                Some((module.clone(), module))
+            }
+            // Sources in extra watch directories don't map to a module of
+            // this project, so they rebuild and reload the page
+            None if watched_ext && rules.extra_dirs.iter().any(|dir| path.starts_with(dir)) => {
+                Some((
+                    path.to_string_lossy().into_owned(),
+                    FULL_RELOAD_MODULE.to_string(),
+                ))
+            }
+            _ => None,
+        }
     }
 
     /// Mark all modules as updated
@@ -103,11 +239,16 @@ impl HmrContext {
     /// Get pending module updates
     pub fn get_pending_updates(&self) -> Vec<String> {
         let modules = self.modules.lock().unwrap();
-        modules
+        let mut pending: Vec<String> = modules
             .values()
             .filter(|update| !update.is_updated)
             .map(|update| update.module.clone())
-            .collect()
+            .collect();
+
+        // Several full reload files collapse into a single sentinel
+        pending.sort();
+        pending.dedup();
+        pending
     }
 
     /// Record a full rebuild
@@ -118,6 +259,24 @@ impl HmrContext {
         // Mark all modules as updated when a full rebuild happens
         self.mark_modules_updated();
     }
+
+    /// Record how long a rebuild took
+    pub fn record_rebuild_duration(&self, duration: Duration) {
+        let mut durations = self.rebuild_durations.lock().unwrap();
+        if durations.len() == REBUILD_HISTORY {
+            durations.pop_front();
+        }
+        durations.push_back(duration);
+    }
+
+    /// Average duration of the most recent rebuilds, if any were recorded
+    pub fn average_rebuild_duration(&self) -> Option<Duration> {
+        let durations = self.rebuild_durations.lock().unwrap();
+        let count = u32::try_from(durations.len())
+            .ok()
+            .filter(|count| *count > 0)?;
+        Some(durations.iter().sum::<Duration>() / count)
+    }
     /// Check if a rebuild is needed
     pub fn should_rebuild(&self, debounce_time: Duration) -> bool {
         // Check if enough time has passed since last rebuild
@@ -0,0 +1,182 @@
+// Messages exchanged with the HMR client over the WebSocket connection
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the HMR protocol, announced in the `hello` message
+///
+/// Bump this when a message changes incompatibly, together with
+/// `PROTOCOL_VERSION` in `hmr_client.js`. Clients cached by the browser detect
+/// the mismatch and reload to fetch the current client.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Message sent from the dev server to HMR clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DevMessage {
+    /// Greeting sent when a client connects
+    #[serde(rename = "hello")]
+    Hello {
+        #[serde(rename = "protocolVersion")]
+        protocol_version: u32,
+        message: String,
+    },
+    /// Modules to hot-swap
+    #[serde(rename = "hmr")]
+    Hmr {
+        modules: Vec<String>,
+        /// Whether the client should keep component state instead of remounting
+        #[serde(rename = "preserveState", default = "default_preserve_state")]
+        preserve_state: bool,
+    },
+    /// Full page reload
+    #[serde(rename = "reload")]
+    Reload,
+    /// Rebuild progress: `started`, `completed` or `failed`
+    #[serde(rename = "rebuild")]
+    Rebuild {
+        status: String,
+        #[serde(
+            rename = "durationMs",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        duration_ms: Option<u64>,
+        /// Compiler output, stripped of ANSI escape codes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// The build could not run, e.g. because a hook failed
+    #[serde(rename = "build-error")]
+    BuildError { message: String },
+    /// Files changed on disk, relative to the project
+    #[serde(rename = "fileChange")]
+    FileChange { paths: Vec<String>, kind: String },
+}
+
+impl DevMessage {
+    /// The greeting for a newly connected client
+    pub fn hello() -> Self {
+        Self::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            message: "Orbit HMR connected".to_string(),
+        }
+    }
+
+    /// Serialize the message for sending over the socket
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("HMR messages always serialize")
+    }
+}
+
+fn default_preserve_state() -> bool {
+    true
+}
+
+/// Message sent from HMR clients to the dev server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    /// Page URL the client is showing, used to target updates
+    #[serde(rename = "register")]
+    Register { url: String },
+    /// The client finished applying an update
+    #[serde(rename = "hmr_ready")]
+    HmrReady,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_messages_match_client_format() {
+        let hello: serde_json::Value =
+            serde_json::from_str(&DevMessage::hello().to_json()).unwrap();
+        assert_eq!(hello["type"], "hello");
+        assert_eq!(hello["protocolVersion"], PROTOCOL_VERSION);
+
+        let rebuild = DevMessage::Rebuild {
+            status: "completed".to_string(),
+            duration_ms: Some(2300),
+            output: None,
+        };
+        assert_eq!(
+            rebuild.to_json(),
+            r#"{"type":"rebuild","status":"completed","durationMs":2300}"#
+        );
+        assert_eq!(DevMessage::Reload.to_json(), r#"{"type":"reload"}"#);
+
+        let hmr = DevMessage::Hmr {
+            modules: vec!["app".to_string()],
+            preserve_state: true,
+        };
+        assert_eq!(
+            hmr.to_json(),
+            r#"{"type":"hmr","modules":["app"],"preserveState":true}"#
+        );
+
+        let error = DevMessage::BuildError {
+            message: "hook failed".to_string(),
+        };
+        assert_eq!(
+            error.to_json(),
+            r#"{"type":"build-error","message":"hook failed"}"#
+        );
+    }
+
+    #[test]
+    fn test_dev_messages_round_trip() {
+        let messages = [
+            DevMessage::hello(),
+            DevMessage::Hmr {
+                modules: vec!["app".to_string(), "button".to_string()],
+                preserve_state: false,
+            },
+            DevMessage::Reload,
+            DevMessage::Rebuild {
+                status: "started".to_string(),
+                duration_ms: None,
+                output: None,
+            },
+            DevMessage::Rebuild {
+                status: "failed".to_string(),
+                duration_ms: Some(1200),
+                output: Some("error[E0308]: mismatched types".to_string()),
+            },
+            DevMessage::FileChange {
+                paths: vec!["src/app.orbit".to_string()],
+                kind: "Modify(Data(Content))".to_string(),
+            },
+            DevMessage::BuildError {
+                message: "hook failed".to_string(),
+            },
+        ];
+
+        for message in messages {
+            let json = message.to_json();
+            let parsed: DevMessage = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, message, "round trip of {json}");
+        }
+    }
+
+    #[test]
+    fn test_client_messages_parse() {
+        let register: ClientMessage =
+            serde_json::from_str(r#"{"type":"register","url":"/about"}"#).unwrap();
+        assert_eq!(
+            register,
+            ClientMessage::Register {
+                url: "/about".to_string()
+            }
+        );
+
+        let ready: ClientMessage = serde_json::from_str(r#"{"type":"hmr_ready"}"#).unwrap();
+        assert_eq!(ready, ClientMessage::HmrReady);
+    }
+
+    #[test]
+    fn test_client_script_speaks_current_protocol() {
+        let declaration = format!("const PROTOCOL_VERSION = {PROTOCOL_VERSION};");
+        assert!(crate::hmr_inject::HMR_CLIENT_SCRIPT.contains(&declaration));
+    }
+}
@@ -2,10 +2,20 @@
 // This is injected into HTML during development to enable hot module reloading
 
 (function() {
-    // Configuration
+    // HMR protocol spoken by this client, see src/hmr/protocol.rs
+    const PROTOCOL_VERSION = 1;
+
+    // sessionStorage key marking a reload caused by a protocol mismatch
+    const PROTOCOL_RELOAD_KEY = '__orbit_hmr_protocol_reload';
+
+    // Configuration, overridden by the dev server's [hmr] settings
     const config = {
-        reconnectInterval: 2000,  // Reconnection interval in ms
+        reconnectInterval: 1000,  // Initial reconnection delay in ms, doubled per attempt
+        reconnectMaxDelay: 30000,
         reconnectMaxAttempts: 10,
+        debounceMs: 0,            // Batch HMR updates arriving within this window
+        showNotifications: true,
+        wsPort: null,
         debug: true
     };
 
@@ -13,6 +23,10 @@
     let socket = null;
     let reconnectAttempts = 0;
     let isConnected = false;
+    let protocolMismatch = false;
+    let pendingModules = new Set();
+    let pendingPreserveState = true;
+    let pendingTimer = null;
 
     // Create a logger that respects the debug setting
     const log = {
@@ -27,16 +41,43 @@
         }
     };
 
+    // Fetch the client settings from the dev server
+    function loadConfig() {
+        // Inlined clients have their settings embedded in the page
+        if (window.__ORBIT_HMR_CONFIG__) {
+            return Promise.resolve(window.__ORBIT_HMR_CONFIG__);
+        }
+        return fetch('/__orbit/hmr-config.json', { cache: 'no-store' })
+            .then((response) => response.ok ? response.json() : {})
+            .catch(() => ({}));
+    }
+
+    // Apply settings received from the dev server
+    function applyConfig(settings) {
+        if (typeof settings.maxRetries === 'number') config.reconnectMaxAttempts = settings.maxRetries;
+        if (typeof settings.debounceMs === 'number') config.debounceMs = settings.debounceMs;
+        if (typeof settings.showNotifications === 'boolean') config.showNotifications = settings.showNotifications;
+        if (typeof settings.wsPort === 'number') config.wsPort = settings.wsPort;
+    }
+
+    // Build the WebSocket URL from the current settings
+    function webSocketUrl() {
+        const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+        // WebSocket server port, pinned by the dev server when the client is inlined
+        const port = config.wsPort || window.__ORBIT_HMR_WS_PORT__ || parseInt(window.location.port) + 1;
+        const hostname = window.location.hostname || 'localhost';
+        return `${protocol}//${hostname}:${port}`;
+    }
+
     // Initialize the HMR system
     function init() {
         log.info("Initializing HMR system");
         
-        // Connect to the WebSocket server
-        const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
-        const port = parseInt(window.location.port) + 1; // WebSocket server port
-        const wsUrl = `${protocol}//${window.location.hostname}:${port}`;
-        
-        connectWebSocket(wsUrl);
+        // Connect to the WebSocket server once the settings are known
+        loadConfig().then((settings) => {
+            applyConfig(settings);
+            connectWebSocket(webSocketUrl());
+        });
         
         // Listen for page unload to close the socket
         window.addEventListener('beforeunload', () => {
@@ -71,7 +112,7 @@
             socket.onclose = () => {
                 log.info('WebSocket connection closed');
                 isConnected = false;
-                attemptReconnect(url);
+                attemptReconnect();
             };
 
             socket.onerror = (error) => {
@@ -84,7 +125,7 @@
             };
         } catch (error) {
             log.error(`Failed to connect to WebSocket: ${error}`);
-            attemptReconnect(url);
+            attemptReconnect();
         }
     }
 
@@ -94,6 +135,15 @@
             const message = JSON.parse(data);
             
             switch (message.type) {
+                case 'hello':
+                    handleHello(message);
+                    break;
+
+                case 'reload':
+                    log.info('Reloading page');
+                    window.location.reload();
+                    break;
+
                 case 'fileChange':
                     log.info(`File change detected: ${message.paths.join(', ')}`);
                     break;
@@ -102,8 +152,13 @@
                     handleRebuild(message);
                     break;
                     
+                case 'build-error':
+                    handleBuildError(message);
+                    break;
+                    
                 case 'hmr':
-                    handleHmrUpdate(message);
+                    // Servers predating hmr.preserve_state always expected state to be kept
+                    queueHmrUpdate(message.modules, message.preserveState !== false);
                     break;
                     
                 default:
@@ -114,10 +169,33 @@
         }
     }
 
+    // Check that the server speaks our protocol version
+    function handleHello(message) {
+        if (message.protocolVersion === PROTOCOL_VERSION) {
+            sessionStorage.removeItem(PROTOCOL_RELOAD_KEY);
+            log.info(message.message);
+            return;
+        }
+
+        log.warn(`Server speaks HMR protocol ${message.protocolVersion}, this client speaks ${PROTOCOL_VERSION}`);
+
+        // A cached client is out of date: reload once to fetch the current one
+        const serverVersion = String(message.protocolVersion);
+        if (sessionStorage.getItem(PROTOCOL_RELOAD_KEY) !== serverVersion) {
+            sessionStorage.setItem(PROTOCOL_RELOAD_KEY, serverVersion);
+            window.location.reload();
+            return;
+        }
+
+        // Still mismatched after a reload, so give up instead of looping
+        log.error('HMR disabled: incompatible dev server protocol');
+        protocolMismatch = true;
+        socket.close();
+    }
+
     // Handle rebuild messages
     function handleRebuild(message) {
-        const statusIndicator = document.getElementById('orbit-hmr-status') || 
-            createStatusIndicator();
+        const statusIndicator = getStatusIndicator();
         
         if (message.status === 'started') {
             log.info('Project rebuild started');
@@ -125,8 +203,9 @@
             statusIndicator.className = 'orbit-hmr-status rebuilding';
         }
         else if (message.status === 'completed') {
-            log.info('Project rebuild completed successfully');
-            statusIndicator.textContent = 'Rebuild successful';
+            const took = formatDuration(message.durationMs);
+            log.info(`Project rebuild completed successfully${took}`);
+            statusIndicator.textContent = `Rebuild successful${took}`;
             statusIndicator.className = 'orbit-hmr-status success';
             
             // Hide the indicator after a delay
@@ -135,17 +214,58 @@
             }, 3000);
         }
         else if (message.status === 'failed') {
-            log.error('Project rebuild failed');
+            log.error(`Project rebuild failed${formatDuration(message.durationMs)}`);
+            if (message.output) {
+                console.error(message.output);
+            }
             statusIndicator.textContent = 'Rebuild failed';
             statusIndicator.className = 'orbit-hmr-status error';
         }
-    }    // Handle HMR updates
+    }
+
+    // Handle errors that stopped the build from running
+    function handleBuildError(message) {
+        log.error(`Build error: ${message.message}`);
+        const statusIndicator = getStatusIndicator();
+        statusIndicator.textContent = 'Build error, see console';
+        statusIndicator.className = 'orbit-hmr-status error';
+    }
+
+    // Format a rebuild duration as a suffix like " (2.3s)"
+    function formatDuration(durationMs) {
+        if (typeof durationMs !== 'number') {
+            return '';
+        }
+        return ` (${(durationMs / 1000).toFixed(1)}s)`;
+    }
+
+    // Batch HMR updates that arrive within the debounce window
+    function queueHmrUpdate(modules, preserveState) {
+        if (!config.debounceMs) {
+            handleHmrUpdate({ modules, preserveState });
+            return;
+        }
+
+        // A batch only keeps state if every update in it allows it
+        modules.forEach((module) => pendingModules.add(module));
+        pendingPreserveState = pendingPreserveState && preserveState;
+        clearTimeout(pendingTimer);
+        pendingTimer = setTimeout(() => {
+            const batched = Array.from(pendingModules);
+            const batchPreserveState = pendingPreserveState;
+            pendingModules.clear();
+            pendingPreserveState = true;
+            handleHmrUpdate({ modules: batched, preserveState: batchPreserveState });
+        }, config.debounceMs);
+    }
+
+    // Handle HMR updates
     function handleHmrUpdate(message) {
-        log.info(`HMR update for modules: ${message.modules.join(', ')}`);
+        const mode = message.preserveState ? 'preserving state' : 'remounting';
+        log.info(`HMR update for modules (${mode}): ${message.modules.join(', ')}`);
         
         // Create a status indicator to show HMR activity
-        const statusIndicator = document.getElementById('orbit-hmr-status') || 
-            createStatusIndicator();
+        const statusIndicator = getStatusIndicator();
         
         statusIndicator.textContent = 'Applying HMR updates...';
         statusIndicator.className = 'orbit-hmr-status rebuilding';
@@ -153,7 +273,9 @@
         try {
             // Apply HMR updates - the actual implementation depends on your framework
             if (window.__ORBIT_APPLY_HMR) {
-                const result = window.__ORBIT_APPLY_HMR(message.modules);
+                const result = window.__ORBIT_APPLY_HMR(message.modules, {
+                    preserveState: message.preserveState
+                });
                 
                 // Handle Promise or direct result
                 if (result instanceof Promise) {
@@ -197,6 +319,14 @@
         }
     }
 
+    // Get the status indicator, or a detached one when notifications are off
+    function getStatusIndicator() {
+        if (!config.showNotifications) {
+            return document.createElement('div');
+        }
+        return document.getElementById('orbit-hmr-status') || createStatusIndicator();
+    }
+
     // Create a status indicator element
     function createStatusIndicator() {
         const indicator = document.createElement('div');
@@ -238,8 +368,11 @@
         return indicator;
     }
 
-    // Attempt to reconnect to the WebSocket server
-    function attemptReconnect(url) {
+    // Attempt to reconnect to the WebSocket server with exponential backoff
+    function attemptReconnect() {
+        if (protocolMismatch) {
+            return;
+        }
         if (reconnectAttempts >= config.reconnectMaxAttempts) {
             log.error('Maximum reconnection attempts reached');
             return;
@@ -247,11 +380,18 @@
         
         reconnectAttempts++;
         
-        const delay = config.reconnectInterval;
+        const delay = Math.min(
+            config.reconnectInterval * 2 ** (reconnectAttempts - 1),
+            config.reconnectMaxDelay
+        );
         log.info(`Attempting to reconnect in ${delay}ms (attempt ${reconnectAttempts}/${config.reconnectMaxAttempts})`);
         
         setTimeout(() => {
-            connectWebSocket(url);
+            // Pick up settings changed while the server was restarting
+            loadConfig().then((settings) => {
+                applyConfig(settings);
+                connectWebSocket(webSocketUrl());
+            });
         }, delay);
     }
 
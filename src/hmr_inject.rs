@@ -2,45 +2,141 @@
 
 use anyhow::Result;
 use log::debug;
-use std::io::Read;
+use serde::Serialize;
 use std::path::Path;
 
+use crate::config::HmrConfig;
+
 /// The HMR client script as a static string
 pub const HMR_CLIENT_SCRIPT: &str = include_str!("hmr_client.js");
 
+/// Path the HMR client fetches its settings from
+pub const HMR_CONFIG_PATH: &str = "/__orbit/hmr-config.json";
+
 /// Checks if a file is an HTML file based on extension
 pub fn is_html_file(path: &Path) -> bool {
     if let Some(ext) = path.extension() {
-        ext.to_string_lossy().eq_ignore_ascii_case("html")
+        let ext = ext.to_string_lossy();
+        ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm")
     } else {
         false
     }
 }
 
+/// How the HMR client is added to HTML pages
+#[derive(Debug, Clone, Default)]
+pub struct InjectOptions {
+    /// Embed the client script instead of linking `/__orbit_hmr_client.js`
+    pub inline: bool,
+    /// Nonce attribute for pages with a `script-src 'nonce-...'` policy
+    pub nonce: Option<String>,
+    /// Settings embedded into inlined clients
+    pub client: ClientConfig,
+}
+
+impl InjectOptions {
+    /// Build injection options from the HMR configuration
+    pub fn from_config(config: &HmrConfig) -> Self {
+        Self {
+            inline: config.inline_client,
+            nonce: config.csp_nonce.clone(),
+            client: ClientConfig::from_config(config),
+        }
+    }
+}
+
+/// HMR settings that control the browser client's behavior
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientConfig {
+    /// Reconnection attempts before the client gives up
+    pub max_retries: u32,
+    /// Delay used to batch updates that arrive in quick succession
+    pub debounce_ms: u64,
+    /// Whether to show the status indicator in the page
+    pub show_notifications: bool,
+}
+
+impl ClientConfig {
+    /// Take the client settings from the HMR configuration
+    pub fn from_config(config: &HmrConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            debounce_ms: config.debounce_ms,
+            show_notifications: config.show_notifications,
+        }
+    }
+
+    /// Serialize the settings for a server on `port` as JSON
+    pub fn to_json(&self, port: u16) -> String {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Payload<'a> {
+            ws_port: u32,
+            #[serde(flatten)]
+            config: &'a ClientConfig,
+        }
+
+        serde_json::to_string(&Payload {
+            ws_port: u32::from(port) + 1,
+            config: self,
+        })
+        .expect("HMR client config is always serializable")
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self::from_config(&HmrConfig::default())
+    }
+}
+
 /// Inject HMR client code into an HTML response
-pub fn inject_hmr_client(html_content: &str, _port: u16) -> Result<String> {
+#[allow(dead_code)] // Used in tests and maintenance operations
+pub fn inject_hmr_client(html_content: &str, port: u16) -> Result<String> {
+    inject_hmr_client_with_options(html_content, port, &InjectOptions::default())
+}
+
+/// Inject HMR client code into an HTML response using the given options
+pub fn inject_hmr_client_with_options(
+    html_content: &str,
+    port: u16,
+    options: &InjectOptions,
+) -> Result<String> {
     debug!("Injecting HMR client code into HTML response");
 
     // Check if the HTML content already has the HMR client script
-    if html_content.contains("__ORBIT_REGISTER_HMR_HANDLER") {
+    if html_content.contains("__ORBIT_REGISTER_HMR_HANDLER")
+        || html_content.contains("/__orbit_hmr_client.js")
+    {
         debug!("HMR client code already present in HTML");
         return Ok(html_content.to_owned());
     }
 
+    // Pages with a CSP meta tag need the HMR client allowed explicitly
+    let mut options = options.clone();
+    let html_content = &match find_csp_meta_content(html_content) {
+        Some(range) => {
+            if options.inline && options.nonce.is_none() {
+                options.nonce = Some(crate::utils::crypto::random_id());
+            }
+            let policy = allow_hmr_client(&html_content[range.clone()], port, &options);
+            debug!("Extended Content-Security-Policy meta tag for HMR: {policy}");
+            format!(
+                "{}{policy}{}",
+                &html_content[..range.start],
+                &html_content[range.end..]
+            )
+        }
+        None => html_content.to_owned(),
+    };
+
+    let script = hmr_script_tag(port, &options);
+
     // Find where to inject the script (before closing </body> tag)
     if let Some(pos) = html_content.to_lowercase().rfind("</body>") {
         let (before, after) = html_content.split_at(pos);
 
-        // We can either embed the script or reference it as an external file
-        // Using external file is often better for debugging
-        let script = format!(
-            "<script type=\"text/javascript\" src=\"/__orbit_hmr_client.js?v={}\"></script>\n",
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        );
-
         // Inject the script
         let injected_html = format!("{before}{script}{after}");
         debug!("HMR client code injected successfully");
@@ -49,16 +145,141 @@ pub fn inject_hmr_client(html_content: &str, _port: u16) -> Result<String> {
     } else {
         // If no </body> tag is found, append the script at the end
         debug!("No </body> tag found, appending HMR client code at the end");
-        let script = format!(
-            "<script type=\"text/javascript\" src=\"/__orbit_hmr_client.js?v={}\"></script>\n",
+        let injected_html = format!("{html_content}{script}");
+
+        Ok(injected_html)
+    }
+}
+
+/// Locate the `content` value of a `Content-Security-Policy` meta tag
+fn find_csp_meta_content(html: &str) -> Option<std::ops::Range<usize>> {
+    // ASCII lowercasing keeps byte offsets aligned with the original
+    let lower = html.to_ascii_lowercase();
+    let mut search_from = 0;
+
+    while let Some(offset) = lower[search_from..].find("<meta") {
+        let start = search_from + offset;
+        let end = start + lower[start..].find('>')?;
+        let tag = &lower[start..end];
+        search_from = end;
+
+        if !tag.contains("content-security-policy") {
+            continue;
+        }
+
+        let attr = tag.find("content=")?;
+        let quote_pos = start + attr + "content=".len();
+        let quote = html[quote_pos..].chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value_start = quote_pos + 1;
+        let value_end = value_start + html[value_start..end].find(quote)?;
+        return Some(value_start..value_end);
+    }
+
+    None
+}
+
+/// Extend a CSP so the HMR script and its WebSocket connection are permitted
+fn allow_hmr_client(policy: &str, port: u16, options: &InjectOptions) -> String {
+    let script_source = match (&options.nonce, options.inline) {
+        (Some(nonce), _) => format!("'nonce-{nonce}'"),
+        (None, true) => "'unsafe-inline'".to_string(),
+        (None, false) => "'self'".to_string(),
+    };
+    let ws_source = format!(
+        "ws://*:{} wss://*:{}",
+        u32::from(port) + 1,
+        u32::from(port) + 1
+    );
+
+    let mut directives: Vec<(String, String)> = policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(
+            |directive| match directive.split_once(char::is_whitespace) {
+                Some((name, sources)) => (name.to_ascii_lowercase(), sources.trim().to_string()),
+                None => (directive.to_ascii_lowercase(), String::new()),
+            },
+        )
+        .collect();
+    let default_sources = directives
+        .iter()
+        .find(|(name, _)| name == "default-src")
+        .map(|(_, sources)| sources.clone());
+
+    for (directive, source) in [("script-src", &script_source), ("connect-src", &ws_source)] {
+        match directives.iter_mut().find(|(name, _)| name == directive) {
+            Some((_, sources)) => *sources = add_source(sources, source),
+            // Without the directive the browser falls back to default-src
+            None => {
+                if let Some(default_sources) = &default_sources {
+                    directives.push((directive.to_string(), add_source(default_sources, source)));
+                }
+            }
+        }
+    }
+
+    directives
+        .iter()
+        .map(|(name, sources)| {
+            if sources.is_empty() {
+                name.clone()
+            } else {
+                format!("{name} {sources}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Add a source to a CSP source list, replacing `'none'`
+fn add_source(sources: &str, source: &str) -> String {
+    if sources
+        .split_whitespace()
+        .any(|existing| existing == source)
+    {
+        return sources.to_string();
+    }
+    let existing: Vec<&str> = sources
+        .split_whitespace()
+        .filter(|existing| !existing.eq_ignore_ascii_case("'none'"))
+        .collect();
+    if existing.is_empty() {
+        source.to_string()
+    } else {
+        format!("{} {source}", existing.join(" "))
+    }
+}
+
+/// Build the `<script>` tag that loads or embeds the HMR client
+fn hmr_script_tag(port: u16, options: &InjectOptions) -> String {
+    let nonce = options
+        .nonce
+        .as_ref()
+        .map(|nonce| format!(" nonce=\"{nonce}\""))
+        .unwrap_or_default();
+
+    if options.inline {
+        // Pin the WebSocket port so the client also works when the page is
+        // opened from somewhere other than the dev server
+        format!(
+            "<script type=\"text/javascript\"{nonce}>\nwindow.__ORBIT_HMR_WS_PORT__ = {};\nwindow.__ORBIT_HMR_CONFIG__ = {};\n{}\n</script>\n",
+            u32::from(port) + 1,
+            options.client.to_json(port),
+            HMR_CLIENT_SCRIPT
+        )
+    } else {
+        // Using external file is often better for debugging
+        format!(
+            "<script type=\"text/javascript\"{nonce} src=\"/__orbit_hmr_client.js?v={}\"></script>\n",
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs()
-        );
-        let injected_html = format!("{html_content}{script}");
-
-        Ok(injected_html)
+        )
     }
 }
 
@@ -67,17 +288,87 @@ pub fn get_hmr_client_js() -> &'static str {
     HMR_CLIENT_SCRIPT
 }
 
-/// Process HTML file and inject HMR client code
-pub fn process_html_file(path: &Path, port: u16) -> Result<Vec<u8>> {
-    debug!("Processing HTML file: {path:?}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_external_client() {
+        let html = "<html><body><h1>Hi</h1></body></html>";
+        let injected = inject_hmr_client(html, 3000).unwrap();
+        assert!(injected.contains("src=\"/__orbit_hmr_client.js"));
+        assert!(injected.ends_with("</script>\n</body></html>"));
 
-    // Read HTML file content
-    let mut file = std::fs::File::open(path)?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
+        // Injecting twice leaves the page unchanged
+        assert_eq!(inject_hmr_client(&injected, 3000).unwrap(), injected);
+    }
 
-    // Inject HMR client code
-    let injected_content = inject_hmr_client(&content, port)?;
+    #[test]
+    fn test_inject_inline_client_with_nonce() {
+        let html = "<html><body></body></html>";
+        let options = InjectOptions {
+            inline: true,
+            nonce: Some("abc123".to_string()),
+            ..Default::default()
+        };
+        let injected = inject_hmr_client_with_options(html, 3000, &options).unwrap();
+        assert!(injected.contains("<script type=\"text/javascript\" nonce=\"abc123\">"));
+        assert!(injected.contains("window.__ORBIT_HMR_WS_PORT__ = 3001;"));
+        assert!(injected.contains("window.__ORBIT_HMR_CONFIG__ = {\"wsPort\":3001,"));
+        assert!(injected.contains("__ORBIT_REGISTER_HMR_HANDLER"));
+        assert!(!injected.contains("/__orbit_hmr_client.js"));
+    }
 
-    Ok(injected_content.into_bytes())
+    #[test]
+    fn test_inject_extends_csp_meta() {
+        let html = concat!(
+            "<html><head><meta http-equiv=\"Content-Security-Policy\" ",
+            "content=\"default-src 'self'; script-src 'none'\"></head>",
+            "<body></body></html>"
+        );
+        let injected = inject_hmr_client(html, 3000).unwrap();
+        assert!(injected.contains("script-src 'self'"));
+        assert!(!injected.contains("'none'"));
+        assert!(injected.contains("connect-src 'self' ws://*:3001 wss://*:3001"));
+
+        // Inline scripts get a generated nonce that matches the policy
+        let options = InjectOptions {
+            inline: true,
+            nonce: None,
+            ..Default::default()
+        };
+        let injected = inject_hmr_client_with_options(html, 3000, &options).unwrap();
+        let nonce_start = injected.find("'nonce-").unwrap() + "'nonce-".len();
+        let nonce_len = injected[nonce_start..].find('\'').unwrap();
+        let nonce = &injected[nonce_start..nonce_start + nonce_len];
+        assert!(injected.contains(&format!("nonce=\"{nonce}\">")));
+    }
+
+    #[test]
+    fn test_client_config_json() {
+        let config = HmrConfig {
+            max_retries: 7,
+            debounce_ms: 250,
+            show_notifications: false,
+            ..HmrConfig::default()
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&ClientConfig::from_config(&config).to_json(3000)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "wsPort": 3001,
+                "maxRetries": 7,
+                "debounceMs": 250,
+                "showNotifications": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_inject_without_csp_is_unchanged() {
+        let html = "<html><head><meta charset=\"utf-8\"></head><body></body></html>";
+        let injected = inject_hmr_client(html, 3000).unwrap();
+        assert!(injected.starts_with("<html><head><meta charset=\"utf-8\"></head><body>"));
+    }
 }
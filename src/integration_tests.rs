@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use crate::config::OrbitonConfig;
-    use crate::dev_server::DevServer;
+    use crate::dev_server::{DevServer, DevServerHandle};
     use crate::hmr::HmrContext;
     use std::time::Duration;
     use tempfile::tempdir;
@@ -39,13 +39,351 @@ mod tests {
         hmr_context.mark_modules_updated();
         assert!(!hmr_context.needs_update());
 
+        // Identical content must not be recorded as a change
+        std::fs::write(&test_file, "// Test module content").unwrap();
+        assert!(hmr_context.record_file_change(&test_file).is_none());
+        assert!(!hmr_context.needs_update());
+
         // Test clearing updates
+        std::fs::write(&test_file, "// Updated module content").unwrap();
         let _ = hmr_context.record_file_change(&test_file);
         assert!(hmr_context.needs_update());
         hmr_context.clear();
         assert!(!hmr_context.needs_update());
     }
 
+    #[test]
+    fn test_hmr_full_reload_files() {
+        let temp_dir = tempdir().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        let cargo_toml = project_root.join("Cargo.toml");
+        std::fs::write(&cargo_toml, "[package]\nname = \"app\"\n").unwrap();
+        let style_file = project_root.join("src/theme.css");
+        std::fs::create_dir_all(project_root.join("src")).unwrap();
+        std::fs::write(&style_file, "body {}").unwrap();
+
+        let hmr_context = HmrContext::new(project_root);
+
+        // Cargo.toml requests a full reload instead of a module update
+        assert_eq!(
+            hmr_context.record_file_change(&cargo_toml).as_deref(),
+            Some(crate::hmr::FULL_RELOAD_MODULE)
+        );
+        assert!(hmr_context.needs_update());
+
+        // Unwatched extensions are ignored until configured
+        assert!(hmr_context.record_file_change(&style_file).is_none());
+
+        let mut hmr_config = crate::config::HmrConfig::default();
+        hmr_config.watch_extensions.push("css".to_string());
+        hmr_context.configure(&hmr_config).unwrap();
+        assert_eq!(
+            hmr_context.record_file_change(&style_file).as_deref(),
+            Some("theme")
+        );
+    }
+
+    #[test]
+    fn test_hmr_file_removed() {
+        let temp_dir = tempdir().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+        let test_file = project_root.join("src/components/button.rs");
+        std::fs::create_dir_all(test_file.parent().unwrap()).unwrap();
+        std::fs::write(&test_file, "pub struct Button;").unwrap();
+
+        let hmr_context = HmrContext::new(project_root);
+        hmr_context.record_file_change(&test_file);
+        assert_eq!(hmr_context.get_pending_updates(), vec!["components/button"]);
+
+        // A removed module is no longer reported, but the page still reloads
+        std::fs::remove_file(&test_file).unwrap();
+        assert_eq!(
+            hmr_context.record_file_removed(&test_file).as_deref(),
+            Some("components/button")
+        );
+        assert_eq!(
+            hmr_context.get_pending_updates(),
+            vec![crate::hmr::FULL_RELOAD_MODULE]
+        );
+    }
+
+    #[test]
+    fn test_hmr_save_by_replacing_file() {
+        let temp_dir = tempdir().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+        let test_file = project_root.join("src/components/button.rs");
+        std::fs::create_dir_all(test_file.parent().unwrap()).unwrap();
+        std::fs::write(&test_file, "pub struct Button;").unwrap();
+
+        let hmr_context = HmrContext::new(project_root);
+        hmr_context.record_file_change(&test_file);
+        hmr_context.mark_modules_updated();
+
+        // Editors like vim save by removing the file and creating it again
+        std::fs::remove_file(&test_file).unwrap();
+        hmr_context.record_file_removed(&test_file);
+        std::fs::write(&test_file, "pub struct Button { label: String }").unwrap();
+        assert_eq!(
+            hmr_context.record_file_change(&test_file).as_deref(),
+            Some("components/button")
+        );
+
+        // The module is hot-swapped instead of reloading the page
+        assert_eq!(hmr_context.get_pending_updates(), vec!["components/button"]);
+    }
+
+    #[test]
+    fn test_hmr_file_renamed() {
+        let temp_dir = tempdir().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+        let old_file = project_root.join("src/button.rs");
+        let new_file = project_root.join("src/widgets/button.rs");
+        std::fs::create_dir_all(new_file.parent().unwrap()).unwrap();
+        std::fs::write(&old_file, "pub struct Button;").unwrap();
+
+        let hmr_context = HmrContext::new(project_root.clone());
+        hmr_context.record_file_change(&old_file);
+
+        // The entry moves to the module at the new path
+        std::fs::rename(&old_file, &new_file).unwrap();
+        assert_eq!(
+            hmr_context
+                .record_file_renamed(&old_file, &new_file)
+                .as_deref(),
+            Some("widgets/button")
+        );
+        assert_eq!(hmr_context.get_pending_updates(), vec!["widgets/button"]);
+
+        // Renaming out of the watched sources counts as a removal
+        let backup = project_root.join("button.rs.bak");
+        std::fs::rename(&new_file, &backup).unwrap();
+        assert!(hmr_context
+            .record_file_renamed(&new_file, &backup)
+            .is_none());
+        assert_eq!(
+            hmr_context.get_pending_updates(),
+            vec![crate::hmr::FULL_RELOAD_MODULE]
+        );
+    }
+
+    #[test]
+    fn test_hmr_extra_watch_dirs() {
+        let temp_dir = tempdir().unwrap();
+        let project_root = temp_dir.path().join("app");
+        let shared_file = temp_dir.path().join("shared/src/button.rs");
+        std::fs::create_dir_all(shared_file.parent().unwrap()).unwrap();
+        std::fs::write(&shared_file, "pub struct Button;").unwrap();
+
+        let hmr_context = HmrContext::new(project_root);
+
+        // Files outside the project are ignored unless their directory is watched
+        assert!(hmr_context.record_file_change(&shared_file).is_none());
+
+        let mut hmr_config = crate::config::HmrConfig::default();
+        hmr_config.watch_dirs.push("../shared".to_string());
+        hmr_context.configure(&hmr_config).unwrap();
+        let shared_file = temp_dir.path().join("app/../shared/src/button.rs");
+        assert_eq!(
+            hmr_context.record_file_change(&shared_file).as_deref(),
+            Some(crate::hmr::FULL_RELOAD_MODULE)
+        );
+    }
+
+    /// Start a dev server for `root` on a free local port
+    fn start_local_server(root: &std::path::Path) -> (DevServer, DevServerHandle) {
+        let mut config = OrbitonConfig::default();
+        config.dev_server.host = "127.0.0.1".to_string();
+        config.dev_server.port = 0;
+
+        let mut server = DevServer::from_config(root, &config).unwrap();
+        let handle = server.start_detached().unwrap();
+        (server, handle)
+    }
+
+    /// HTTP client that talks to local servers directly, ignoring proxy settings
+    fn local_http_client() -> reqwest::blocking::Client {
+        reqwest::blocking::Client::builder()
+            .no_proxy()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dev_server_serves_over_http() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join("index.html"),
+            "<html><body><h1>Hello</h1></body></html>",
+        )
+        .unwrap();
+
+        let (server, handle) = start_local_server(temp_dir.path());
+        let base_url = format!("http://127.0.0.1:{}", handle.port());
+        let http = local_http_client();
+
+        // HTML pages get the HMR client injected
+        let page = http.get(format!("{base_url}/")).send().unwrap();
+        assert!(page.status().is_success());
+        let body = page.text().unwrap();
+        assert!(body.contains("<h1>Hello</h1>"));
+        assert!(body.contains("/__orbit_hmr_client.js"));
+
+        let script = http
+            .get(format!("{base_url}/__orbit_hmr_client.js"))
+            .send()
+            .unwrap();
+        assert!(script.status().is_success());
+        let content_type = script.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(content_type.starts_with("application/javascript"));
+        assert!(script.text().unwrap().contains("WebSocket"));
+        assert_eq!(server.request_count(), 2);
+
+        handle.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_dev_server_custom_index_file() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("docs")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("docs/main.htm"),
+            "<html><body><h1>Docs</h1></body></html>",
+        )
+        .unwrap();
+
+        let (server, handle) = start_local_server(temp_dir.path());
+        let mut config = server.config();
+        config.dev_server.index_file = "main.htm".to_string();
+        server.update_config(config);
+
+        // Directories are served through the configured index, with HMR injected
+        let url = format!("http://127.0.0.1:{}/docs/", handle.port());
+        let page = local_http_client().get(url).send().unwrap();
+        assert!(page.status().is_success());
+        let body = page.text().unwrap();
+        assert!(body.contains("<h1>Docs</h1>"));
+        assert!(body.contains("/__orbit_hmr_client.js"));
+
+        handle.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_dev_server_head_requests() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("app.js"), "console.log('hello');").unwrap();
+
+        let (_server, handle) = start_local_server(temp_dir.path());
+        let url = format!("http://127.0.0.1:{}/app.js", handle.port());
+        let http = local_http_client();
+
+        // HEAD returns the GET headers without a body
+        let response = http.head(&url).send().unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.headers()["content-length"], "21");
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("application/javascript"));
+        assert!(response.bytes().unwrap().is_empty());
+
+        let response = http.post(&url).send().unwrap();
+        assert_eq!(response.status().as_u16(), 405);
+        assert_eq!(response.headers()["allow"], "GET, HEAD");
+
+        handle.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_dev_server_conditional_requests() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("style.css"), "body {}").unwrap();
+        std::fs::write(temp_dir.path().join("index.html"), "<html></html>").unwrap();
+
+        let (_server, handle) = start_local_server(temp_dir.path());
+        let base_url = format!("http://127.0.0.1:{}", handle.port());
+        let http = local_http_client();
+
+        let response = http.get(format!("{base_url}/style.css")).send().unwrap();
+        let etag = response.headers()["etag"].clone();
+
+        // A matching ETag is answered without the body
+        let response = http
+            .get(format!("{base_url}/style.css"))
+            .header("If-None-Match", etag.clone())
+            .send()
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 304);
+        assert_eq!(response.headers()["etag"], etag);
+
+        // Pages with the HMR client injected are never cached
+        let response = http.get(format!("{base_url}/index.html")).send().unwrap();
+        assert!(response.headers().get("etag").is_none());
+
+        handle.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_dev_server_precompressed_assets() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("app.wasm"), "raw").unwrap();
+        std::fs::write(temp_dir.path().join("app.wasm.br"), "brotli").unwrap();
+        std::fs::write(temp_dir.path().join("app.wasm.gz"), "gzip").unwrap();
+
+        let (_server, handle) = start_local_server(temp_dir.path());
+        let url = format!("http://127.0.0.1:{}/app.wasm", handle.port());
+        let http = local_http_client();
+        let fetch = |accept_encoding: &str| {
+            let response = http
+                .get(&url)
+                .header("Accept-Encoding", accept_encoding)
+                .send()
+                .unwrap();
+            let encoding = response
+                .headers()
+                .get("content-encoding")
+                .map(|value| value.to_str().unwrap().to_string());
+            assert_eq!(response.headers()["content-type"], "application/wasm");
+            (encoding, response.text().unwrap())
+        };
+
+        assert_eq!(
+            fetch("gzip, br"),
+            (Some("br".to_string()), "brotli".to_string())
+        );
+        assert_eq!(
+            fetch("gzip"),
+            (Some("gzip".to_string()), "gzip".to_string())
+        );
+        assert_eq!(
+            fetch("br;q=0, gzip"),
+            (Some("gzip".to_string()), "gzip".to_string())
+        );
+        assert_eq!(fetch("identity"), (None, "raw".to_string()));
+
+        handle.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_hmr_rolling_rebuild_average() {
+        let hmr_context = HmrContext::default();
+        assert_eq!(hmr_context.average_rebuild_duration(), None);
+
+        hmr_context.record_rebuild_duration(Duration::from_secs(60));
+        for _ in 0..10 {
+            hmr_context.record_rebuild_duration(Duration::from_secs(2));
+        }
+
+        // The slow first build has dropped out of the window
+        assert_eq!(
+            hmr_context.average_rebuild_duration(),
+            Some(Duration::from_secs(2))
+        );
+    }
+
     #[test]
     fn test_hmr_timestamp_functionality() {
         let temp_dir = tempdir().unwrap();
@@ -169,6 +507,7 @@ mod tests {
         std::thread::sleep(Duration::from_millis(150));
 
         // Add another change
+        std::fs::write(&main_file, "fn main() { println!(\"Hello again\"); }").unwrap();
         hmr_context.record_file_change(&main_file);
         let should_rebuild = hmr_context.should_rebuild(Duration::from_millis(100));
         assert!(should_rebuild); // Should rebuild after debounce time
@@ -213,6 +552,13 @@ mod tests {
 
         let result = server.send_rebuild_status("completed");
         let _ = result;
+
+        let result = server.send_rebuild_finished(
+            "failed",
+            Duration::from_millis(2300),
+            Some("error[E0308]: mismatched types"),
+        );
+        let _ = result;
     }
 
     #[test]
@@ -0,0 +1,47 @@
+// Library interface of orbiton, for driving builds and the dev server from other tools
+//
+// The `orbiton` binary is a thin wrapper over this crate.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+mod build_manifest;
+pub mod commands;
+pub mod config;
+mod dev_maintenance;
+pub mod dev_server;
+pub mod hmr;
+mod hmr_inject;
+#[cfg(test)]
+mod integration_tests;
+mod maintenance;
+pub mod output;
+mod parse_cache;
+pub mod snapshot;
+pub mod templates;
+mod test_hmr_module;
+mod utils;
+
+pub use commands::build::{BuildOptions, BuildTarget};
+pub use commands::dev::DevOptions;
+pub use config::OrbitonConfig;
+pub use dev_server::{DevServer, DevServerHandle};
+pub use hmr::HmrContext;
+pub use templates::project_templates::TemplateManager;
+pub use utils::network;
+
+/// Build a project for a target with default options
+///
+/// Uses `build.release` from the config. Returns the output directory.
+pub fn build(project_dir: &Path, config: &OrbitonConfig, target: BuildTarget) -> Result<PathBuf> {
+    let options = BuildOptions {
+        release: config.build.release,
+        ..BuildOptions::default()
+    };
+    commands::build::run(project_dir, config, target, &options)
+}
+
+/// Run the development server for a project, blocking until the process is interrupted
+pub fn dev(project_dir: &Path, config: &OrbitonConfig) -> Result<()> {
+    commands::dev::run(project_dir, config, &DevOptions::default())
+}
@@ -1,20 +1,13 @@
 // Main entry point for the orbiton CLI tool
 
-use clap::{Parser, Subcommand};
+use anyhow::Context;
+use clap::{CommandFactory, Parser, Subcommand};
 use console::style;
 use log::info;
+use std::path::PathBuf;
 
-mod commands;
-mod config;
-mod dev_server;
-mod hmr;
-mod hmr_inject;
-#[cfg(test)]
-mod integration_tests;
-mod maintenance;
-mod templates;
-mod test_hmr_module;
-mod utils;
+use orbiton::output::{self, ColorChoice, Verbosity};
+use orbiton::{commands, config, status};
 
 /// Version of the orbiton CLI
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -29,6 +22,26 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Only print errors, warnings and explicitly requested output
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// When to use colors
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Configuration profile to apply from .orbiton.toml (overrides ORBITON_PROFILE)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Configuration file to use instead of searching for .orbiton.toml (overrides ORBITON_CONFIG)
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Forbid network access, including toolchain installs (also enabled by CARGO_NET_OFFLINE)
+    #[arg(long, global = true)]
+    offline: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -38,12 +51,21 @@ enum Commands {
     /// Create a new Orbit project
     New(commands::new::NewArgs),
 
+    /// Add a component to an existing project
+    Add(commands::add::AddArgs),
+
     /// Start the development server
     Dev(commands::dev::DevArgs),
 
+    /// Check the development environment for common problems
+    Doctor(commands::doctor::DoctorArgs),
+
     /// Build the project
     Build(commands::build::BuildArgs),
 
+    /// Serve a production build locally
+    Preview(commands::preview::PreviewArgs),
+
     /// Configure the renderer
     Renderer(commands::renderer::RendererArgs),
     /// Run tests for the project
@@ -54,30 +76,100 @@ enum Commands {
 
     /// Perform maintenance operations
     Maintenance(commands::maintenance::MaintenanceArgs),
+
+    /// Upgrade the Orbit dependencies in Cargo.toml
+    Upgrade(commands::upgrade::UpgradeArgs),
+
+    /// Format the sections of .orbit components
+    Fmt(commands::fmt::FmtArgs),
+
+    /// Check .orbit components for common mistakes
+    Lint(commands::lint::LintArgs),
+
+    /// Work with project templates
+    Templates(commands::templates::TemplatesArgs),
+
+    /// Generate shell completion scripts
+    #[command(hide = true)]
+    Completions(commands::completions::CompletionsArgs),
 }
 
 fn main() -> anyhow::Result<()> {
     // Parse the command line arguments
     let cli = Cli::parse();
 
+    output::configure_colors(cli.color);
+
+    // Machine-readable output must not be mixed with status lines
+    let machine_readable = match &cli.command {
+        Commands::Config(args) => args.is_machine_readable(),
+        Commands::Maintenance(args) => args.is_machine_readable(),
+        Commands::Test(args) => args.is_machine_readable(),
+        Commands::Completions(_) => true,
+        _ => false,
+    };
+    let verbosity = if cli.quiet || machine_readable {
+        Verbosity::Quiet
+    } else if cli.verbose {
+        Verbosity::Verbose
+    } else {
+        Verbosity::Normal
+    };
+    output::set_verbosity(verbosity);
+
     // Initialize logging
-    if cli.verbose {
-        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
+    let default_filter = match verbosity {
+        Verbosity::Quiet => "warn",
+        Verbosity::Normal => "info",
+        Verbosity::Verbose => "debug",
+    };
+    let write_style = if console::colors_enabled_stderr() {
+        env_logger::WriteStyle::Always
     } else {
-        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+        env_logger::WriteStyle::Never
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+        .write_style(write_style)
+        .init();
+
+    // Make an explicit config file visible to every config load
+    if let Some(path) = &cli.config {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("Config file not found: {}", path.display()))?;
+        std::env::set_var(config::CONFIG_ENV_VAR, path);
+    }
+
+    // Spawned cargo processes read the same variable
+    if cli.offline {
+        std::env::set_var(orbiton::network::OFFLINE_ENV_VAR, "true");
+    }
+
+    // Make the selected profile visible to every config load
+    if let Some(profile) = &cli.profile {
+        std::env::set_var(config::PROFILE_ENV_VAR, profile);
     }
 
     // Print welcome message
-    println!("{} v{}", style("orbiton").bold().green(), VERSION);
+    status!("{} v{}", style("orbiton").bold().green(), VERSION);
 
     // Execute the appropriate command
     match cli.command {
         Commands::New(args) => {
             commands::new::execute(args)?;
         }
+        Commands::Add(args) => {
+            commands::add::execute(args)?;
+        }
         Commands::Dev(args) => {
             commands::dev::execute(args)?;
         }
+        Commands::Doctor(args) => {
+            commands::doctor::execute(args)?;
+        }
+        Commands::Preview(args) => {
+            commands::preview::execute(args)?;
+        }
         Commands::Build(args) => {
             commands::build::execute(args)?;
         }
@@ -93,6 +185,21 @@ fn main() -> anyhow::Result<()> {
         Commands::Maintenance(args) => {
             commands::maintenance::execute(args)?;
         }
+        Commands::Upgrade(args) => {
+            commands::upgrade::execute(args)?;
+        }
+        Commands::Fmt(args) => {
+            commands::fmt::execute(args)?;
+        }
+        Commands::Lint(args) => {
+            commands::lint::execute(args)?;
+        }
+        Commands::Templates(args) => {
+            commands::templates::execute(args)?;
+        }
+        Commands::Completions(args) => {
+            commands::completions::execute(args, &mut Cli::command())?;
+        }
     }
     info!("Command completed successfully");
     Ok(())
@@ -103,9 +210,65 @@ fn main() -> anyhow::Result<()> {
 pub fn show_help_info() {
     println!("Available commands:");
     println!("  new         - Create a new Orbit project");
+    println!("  add         - Add a component to a project");
     println!("  dev         - Start development server");
+    println!("  doctor      - Check development environment");
     println!("  build       - Build project");
+    println!("  preview     - Serve a production build");
     println!("  test        - Run tests");
     println!("  config      - Manage configuration");
     println!("  maintenance - Perform maintenance operations");
+    println!("  upgrade     - Upgrade Orbit dependencies");
+    println!("  fmt         - Format .orbit components");
+    println!("  lint        - Check .orbit components");
+    println!("  templates   - Validate project templates");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_completions_cover_cli() {
+        let mut script = Vec::new();
+        commands::completions::write_script(
+            clap_complete::Shell::Bash,
+            &mut Cli::command(),
+            &mut script,
+        );
+
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("orbiton,dev)"));
+        assert!(script.contains("--offline"));
+        assert!(script.contains("--port"));
+    }
+
+    #[test]
+    fn test_quiet_conflicts_with_verbose() {
+        let cli = Cli::try_parse_from(["orbiton", "doctor", "--quiet"]).unwrap();
+        assert!(cli.quiet);
+        assert!(Cli::try_parse_from(["orbiton", "-q", "-v", "doctor"]).is_err());
+    }
+
+    #[test]
+    fn test_config_flag() {
+        let cli = Cli::try_parse_from(["orbiton", "build", "--config", "ci/orbiton.toml"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("ci/orbiton.toml")));
+        let cli = Cli::try_parse_from(["orbiton", "--config", "a.toml", "config", "show"]).unwrap();
+        assert!(matches!(cli.command, Commands::Config(_)));
+    }
+
+    #[test]
+    fn test_color_flag() {
+        let cli = Cli::try_parse_from(["orbiton", "doctor"]).unwrap();
+        assert_eq!(cli.color, ColorChoice::Auto);
+        let cli = Cli::try_parse_from(["orbiton", "build", "--color", "never"]).unwrap();
+        assert_eq!(cli.color, ColorChoice::Never);
+        assert!(Cli::try_parse_from(["orbiton", "--color", "sometimes", "doctor"]).is_err());
+    }
 }
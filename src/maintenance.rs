@@ -75,7 +75,9 @@ impl MaintenanceManager {
     #[allow(dead_code)] // Used in tests and maintenance operations
     pub fn apply_config_overrides(&mut self, overrides: OrbitonConfig) {
         info!("Applying configuration overrides");
-        self.config.merge_with(&overrides);
+        for conflict in self.config.merge_with(&overrides) {
+            info!("Overriding configuration value {conflict}");
+        }
 
         println!(
             "{} Configuration updated with overrides",
@@ -0,0 +1,119 @@
+// Cache of Rust code generated from .orbit files, keyed on content hash
+
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::crypto::content_hash;
+
+/// Directory, relative to the project root, holding cached generated code
+pub const PARSE_CACHE_DIR: &str = ".orbiton/parse-cache";
+
+/// On-disk cache of generated Rust code for `.orbit` files
+///
+/// Each source file has one entry, named after the hash of its path. The entry
+/// starts with a header recording the hash of the source it was generated
+/// from, so an edited file replaces its stale entry instead of piling up new
+/// ones.
+#[derive(Debug, Clone)]
+pub struct ParseCache {
+    dir: PathBuf,
+}
+
+impl ParseCache {
+    /// Open the cache for a project
+    pub fn new(project_dir: &Path) -> Self {
+        Self {
+            dir: project_dir.join(PARSE_CACHE_DIR),
+        }
+    }
+
+    /// Return the cached code for `path` if it was generated from `source`
+    pub fn get(&self, path: &Path, source: &[u8]) -> Option<String> {
+        let content = fs::read_to_string(self.entry_path(path)).ok()?;
+        let (header, code) = content.split_once('\n')?;
+        if header == Self::header(source) {
+            debug!("Parse cache hit for {path:?}");
+            Some(code.to_string())
+        } else {
+            debug!("Parse cache entry for {path:?} is stale");
+            None
+        }
+    }
+
+    /// Store the code generated for `path` from `source`, replacing any stale entry
+    pub fn put(&self, path: &Path, source: &[u8], code: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create parse cache: {:?}", self.dir))?;
+
+        let entry_path = self.entry_path(path);
+        fs::write(&entry_path, format!("{}\n{code}", Self::header(source)))
+            .with_context(|| format!("Failed to write parse cache entry: {entry_path:?}"))
+    }
+
+    /// Return cached code for `path`, generating and storing it on a miss
+    ///
+    /// The flag is `true` when the code came from the cache.
+    pub fn get_or_generate(
+        &self,
+        path: &Path,
+        source: &[u8],
+        generate: impl FnOnce() -> Result<String>,
+    ) -> Result<(String, bool)> {
+        if let Some(code) = self.get(path, source) {
+            return Ok((code, true));
+        }
+
+        let code = generate()?;
+        self.put(path, source, &code)?;
+        Ok((code, false))
+    }
+
+    fn entry_path(&self, path: &Path) -> PathBuf {
+        let key = content_hash(path.to_string_lossy().as_bytes());
+        self.dir.join(format!("{}.rs", &key[..16]))
+    }
+
+    /// Header tying an entry to its source and the orbiton version that produced it
+    fn header(source: &[u8]) -> String {
+        format!(
+            "// orbiton {} source {}",
+            env!("CARGO_PKG_VERSION"),
+            content_hash(source)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_cache_invalidation() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cache = ParseCache::new(temp_dir.path());
+        let path = Path::new("src/components/button.orbit");
+
+        let (code, hit) = cache.get_or_generate(path, b"v1", || Ok("// one".to_string()))?;
+        assert_eq!(code, "// one");
+        assert!(!hit);
+
+        // Unchanged source is served from the cache without regenerating
+        let (code, hit) = cache.get_or_generate(path, b"v1", || unreachable!())?;
+        assert_eq!(code, "// one");
+        assert!(hit);
+
+        // Changed source replaces the entry
+        let (code, hit) = cache.get_or_generate(path, b"v2", || Ok("// two".to_string()))?;
+        assert_eq!(code, "// two");
+        assert!(!hit);
+        assert_eq!(
+            fs::read_dir(temp_dir.path().join(PARSE_CACHE_DIR))?.count(),
+            1
+        );
+
+        Ok(())
+    }
+}
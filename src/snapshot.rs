@@ -0,0 +1,182 @@
+// Snapshot testing for component render output
+//
+// Tests call `assert_snapshot` with a name and the rendered output. Snapshots
+// are stored under `tests/snapshots/` of the crate being tested; a mismatch
+// fails the test unless `orbiton test --update-snapshots` is running, in which
+// case the stored snapshot is rewritten.
+
+use anyhow::{Context, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Environment variable that switches snapshot assertions to update mode
+///
+/// Set by `orbiton test --update-snapshots`.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "ORBITON_UPDATE_SNAPSHOTS";
+
+/// Directory holding snapshots, relative to the crate root
+pub const SNAPSHOT_DIR: &str = "tests/snapshots";
+
+/// What happened to a snapshot that passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The output matched the stored snapshot
+    Matched,
+    /// The stored snapshot was written or rewritten in update mode
+    Updated,
+}
+
+/// Compare `actual` with the snapshot called `name`, panicking on a mismatch
+///
+/// Snapshots live in `tests/snapshots/<name>.snap` of the crate under test.
+/// Use `/` in `name` to group snapshots in subdirectories.
+///
+/// ```no_run
+/// let html = "<button>Save</button>";
+/// orbiton::snapshot::assert_snapshot("button/default", html);
+/// ```
+#[track_caller]
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    if let Err(err) = check_snapshot(&root.join(SNAPSHOT_DIR), name, actual, update_mode()) {
+        panic!("{err:#}");
+    }
+}
+
+/// Whether snapshots are being updated rather than compared
+pub fn update_mode() -> bool {
+    std::env::var(UPDATE_SNAPSHOTS_ENV_VAR).is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+}
+
+/// Compare or update the snapshot called `name` in `dir`
+///
+/// Returns an error describing the difference when the output doesn't match,
+/// or when the snapshot doesn't exist outside update mode.
+pub fn check_snapshot(
+    dir: &Path,
+    name: &str,
+    actual: &str,
+    update: bool,
+) -> Result<SnapshotOutcome> {
+    let path = snapshot_path(dir, name)?;
+    let actual = actual.replace("\r\n", "\n");
+
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {parent:?}"))?;
+        }
+        std::fs::write(&path, &actual)
+            .with_context(|| format!("Failed to write snapshot {path:?}"))?;
+        return Ok(SnapshotOutcome::Updated);
+    }
+
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "Snapshot '{name}' does not exist yet; run `orbiton test --update-snapshots` to record {}",
+            path.display()
+        ));
+    }
+    let expected = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read snapshot {path:?}"))?
+        .replace("\r\n", "\n");
+    if expected == actual {
+        return Ok(SnapshotOutcome::Matched);
+    }
+
+    Err(anyhow::anyhow!(
+        "Snapshot '{name}' does not match {}\n{}\nRun `orbiton test --update-snapshots` if the change is intended",
+        path.display(),
+        line_diff(&expected, &actual)
+    ))
+}
+
+/// File of a snapshot, refusing names that would leave the snapshot directory
+fn snapshot_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let relative = Path::new(name);
+    let valid = !name.is_empty()
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+        return Err(anyhow::anyhow!(
+            "Invalid snapshot name '{name}': use a relative name like 'button/default'"
+        ));
+    }
+    Ok(dir.join(format!("{name}.snap")))
+}
+
+/// Lines that differ between the stored and the actual output
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut diff = String::new();
+    for line in 0..expected.len().max(actual.len()) {
+        let (before, after) = (expected.get(line), actual.get(line));
+        if before == after {
+            continue;
+        }
+        diff.push_str(&format!("@@ line {} @@\n", line + 1));
+        if let Some(before) = before {
+            diff.push_str(&format!("-{before}\n"));
+        }
+        if let Some(after) = after {
+            diff.push_str(&format!("+{after}\n"));
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_workflow() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let dir = temp_dir.path();
+        let html = "<button>\n  Save\n</button>\n";
+
+        let missing = check_snapshot(dir, "button/default", html, false).unwrap_err();
+        assert!(missing.to_string().contains("--update-snapshots"));
+
+        assert_eq!(
+            check_snapshot(dir, "button/default", html, true)?,
+            SnapshotOutcome::Updated
+        );
+        assert!(dir.join("button/default.snap").is_file());
+        assert_eq!(
+            check_snapshot(dir, "button/default", &html.replace('\n', "\r\n"), false)?,
+            SnapshotOutcome::Matched
+        );
+
+        let changed = html.replace("Save", "Submit");
+        let mismatch = check_snapshot(dir, "button/default", &changed, false).unwrap_err();
+        assert!(mismatch
+            .to_string()
+            .contains("@@ line 2 @@\n-  Save\n+  Submit\n"));
+
+        check_snapshot(dir, "button/default", &changed, true)?;
+        assert_eq!(
+            check_snapshot(dir, "button/default", &changed, false)?,
+            SnapshotOutcome::Matched
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_names_stay_inside_the_directory() {
+        let dir = Path::new("tests/snapshots");
+        assert!(snapshot_path(dir, "card").is_ok());
+        assert!(snapshot_path(dir, "").is_err());
+        assert!(snapshot_path(dir, "../card").is_err());
+        assert!(snapshot_path(dir, "/tmp/card").is_err());
+    }
+}
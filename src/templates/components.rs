@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::str::FromStr;
+
+use super::project_templates::ComponentFormat;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PropType {
@@ -13,6 +16,47 @@ pub enum PropType {
     Object(HashMap<String, PropType>),
 }
 
+impl PropType {
+    /// Rust type used for this prop in generated component code
+    pub fn rust_type(&self) -> String {
+        match self {
+            Self::String => "String".to_string(),
+            Self::Number => "f64".to_string(),
+            Self::Boolean => "bool".to_string(),
+            Self::Array(inner) => format!("Vec<{}>", inner.rust_type()),
+            Self::Object(_) => "std::collections::HashMap<String, String>".to_string(),
+        }
+    }
+}
+
+impl FromStr for PropType {
+    type Err = anyhow::Error;
+
+    /// Parse `string`, `number`, `boolean` or `array<T>` (also written `T[]`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let lower = s.to_lowercase();
+
+        if let Some(inner) = lower
+            .strip_prefix("array<")
+            .and_then(|rest| rest.strip_suffix('>'))
+            .or_else(|| lower.strip_suffix("[]"))
+        {
+            return Ok(Self::Array(Box::new(inner.parse()?)));
+        }
+
+        match lower.as_str() {
+            "string" | "str" => Ok(Self::String),
+            "number" | "f64" | "i32" => Ok(Self::Number),
+            "boolean" | "bool" => Ok(Self::Boolean),
+            "object" => Ok(Self::Object(HashMap::new())),
+            _ => Err(anyhow::anyhow!(
+                "Invalid prop type: {s}. Valid types are: string, number, boolean, object, array<T>"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Component {
     pub name: String,
@@ -51,6 +95,71 @@ impl Component {
     }
 }
 
+impl Component {
+    /// Render the component as an `.orbit` file in the given format
+    pub fn to_orbit(&self, format: &ComponentFormat) -> String {
+        let template = format!("<div class=\"{}\">\n  <slot />\n</div>\n", self.template);
+        let style = format!(".{} {{\n  display: block;\n}}\n", self.template);
+        let code = self.rust_code();
+
+        match format {
+            ComponentFormat::Legacy => format!(
+                "<template>\n{template}</template>\n\n<style>\n{style}</style>\n\n<script>\n{code}</script>\n"
+            ),
+            ComponentFormat::Modern => format!(
+                "<template>\n{template}</template>\n\n<style>\n{style}</style>\n\n<code lang=\"rust\">\n{code}</code>\n"
+            ),
+            ComponentFormat::Markdown => format!(
+                "# {}\n\n{}\n\n```html\n{template}```\n\n```css\n{style}```\n\n```rust\n{code}```\n",
+                self.name, self.description
+            ),
+        }
+    }
+
+    /// Rust section of the component with a props struct for any declared props
+    fn rust_code(&self) -> String {
+        let mut code = String::from("use orbit::prelude::*;\n\n");
+
+        let props_type = if self.props.is_empty() {
+            "()".to_string()
+        } else {
+            let mut props: Vec<_> = self.props.iter().collect();
+            props.sort_by(|a, b| a.0.cmp(b.0));
+
+            code.push_str(&format!(
+                "#[derive(Props)]\npub struct {}Props {{\n",
+                self.name
+            ));
+            for (name, prop_type) in props {
+                code.push_str(&format!("    pub {name}: {},\n", prop_type.rust_type()));
+            }
+            code.push_str("}\n\n");
+            format!("{}Props", self.name)
+        };
+
+        if self.props.is_empty() {
+            code.push_str(&format!("pub struct {};\n\n", self.name));
+        } else {
+            code.push_str(&format!(
+                "pub struct {} {{\n    props: {props_type},\n}}\n\n",
+                self.name
+            ));
+        }
+
+        let constructor = if self.props.is_empty() {
+            "    fn new(_: Self::Props) -> Self {\n        Self\n    }\n"
+        } else {
+            "    fn new(props: Self::Props) -> Self {\n        Self { props }\n    }\n"
+        };
+        code.push_str(&format!(
+            "impl Component for {} {{\n    type Props = {props_type};\n\n{constructor}}}\n",
+            self.name
+        ));
+
+        code
+    }
+}
+
 impl Display for Component {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} ({})", self.name, self.description)
@@ -146,4 +255,45 @@ mod tests {
         assert!(code.contains("tags"));
         assert!(code.contains("user"));
     }
+
+    #[test]
+    fn test_prop_type_from_str() {
+        assert!(matches!("string".parse::<PropType>(), Ok(PropType::String)));
+        assert!(matches!(
+            "Boolean".parse::<PropType>(),
+            Ok(PropType::Boolean)
+        ));
+        assert_eq!(
+            "array<number>".parse::<PropType>().unwrap().rust_type(),
+            "Vec<f64>"
+        );
+        assert_eq!(
+            "string[]".parse::<PropType>().unwrap().rust_type(),
+            "Vec<String>"
+        );
+        assert!("widget".parse::<PropType>().is_err());
+    }
+
+    #[test]
+    fn test_component_to_orbit() {
+        let mut component = Component::new(
+            "UserCard".to_string(),
+            "user-card".to_string(),
+            HashMap::new(),
+            "A user card component".to_string(),
+        );
+        component.add_prop("title".to_string(), PropType::String);
+
+        let legacy = component.to_orbit(&ComponentFormat::Legacy);
+        assert!(legacy.contains("<script>"));
+        assert!(legacy.contains("pub title: String,"));
+        assert!(legacy.contains("type Props = UserCardProps;"));
+
+        let modern = component.to_orbit(&ComponentFormat::Modern);
+        assert!(modern.contains("<code lang=\"rust\">"));
+
+        let markdown = component.to_orbit(&ComponentFormat::Markdown);
+        assert!(markdown.contains("```rust"));
+        assert!(markdown.starts_with("# UserCard"));
+    }
 }
@@ -0,0 +1,160 @@
+// Boolean conditions that decide whether a template file is generated
+
+use anyhow::Result;
+
+use super::project_templates::GenerationContext;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+/// Evaluate a condition such as `features.benches && !features.docs`
+///
+/// Supports `true`, `false`, `features.<name>`, `!`, `&&`, `||` and parentheses.
+pub fn evaluate(condition: &str, context: &GenerationContext) -> Result<bool> {
+    let tokens = tokenize(condition)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        context,
+    };
+    let value = parser.or()?;
+    if let Some(token) = parser.peek() {
+        return Err(anyhow::anyhow!(
+            "Unexpected {token:?} in condition `{condition}`"
+        ));
+    }
+    Ok(value)
+}
+
+fn tokenize(condition: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = condition.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => {}
+            '!' => tokens.push(Token::Not),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '&' | '|' => {
+                if chars.next() != Some(c) {
+                    return Err(anyhow::anyhow!(
+                        "Expected `{c}{c}` in condition `{condition}`"
+                    ));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || matches!(next, '_' | '-' | '.')) {
+                        break;
+                    }
+                    ident.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unexpected character '{c}' in condition `{condition}`"
+                ))
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    context: &'a GenerationContext,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<bool> {
+        let mut value = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            value |= self.and()?;
+        }
+        Ok(value)
+    }
+
+    fn and(&mut self) -> Result<bool> {
+        let mut value = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            value &= self.unary()?;
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<bool> {
+        match self.next().cloned() {
+            Some(Token::Not) => Ok(!self.unary()?),
+            Some(Token::Open) => {
+                let value = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(anyhow::anyhow!("Missing `)` in condition")),
+                }
+            }
+            Some(Token::Ident(name)) => self.variable(&name),
+            Some(token) => Err(anyhow::anyhow!("Unexpected {token:?} in condition")),
+            None => Err(anyhow::anyhow!("Condition ends unexpectedly")),
+        }
+    }
+
+    fn variable(&self, name: &str) -> Result<bool> {
+        match name {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => match name.strip_prefix("features.") {
+                Some(feature) if !feature.is_empty() => Ok(self.context.has_feature(feature)),
+                _ => Err(anyhow::anyhow!(
+                    "Unknown variable `{name}` in condition (expected `features.<name>`)"
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let context = GenerationContext::with_features(["benches"]);
+
+        assert!(evaluate("features.benches", &context).unwrap());
+        assert!(!evaluate("features.docs", &context).unwrap());
+        assert!(evaluate("!features.docs && features.benches", &context).unwrap());
+        assert!(evaluate("features.docs || true", &context).unwrap());
+        assert!(!evaluate("!(features.benches || features.docs)", &context).unwrap());
+
+        assert!(evaluate("benches", &context).is_err());
+        assert!(evaluate("features.benches &", &context).is_err());
+        assert!(evaluate("(features.benches", &context).is_err());
+        assert!(evaluate("features.benches features.docs", &context).is_err());
+    }
+}
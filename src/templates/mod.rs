@@ -1,2 +1,4 @@
 pub mod components;
+pub mod condition;
 pub mod project_templates;
+pub mod validation;
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use log::debug;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 
@@ -9,6 +10,9 @@ pub struct ProjectTemplate {
     pub name: String,
     pub description: String,
     pub files: Vec<TemplateFile>,
+    /// Directories to create even when no file is written into them
+    #[serde(default)]
+    pub directories: Vec<String>,
     pub dependencies: Vec<String>,
     pub dev_dependencies: Vec<String>,
     pub format: Option<ComponentFormat>, // Default to Legacy if None
@@ -17,7 +21,38 @@ pub struct ProjectTemplate {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateFile {
     pub path: String,
+    #[serde(default)]
     pub content: String,
+    /// File in the template directory to copy verbatim instead of `content`,
+    /// for binary assets such as images and fonts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Only generate the file when this condition holds, e.g. `features.benches`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+/// Choices made when generating a project, used by file conditions
+#[derive(Debug, Clone, Default)]
+pub struct GenerationContext {
+    features: BTreeSet<String>,
+}
+
+impl GenerationContext {
+    pub fn with_features<I, S>(features: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            features: features.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether the optional feature `name` was requested
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.contains(name)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,8 +72,10 @@ impl fmt::Display for TemplateType {
     }
 }
 
-impl TemplateType {
-    pub fn from_str(s: &str) -> Result<Self> {
+impl std::str::FromStr for TemplateType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
             "basic" => Ok(Self::Basic),
             "advanced" => Ok(Self::Advanced),
@@ -58,12 +95,69 @@ pub enum ComponentFormat {
     Markdown, // Full Markdown format with code blocks
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for ComponentFormat {
     fn default() -> Self {
         Self::Legacy
     }
 }
 
+impl std::str::FromStr for ComponentFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "legacy" => Ok(Self::Legacy),
+            "modern" => Ok(Self::Modern),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(anyhow::anyhow!(
+                "Invalid component format: {}. Valid options are: legacy, modern, markdown",
+                s
+            )),
+        }
+    }
+}
+
+impl ComponentFormat {
+    /// File extensions of component files, in any format
+    pub const EXTENSIONS: [&'static str; 2] = ["orbit", "orbit.md"];
+
+    /// File extension of components written in this format, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "orbit.md",
+            Self::Legacy | Self::Modern => "orbit",
+        }
+    }
+
+    /// Format of a component file, judged by its extension
+    ///
+    /// `.orbit.md` files are Markdown. Plain `.orbit` files use section tags in
+    /// either the legacy or modern style and are reported as legacy.
+    pub fn from_path(path: &Path) -> Self {
+        let is_markdown = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".orbit.md"));
+        if is_markdown {
+            Self::Markdown
+        } else {
+            Self::Legacy
+        }
+    }
+
+    /// Format of an existing component file, judged by its extension and contents
+    ///
+    /// Plain `.orbit` files are modern when their code is in a `<code>` tag.
+    pub fn detect(path: &Path, content: &str) -> Self {
+        match Self::from_path(path) {
+            Self::Markdown => Self::Markdown,
+            _ if content.lines().any(|line| line.trim().starts_with("<code")) => Self::Modern,
+            _ => Self::Legacy,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentSection {
     pub name: String, // e.g., "template", "style", "code", "tests", "markdown"
@@ -71,12 +165,81 @@ pub struct ComponentSection {
     pub content: String,
 }
 
+/// `.gitignore` written into projects whose template doesn't ship one
+pub const DEFAULT_GITIGNORE: &str = "# Rust build output
+/target/
+
+# orbiton build output and caches
+/dist/
+/build/
+/.orbiton/
+";
+
+/// Progress bar for writing `files` project files, styled like the build progress
+fn generation_progress(files: usize) -> indicatif::ProgressBar {
+    let progress_bar = indicatif::ProgressBar::new(files as u64);
+    progress_bar.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {msg} ({pos}/{len})",
+            )
+            .expect("Failed to set progress bar style")
+            .progress_chars("#>-"),
+    );
+    if crate::output::is_quiet() {
+        progress_bar.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+    }
+    progress_bar
+}
+
+/// Environment variable pointing at a templates directory, skipping auto-discovery
+pub const TEMPLATE_DIR_ENV_VAR: &str = "ORBITON_TEMPLATE_DIR";
+
+/// Templates built into the binary, used when no templates directory is installed
+static EMBEDDED_TEMPLATES: include_dir::Dir<'static> =
+    include_dir::include_dir!("$CARGO_MANIFEST_DIR/templates");
+
 pub struct TemplateManager {
-    templates_dir: std::path::PathBuf,
+    /// Templates directory on disk, or `None` to use the embedded templates
+    templates_dir: Option<PathBuf>,
 }
 
 impl TemplateManager {
+    /// Use `templates_dir` as the templates directory instead of searching for one
+    pub fn with_dir(templates_dir: &Path) -> Result<Self> {
+        if !templates_dir.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Templates directory {templates_dir:?} does not exist"
+            ));
+        }
+        Ok(Self {
+            templates_dir: Some(templates_dir.to_path_buf()),
+        })
+    }
+
+    /// Use the templates built into the binary
+    pub fn embedded() -> Self {
+        Self {
+            templates_dir: None,
+        }
+    }
+
+    /// Whether templates come from the binary rather than a directory on disk
+    pub fn is_embedded(&self) -> bool {
+        self.templates_dir.is_none()
+    }
+
+    /// Locate the templates directory
+    ///
+    /// `ORBITON_TEMPLATE_DIR` takes precedence over the usual locations
+    /// next to the executable and in the current directory. When none of them
+    /// exist, the templates embedded in the binary are used.
     pub fn new() -> Result<Self> {
+        if let Some(dir) = std::env::var_os(TEMPLATE_DIR_ENV_VAR).filter(|dir| !dir.is_empty()) {
+            return Self::with_dir(Path::new(&dir))
+                .with_context(|| format!("Invalid {TEMPLATE_DIR_ENV_VAR}"));
+        }
+
         // List of possible template directories
         let mut possible_dirs = Vec::new();
 
@@ -106,19 +269,29 @@ impl TemplateManager {
             debug!("Checking for templates in {templates_dir:?}");
             if templates_dir.exists() {
                 return Ok(Self {
-                    templates_dir: templates_dir.clone(),
+                    templates_dir: Some(templates_dir.clone()),
                 });
             }
         }
 
-        Err(anyhow::anyhow!(
-            "Templates directory not found. Make sure the template files are properly installed. Looked in:\n{}",
-            possible_dirs
-                .iter()
-                .map(|p| format!("- {p:?}"))
-                .collect::<Vec<_>>()
-                .join("\n")
-        ))
+        debug!("No templates directory found, using the templates built into orbiton");
+        Ok(Self::embedded())
+    }
+
+    /// Read a file of the template `template`, given relative to the template's directory
+    fn read_template_file(&self, template: &str, relative: &str) -> Result<Vec<u8>> {
+        match &self.templates_dir {
+            Some(dir) => {
+                let path = dir.join(template).join(relative);
+                std::fs::read(&path).with_context(|| format!("Failed to read {path:?}"))
+            }
+            None => EMBEDDED_TEMPLATES
+                .get_file(format!("{template}/{relative}"))
+                .map(|file| file.contents().to_vec())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Built-in template {template} has no file {relative}")
+                }),
+        }
     }
 
     pub fn list_templates(&self) -> Vec<TemplateType> {
@@ -129,28 +302,77 @@ impl TemplateManager {
         ]
     }
 
+    /// Generate a project from a template into `output_dir`
+    ///
+    /// Returns the number of files written.
     pub fn generate_project(
         &self,
         name: &str,
         template_type: TemplateType,
         output_dir: &Path,
-    ) -> Result<()> {
-        let template_dir = self.templates_dir.join(template_type.to_string());
-        if !template_dir.exists() {
-            return Err(anyhow::anyhow!(
-                "Template directory not found: {template_dir:?}"
-            ));
+        context: &GenerationContext,
+    ) -> Result<usize> {
+        let template_name = template_type.to_string();
+        match &self.templates_dir {
+            Some(dir) if !dir.join(&template_name).exists() => {
+                return Err(anyhow::anyhow!(
+                    "Template directory not found: {:?}",
+                    dir.join(&template_name)
+                ));
+            }
+            None if EMBEDDED_TEMPLATES.get_dir(&template_name).is_none() => {
+                return Err(anyhow::anyhow!(
+                    "Template {template_name} is not built into orbiton"
+                ));
+            }
+            _ => {}
         }
 
-        let template_json = std::fs::read_to_string(template_dir.join("template.json"))
-            .with_context(|| format!("Failed to read template.json from {template_dir:?}"))?;
+        let template_json = self
+            .read_template_file(&template_name, "template.json")
+            .with_context(|| format!("Failed to read template.json of {template_name}"))?;
 
         let mut template: ProjectTemplate =
-            serde_json::from_str(&template_json).context("Failed to parse template.json")?;
+            serde_json::from_slice(&template_json).context("Failed to parse template.json")?;
 
         template.name = name.to_string();
 
-        for mut file in template.files {
+        let mut files = Vec::with_capacity(template.files.len());
+        for file in template.files {
+            if let Some(condition) = &file.condition {
+                let included = super::condition::evaluate(condition, context)
+                    .with_context(|| format!("Invalid condition for {}", file.path))?;
+                if !included {
+                    debug!("Skipping {}: `{condition}` is false", file.path);
+                    continue;
+                }
+            }
+            files.push(file);
+        }
+
+        let has_gitignore = files.iter().any(|file| file.path == ".gitignore");
+
+        for directory in &template.directories {
+            let target_dir = output_dir.join(directory);
+            std::fs::create_dir_all(&target_dir)
+                .with_context(|| format!("Failed to create directory {target_dir:?}"))?;
+        }
+
+        let file_count = files.len() + usize::from(!has_gitignore);
+        let progress = generation_progress(file_count);
+
+        // Placeholders are rendered the same way `orbiton templates validate` checks them
+        let parser = liquid::ParserBuilder::with_stdlib()
+            .build()
+            .expect("the standard Liquid parser always builds");
+        let liquid_context = super::validation::project_context(
+            name,
+            &liquid::model::DateTime::now().date().to_string(),
+        );
+
+        for mut file in files {
+            progress.set_message(format!("Writing {}", file.path));
+
             // Convert file extension for Markdown components if needed
             if template.format == Some(ComponentFormat::Markdown) && file.path.ends_with(".orbit") {
                 file.path = file.path.replace(".orbit", ".orbit.md");
@@ -162,11 +384,36 @@ impl TemplateManager {
                     .with_context(|| format!("Failed to create directory {parent:?}"))?;
             }
 
-            std::fs::write(&target_path, file.content)
-                .with_context(|| format!("Failed to write file: {target_path:?}"))?;
+            if let Some(source) = &file.source {
+                let contents = self.read_template_file(&template_name, source)?;
+                std::fs::write(&target_path, contents)
+                    .with_context(|| format!("Failed to write file: {target_path:?}"))?;
+            } else {
+                // `{{ }}` in components are Orbit bindings, not Liquid placeholders
+                let contents = if super::validation::is_component(&file.path) {
+                    file.content
+                } else {
+                    parser
+                        .parse(&file.content)
+                        .and_then(|liquid| liquid.render(&liquid_context))
+                        .with_context(|| format!("Failed to render {}", file.path))?
+                };
+                std::fs::write(&target_path, contents)
+                    .with_context(|| format!("Failed to write file: {target_path:?}"))?;
+            }
+            progress.inc(1);
         }
 
-        Ok(())
+        if !has_gitignore {
+            progress.set_message("Writing .gitignore");
+            let gitignore = output_dir.join(".gitignore");
+            std::fs::write(&gitignore, DEFAULT_GITIGNORE)
+                .with_context(|| format!("Failed to write file: {gitignore:?}"))?;
+            progress.inc(1);
+        }
+
+        progress.finish_and_clear();
+        Ok(file_count)
     }
 
     #[allow(dead_code)] // Used in other modules
@@ -181,53 +428,72 @@ impl TemplateManager {
         }
     }
 
-    #[allow(dead_code)]
+    /// Write sections back out in the given format, separated by blank lines
+    ///
+    /// The inverse of [`Self::parse_component_sections`] for well-formed files.
+    pub fn render_component_sections(
+        sections: &[ComponentSection],
+        format: &ComponentFormat,
+    ) -> String {
+        let blocks: Vec<String> = sections
+            .iter()
+            .map(|section| {
+                let mut content = section.content.clone();
+                if !content.is_empty() && !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                match (format, section.name.as_str()) {
+                    (ComponentFormat::Markdown, "markdown") => content,
+                    (ComponentFormat::Markdown, _) => {
+                        format!("```{}\n{content}```\n", section.lang)
+                    }
+                    (ComponentFormat::Legacy, "code") => format!("<script>\n{content}</script>\n"),
+                    (ComponentFormat::Modern, "code") => {
+                        format!("<code lang=\"{}\">\n{content}</code>\n", section.lang)
+                    }
+                    (_, name) => format!("<{name}>\n{content}</{name}>\n"),
+                }
+            })
+            .collect();
+        blocks.join("\n")
+    }
+
     fn parse_markdown_format(content: &str) -> Result<Vec<ComponentSection>> {
         let mut sections = Vec::new();
         let mut current_section: Option<ComponentSection> = None;
-        let mut in_code_block = false;
-        let mut code_fence_count = 0;
+        // Fences without a language are examples in the prose, not sections
+        let mut in_prose_fence = false;
         let mut markdown_content = String::new();
 
         for line in content.lines() {
-            if line.starts_with("```") {
-                code_fence_count += 1;
-                if code_fence_count % 2 == 1 {
-                    // Start of code block
-                    in_code_block = true;
-                    let lang = line.trim_start_matches('`').trim();
-                    if !lang.is_empty() {
-                        // Save any accumulated markdown content
-                        if !markdown_content.trim().is_empty() {
-                            sections.push(ComponentSection {
-                                name: "markdown".to_string(),
-                                lang: "markdown".to_string(),
-                                content: markdown_content.trim().to_string(),
-                            });
-                            markdown_content.clear();
-                        }
-
-                        if let Some(section) = current_section {
-                            sections.push(section);
-                        }
-                        current_section = Some(ComponentSection {
-                            name: Self::determine_section_name(lang),
-                            lang: lang.to_string(),
-                            content: String::new(),
-                        });
-                    }
-                } else {
+            let is_fence = line.starts_with("```");
+            if in_prose_fence {
+                in_prose_fence = !is_fence;
+                markdown_content.push_str(line);
+                markdown_content.push('\n');
+            } else if let Some(section) = current_section.as_mut() {
+                if is_fence {
                     // End of code block
-                    in_code_block = false;
-                    if let Some(section) = current_section.take() {
-                        sections.push(section);
-                    }
-                }
-            } else if in_code_block {
-                if let Some(ref mut section) = current_section {
+                    sections.extend(current_section.take());
+                } else {
                     section.content.push_str(line);
                     section.content.push('\n');
                 }
+            } else if is_fence {
+                let lang = line.trim_start_matches('`').trim();
+                if lang.is_empty() {
+                    in_prose_fence = true;
+                    markdown_content.push_str(line);
+                    markdown_content.push('\n');
+                } else {
+                    // Start of code block
+                    push_markdown(&mut markdown_content, &mut sections);
+                    current_section = Some(ComponentSection {
+                        name: Self::determine_section_name(lang),
+                        lang: lang.to_string(),
+                        content: String::new(),
+                    });
+                }
             } else {
                 // This is Markdown content outside code blocks
                 markdown_content.push_str(line);
@@ -235,100 +501,59 @@ impl TemplateManager {
             }
         }
 
+        sections.extend(current_section);
+        push_markdown(&mut markdown_content, &mut sections);
         Ok(sections)
     }
 
-    #[allow(dead_code)]
     fn parse_modern_format(content: &str) -> Result<Vec<ComponentSection>> {
-        let mut sections = Vec::new();
-        let mut current_section: Option<ComponentSection> = None;
-
-        for line in content.lines() {
-            if line.starts_with('<') && line.ends_with('>') {
-                // This line indicates a new section
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-
-                let lang = line.trim_matches('<').trim_matches('>').trim();
-                current_section = Some(ComponentSection {
-                    name: Self::determine_section_name(lang),
-                    lang: lang.to_string(),
-                    content: String::new(),
-                });
-            } else if let Some(ref mut section) = current_section {
-                section.content.push_str(line);
-                section.content.push('\n');
-            }
-        }
-
-        if let Some(section) = current_section {
-            sections.push(section);
-        }
-
-        Ok(sections)
+        Self::parse_tagged_format(content)
     }
 
-    #[allow(dead_code)]
     fn parse_legacy_format(content: &str) -> Result<Vec<ComponentSection>> {
+        Self::parse_tagged_format(content)
+    }
+
+    /// Split a component into its `<template>`, `<style>` and code sections
+    ///
+    /// Code may be wrapped in `<script>` (legacy) or `<code lang="...">`
+    /// (modern). Tags must be on lines of their own; anything outside a
+    /// section is ignored.
+    fn parse_tagged_format(content: &str) -> Result<Vec<ComponentSection>> {
         let mut sections = Vec::new();
         let mut current_section: Option<ComponentSection> = None;
 
-        // Legacy format has <template>, <style>, and <script> tags
         for line in content.lines() {
-            if line.contains("<template>") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-                current_section = Some(ComponentSection {
-                    name: "template".to_string(),
-                    lang: "html".to_string(),
-                    content: String::new(),
-                });
-            } else if line.contains("</template>") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-            } else if line.contains("<style>") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-                current_section = Some(ComponentSection {
-                    name: "style".to_string(),
-                    lang: "css".to_string(),
-                    content: String::new(),
-                });
-            } else if line.contains("</style>") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-            } else if line.contains("<script>") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
+            let tag = line.trim();
+            let opened = match tag {
+                "<template>" => Some(("template", "html")),
+                "<style>" => Some(("style", "css")),
+                "<script>" => Some(("code", "rust")),
+                _ if tag.starts_with("<code") && tag.ends_with('>') => {
+                    Some(("code", code_lang(tag).unwrap_or("rust")))
                 }
+                _ => None,
+            };
+
+            if let Some((name, lang)) = opened {
+                sections.extend(current_section.take());
                 current_section = Some(ComponentSection {
-                    name: "code".to_string(),
-                    lang: "rust".to_string(),
+                    name: name.to_string(),
+                    lang: lang.to_string(),
                     content: String::new(),
                 });
-            } else if line.contains("</script>") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
+            } else if matches!(tag, "</template>" | "</style>" | "</script>" | "</code>") {
+                sections.extend(current_section.take());
             } else if let Some(ref mut section) = current_section {
                 section.content.push_str(line);
                 section.content.push('\n');
             }
         }
 
-        if let Some(section) = current_section {
-            sections.push(section);
-        }
-
+        sections.extend(current_section);
         Ok(sections)
     }
 
-    #[allow(dead_code)]
     fn determine_section_name(lang: &str) -> String {
         match lang {
             "html" | "template" => "template",
@@ -341,10 +566,31 @@ impl TemplateManager {
     }
 }
 
+/// Move accumulated Markdown prose into its own section
+fn push_markdown(markdown: &mut String, sections: &mut Vec<ComponentSection>) {
+    if !markdown.trim().is_empty() {
+        sections.push(ComponentSection {
+            name: "markdown".to_string(),
+            lang: "markdown".to_string(),
+            content: markdown.trim().to_string(),
+        });
+    }
+    markdown.clear();
+}
+
+/// The `lang` attribute of a `<code lang="...">` tag
+fn code_lang(tag: &str) -> Option<&str> {
+    let rest = &tag[tag.find("lang=")? + "lang=".len()..];
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &rest[1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::tempdir; // Now properly imported from added dependency
+    use std::str::FromStr;
+    use tempfile::{tempdir, TempDir}; // Now properly imported from added dependency
 
     #[test]
     fn test_template_type_from_str() {
@@ -363,6 +609,189 @@ mod tests {
         assert!(TemplateType::from_str("invalid").is_err());
     }
 
+    /// Generate "my-app" from a `basic` template with the given `template.json`
+    ///
+    /// `sources` are written next to `template.json` for `source` entries to copy.
+    /// Returns the output directory and the number of files written.
+    fn generate_from(
+        template_json: &str,
+        sources: &[(&str, &[u8])],
+        context: &GenerationContext,
+    ) -> Result<(TempDir, usize)> {
+        let templates_dir = tempdir()?;
+        let basic_dir = templates_dir.path().join("basic");
+        std::fs::create_dir_all(&basic_dir)?;
+        std::fs::write(basic_dir.join("template.json"), template_json)?;
+        for (name, contents) in sources {
+            std::fs::write(basic_dir.join(name), contents)?;
+        }
+        let template_manager = TemplateManager {
+            templates_dir: Some(templates_dir.path().to_path_buf()),
+        };
+
+        let output_dir = tempdir()?;
+        let file_count = template_manager.generate_project(
+            "my-app",
+            TemplateType::Basic,
+            output_dir.path(),
+            context,
+        )?;
+        Ok((output_dir, file_count))
+    }
+
+    #[test]
+    fn test_generate_project_writes_gitignore() -> Result<()> {
+        let (output_dir, _) = generate_from(
+            r##"{
+                "name": "basic",
+                "description": "Markdown components",
+                "format": "markdown",
+                "files": [{ "path": "src/app.orbit", "content": "# App" }],
+                "dependencies": [],
+                "dev_dependencies": []
+            }"##,
+            &[],
+            &GenerationContext::default(),
+        )?;
+
+        assert!(output_dir.path().join("src/app.orbit.md").exists());
+        let gitignore = std::fs::read_to_string(output_dir.path().join(".gitignore"))?;
+        assert!(gitignore.contains("/target/"));
+        assert!(gitignore.contains("/.orbiton/"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_project_renders_placeholders() -> Result<()> {
+        let (output_dir, _) = generate_from(
+            r##"{
+                "name": "basic",
+                "description": "Placeholders",
+                "files": [
+                    { "path": "Cargo.toml", "content": "[package]\nname = \"{{project_name}}\"\n" },
+                    { "path": "src/app.orbit", "content": "<template>{{ title }}</template>" }
+                ],
+                "dependencies": [],
+                "dev_dependencies": []
+            }"##,
+            &[],
+            &GenerationContext::default(),
+        )?;
+
+        assert_eq!(
+            std::fs::read_to_string(output_dir.path().join("Cargo.toml"))?,
+            "[package]\nname = \"my-app\"\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(output_dir.path().join("src/app.orbit"))?,
+            "<template>{{ title }}</template>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_project_copies_source_files() -> Result<()> {
+        let logo = [0x89, b'P', b'N', b'G', 0x00, 0xff, 0x0a];
+        let (output_dir, file_count) = generate_from(
+            r##"{
+                "name": "basic",
+                "description": "Binary assets",
+                "files": [
+                    { "path": "src/main.rs", "content": "fn main() {}" },
+                    { "path": "public/logo.bin", "source": "logo.bin" }
+                ],
+                "dependencies": [],
+                "dev_dependencies": []
+            }"##,
+            &[("logo.bin", &logo)],
+            &GenerationContext::default(),
+        )?;
+
+        assert_eq!(file_count, 3);
+        assert_eq!(
+            std::fs::read(output_dir.path().join("public/logo.bin"))?,
+            logo
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_project_creates_directories() -> Result<()> {
+        let (output_dir, _) = generate_from(
+            r##"{
+                "name": "basic",
+                "description": "Empty directories",
+                "files": [{ "path": "src/main.rs", "content": "fn main() {}" }],
+                "directories": ["assets", "tests/fixtures"],
+                "dependencies": [],
+                "dev_dependencies": []
+            }"##,
+            &[],
+            &GenerationContext::default(),
+        )?;
+
+        assert!(output_dir.path().join("assets").is_dir());
+        assert!(output_dir.path().join("tests/fixtures").is_dir());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_project_conditional_files() -> Result<()> {
+        let (output_dir, _) = generate_from(
+            r##"{
+                "name": "basic",
+                "description": "Conditional files",
+                "files": [
+                    { "path": "src/main.rs", "content": "fn main() {}" },
+                    { "path": "benches/app.rs", "content": "", "condition": "features.benches" },
+                    { "path": "docs/index.md", "content": "", "condition": "features.docs" }
+                ],
+                "dependencies": [],
+                "dev_dependencies": []
+            }"##,
+            &[],
+            &GenerationContext::with_features(["benches"]),
+        )?;
+
+        assert!(output_dir.path().join("src/main.rs").exists());
+        assert!(output_dir.path().join("benches/app.rs").exists());
+        assert!(!output_dir.path().join("docs").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_manager_with_dir() {
+        let templates_dir = tempdir().unwrap();
+        let manager = TemplateManager::with_dir(templates_dir.path()).unwrap();
+        assert_eq!(manager.templates_dir.as_deref(), Some(templates_dir.path()));
+
+        let missing = templates_dir.path().join("missing");
+        let err = TemplateManager::with_dir(&missing).err().unwrap();
+        assert!(err.to_string().contains("does not exist"), "{err}");
+    }
+
+    #[test]
+    fn test_generate_project_from_embedded_templates() -> Result<()> {
+        let output_dir = tempdir()?;
+        TemplateManager::embedded().generate_project(
+            "my-app",
+            TemplateType::Basic,
+            output_dir.path(),
+            &GenerationContext::default(),
+        )?;
+
+        let cargo_toml = std::fs::read_to_string(output_dir.path().join("Cargo.toml"))?;
+        assert!(cargo_toml.contains("name = \"my-app\""), "{cargo_toml}");
+        let favicon = std::fs::read(output_dir.path().join("favicon.png"))?;
+        assert!(favicon.starts_with(b"\x89PNG"));
+        Ok(())
+    }
+
     #[test]
     #[ignore] // Ignore this test as it requires template files to be installed
     fn test_create_from_template() -> Result<()> {
@@ -370,7 +799,12 @@ mod tests {
         let template_manager = TemplateManager::new()?;
 
         // Create a basic project
-        template_manager.generate_project("test-project", TemplateType::Basic, temp_dir.path())?;
+        template_manager.generate_project(
+            "test-project",
+            TemplateType::Basic,
+            temp_dir.path(),
+            &GenerationContext::default(),
+        )?;
 
         // Verify created files
         assert!(temp_dir.path().join("Cargo.toml").exists());
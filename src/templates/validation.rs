@@ -0,0 +1,300 @@
+// Checks for project templates (`template.json`)
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::{Component, Path};
+
+use super::project_templates::{GenerationContext, ProjectTemplate};
+
+/// File name of a template's manifest
+pub const TEMPLATE_MANIFEST: &str = "template.json";
+
+/// A problem found in a template, located by file and field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateProblem {
+    pub file: String,
+    /// JSON path of the offending field, e.g. `files[2].path`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.file, self.field, self.message)
+    }
+}
+
+/// Variables available to Liquid placeholders in template files
+pub fn project_context(project_name: &str, generated_date: &str) -> liquid::Object {
+    liquid::object!({
+        "project_name": project_name,
+        "generated_date": generated_date,
+    })
+}
+
+/// Load `template.json` from `template_dir` and check it
+///
+/// Returns an error only when the manifest cannot be read or deserialized;
+/// everything else is reported as a [`TemplateProblem`].
+pub fn validate_template(template_dir: &Path) -> Result<Vec<TemplateProblem>> {
+    let manifest_path = template_dir.join(TEMPLATE_MANIFEST);
+    let contents = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {manifest_path:?}"))?;
+    let template: ProjectTemplate = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {manifest_path:?}"))?;
+
+    let mut problems = check_template(&template);
+    for (index, file) in template.files.iter().enumerate() {
+        let Some(source) = &file.source else {
+            continue;
+        };
+        if check_relative_path(source).is_ok() && !template_dir.join(source).is_file() {
+            problems.push(TemplateProblem {
+                file: TEMPLATE_MANIFEST.to_string(),
+                field: format!("files[{index}].source"),
+                message: format!("'{source}' does not exist in the template directory"),
+            });
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Check file paths, conditions, dependency specs and placeholders of a parsed template
+pub fn check_template(template: &ProjectTemplate) -> Vec<TemplateProblem> {
+    let mut problems = Vec::new();
+    let mut report = |field: String, message: String| {
+        problems.push(TemplateProblem {
+            file: TEMPLATE_MANIFEST.to_string(),
+            field,
+            message,
+        })
+    };
+
+    let parser = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .expect("the standard Liquid parser always builds");
+    let context = project_context("my-app", "2024-01-01");
+
+    for (index, file) in template.files.iter().enumerate() {
+        if let Err(message) = check_relative_path(&file.path) {
+            report(format!("files[{index}].path"), message);
+        }
+        if let Some(condition) = &file.condition {
+            if let Err(err) = super::condition::evaluate(condition, &GenerationContext::default()) {
+                report(format!("files[{index}].condition"), err.to_string());
+            }
+        }
+
+        if let Some(source) = &file.source {
+            if let Err(message) = check_relative_path(source) {
+                report(format!("files[{index}].source"), message);
+            }
+            if !file.content.is_empty() {
+                report(
+                    format!("files[{index}].content"),
+                    "must be empty when `source` is set".to_string(),
+                );
+            }
+            continue;
+        }
+
+        // `{{ }}` in components are Orbit bindings, not Liquid placeholders
+        if is_component(&file.path) {
+            continue;
+        }
+        let rendered = parser
+            .parse(&file.content)
+            .and_then(|liquid| liquid.render(&context));
+        if let Err(err) = rendered {
+            report(
+                format!("files[{index}].content"),
+                format!("{} ({})", first_line(&err.to_string()), file.path),
+            );
+        }
+    }
+
+    for (index, directory) in template.directories.iter().enumerate() {
+        if let Err(message) = check_relative_path(directory) {
+            report(format!("directories[{index}]"), message);
+        }
+    }
+
+    for (table, specs) in [
+        ("dependencies", &template.dependencies),
+        ("dev_dependencies", &template.dev_dependencies),
+    ] {
+        for (index, spec) in specs.iter().enumerate() {
+            if let Err(message) = check_dependency_spec(spec) {
+                report(format!("{table}[{index}]"), message);
+            }
+        }
+    }
+
+    problems
+}
+
+/// Whether a template file is an Orbit component
+pub(crate) fn is_component(path: &str) -> bool {
+    path.ends_with(".orbit") || path.ends_with(".orbit.md")
+}
+
+/// Ensure a path is relative and stays inside the generated project
+fn check_relative_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("path is empty".to_string());
+    }
+
+    let mut depth = 0usize;
+    for component in Path::new(path).components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                return Err(format!("'{path}' must be a relative path"));
+            }
+            Component::ParentDir if depth == 0 => {
+                return Err(format!("'{path}' escapes the project directory"));
+            }
+            Component::ParentDir => depth -= 1,
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+        }
+    }
+
+    if depth == 0 {
+        return Err(format!(
+            "'{path}' does not name anything inside the project"
+        ));
+    }
+    Ok(())
+}
+
+/// Ensure a dependency is a single Cargo.toml entry such as `serde = "1.0"`
+fn check_dependency_spec(spec: &str) -> Result<(), String> {
+    let table: toml::Table = toml::from_str(spec)
+        .map_err(|err| format!("'{spec}' is not valid TOML: {}", first_line(err.message())))?;
+
+    let mut entries = table.iter();
+    let (Some((name, value)), None) = (entries.next(), entries.next()) else {
+        return Err(format!("'{spec}' must declare exactly one dependency"));
+    };
+
+    let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Err(format!("'{name}' is not a valid crate name"));
+    }
+
+    match value {
+        toml::Value::String(version) if version.trim().is_empty() => {
+            Err(format!("'{name}' has an empty version requirement"))
+        }
+        toml::Value::String(_) => Ok(()),
+        toml::Value::Table(details) => {
+            if ["version", "path", "git", "workspace"]
+                .iter()
+                .any(|key| details.contains_key(*key))
+            {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'{name}' needs a version, path, git or workspace source"
+                ))
+            }
+        }
+        _ => Err(format!(
+            "'{name}' must be a version string or a table of dependency details"
+        )),
+    }
+}
+
+fn first_line(message: &str) -> &str {
+    message.lines().next().unwrap_or(message).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::project_templates::TemplateFile;
+
+    fn file(path: &str, content: &str) -> TemplateFile {
+        TemplateFile {
+            path: path.to_string(),
+            content: content.to_string(),
+            source: None,
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn test_bundled_templates_are_valid() {
+        let templates = Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+        for name in ["basic", "component-library"] {
+            let problems = validate_template(&templates.join(name)).unwrap();
+            assert!(problems.is_empty(), "{name}: {problems:?}");
+        }
+    }
+
+    #[test]
+    fn test_check_template_reports_each_problem() {
+        let template = ProjectTemplate {
+            name: "broken".to_string(),
+            description: String::new(),
+            files: vec![
+                file("README.md", "# {{project_name}} ({{generated_date}})"),
+                file("/etc/passwd", ""),
+                file("src/../../outside.rs", ""),
+                file("src/main.rs", "// {{ project_title }}"),
+                file("src/app.orbit", "<div>{{title}}</div>"),
+                TemplateFile {
+                    condition: Some("benches".to_string()),
+                    ..file("benches/app.rs", "")
+                },
+                TemplateFile {
+                    source: Some("../favicon.png".to_string()),
+                    ..file("favicon.png", "")
+                },
+            ],
+            directories: vec!["assets".to_string(), "../shared".to_string()],
+            dependencies: vec![
+                r#"orbitrs = "0.1.0""#.to_string(),
+                r#"serde = { version = "1.0", features = ["derive"] }"#.to_string(),
+                "serde 1.0".to_string(),
+                r#"a = "1"
+b = "2""#
+                    .to_string(),
+            ],
+            dev_dependencies: vec![r#"local = { features = ["x"] }"#.to_string()],
+            format: None,
+        };
+
+        let fields: Vec<String> = check_template(&template)
+            .into_iter()
+            .map(|problem| problem.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "files[1].path",
+                "files[2].path",
+                "files[3].content",
+                "files[5].condition",
+                "files[6].source",
+                "directories[1]",
+                "dependencies[2]",
+                "dependencies[3]",
+                "dev_dependencies[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_relative_path() {
+        assert!(check_relative_path("src/main.rs").is_ok());
+        assert!(check_relative_path("./src/../Cargo.toml").is_ok());
+        assert!(check_relative_path("").is_err());
+        assert!(check_relative_path("src/..").is_err());
+        assert!(check_relative_path("../Cargo.toml").is_err());
+    }
+}
@@ -1,5 +1,53 @@
 // Utility functions for orbiton CLI
 
+use std::path::Path;
+
+/// Crates whose presence in Cargo.toml marks an Orbit project
+const ORBIT_DEPENDENCIES: &[&str] = &["orbit", "orbitrs"];
+
+/// Dependency tables of a Cargo manifest
+pub(crate) const DEPENDENCY_TABLES: &[&str] =
+    &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Whether `dir` is an Orbit project
+///
+/// A project has an `.orbiton.toml`, a legacy `orbit.config.json`, or a
+/// Cargo.toml that depends on `orbit` or `orbitrs` (also under another name
+/// or for a specific target).
+pub fn is_orbit_project(dir: &Path) -> bool {
+    if dir.join(".orbiton.toml").is_file() || dir.join("orbit.config.json").is_file() {
+        return true;
+    }
+
+    let Some(manifest) = std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+    else {
+        return false;
+    };
+
+    let targets = manifest
+        .get("target")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values().filter_map(toml::Value::as_table));
+    std::iter::once(&manifest)
+        .chain(targets)
+        .flat_map(|table| {
+            DEPENDENCY_TABLES
+                .iter()
+                .filter_map(|name| table.get(*name).and_then(toml::Value::as_table))
+        })
+        .flat_map(|dependencies| dependencies.iter())
+        .any(|(name, spec)| {
+            let package = spec
+                .get("package")
+                .and_then(toml::Value::as_str)
+                .unwrap_or(name);
+            ORBIT_DEPENDENCIES.contains(&package)
+        })
+}
+
 pub mod fs {
     use anyhow::{Context, Result};
     use log::debug;
@@ -62,11 +110,355 @@ pub mod fs {
 
         Ok(result)
     }
+
+    /// Compile a list of glob patterns (e.g. `hmr.ignore_patterns`) into a matcher
+    pub fn glob_set(patterns: &[String]) -> Result<globset::GlobSet> {
+        let mut builder = globset::GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = globset::Glob::new(pattern)
+                .with_context(|| format!("Invalid glob pattern: {pattern}"))?;
+            builder.add(glob);
+        }
+        builder.build().context("Failed to build glob set")
+    }
+
+    /// Check whether a path, relative to `root`, matches any of the given globs
+    pub fn is_ignored(ignore: &globset::GlobSet, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        ignore.is_match(relative)
+    }
+
+    /// Resolve the directories to watch: `root` plus `extra`, relative to `root`
+    ///
+    /// Returns the directories to watch and the ones that don't exist.
+    /// Directories inside another watched directory are dropped, since
+    /// watches are recursive.
+    pub fn resolve_watch_dirs(root: &Path, extra: &[String]) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        let mut missing = Vec::new();
+        let mut candidates = Vec::new();
+        for dir in std::iter::once(root.to_path_buf()).chain(extra.iter().map(|d| root.join(d))) {
+            match dir.canonicalize() {
+                Ok(canonical) if canonical.is_dir() => candidates.push((dir, canonical)),
+                _ => missing.push(dir),
+            }
+        }
+
+        // Outer directories first, so nested ones can be skipped
+        candidates.sort_by_key(|(_, canonical)| canonical.components().count());
+        let mut watched: Vec<(PathBuf, PathBuf)> = Vec::new();
+        for (dir, canonical) in candidates {
+            if !watched
+                .iter()
+                .any(|(_, outer)| canonical.starts_with(outer))
+            {
+                watched.push((dir, canonical));
+            }
+        }
+
+        (watched.into_iter().map(|(dir, _)| dir).collect(), missing)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_resolve_watch_dirs() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let root = temp_dir.path().join("app");
+            let shared = temp_dir.path().join("shared");
+            fs::create_dir_all(root.join("src")).unwrap();
+            fs::create_dir_all(&shared).unwrap();
+
+            let extra = [
+                "../shared".to_string(),
+                "src".to_string(),
+                shared.to_string_lossy().into_owned(),
+                "missing".to_string(),
+            ];
+            let (watched, missing) = resolve_watch_dirs(&root, &extra);
+
+            // src/ is covered by the root and the shared crate is listed once
+            assert_eq!(watched.len(), 2);
+            assert_eq!(watched[0], root);
+            assert_eq!(
+                watched[1].canonicalize().unwrap(),
+                shared.canonicalize().unwrap()
+            );
+            assert_eq!(missing, vec![root.join("missing")]);
+        }
+
+        #[test]
+        fn test_is_ignored() {
+            let patterns = vec!["target/**".to_string(), "**/*.log".to_string()];
+            let ignore = glob_set(&patterns).unwrap();
+            let root = Path::new("/project");
+
+            assert!(is_ignored(
+                &ignore,
+                root,
+                Path::new("/project/target/debug/app")
+            ));
+            assert!(is_ignored(
+                &ignore,
+                root,
+                Path::new("/project/logs/build.log")
+            ));
+            assert!(!is_ignored(
+                &ignore,
+                root,
+                Path::new("/project/src/main.rs")
+            ));
+        }
+    }
+}
+
+pub mod env {
+    use anyhow::{Context, Result};
+    use std::path::Path;
+
+    /// Read variables from the project's `.env` for spawned cargo processes
+    ///
+    /// Variables already set in the real environment take precedence and are
+    /// skipped. A missing `.env` yields no variables.
+    pub fn load_dotenv(project_dir: &Path) -> Result<Vec<(String, String)>> {
+        let path = project_dir.join(".env");
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let mut vars = Vec::new();
+        for item in dotenvy::from_path_iter(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+        {
+            let (key, value) =
+                item.with_context(|| format!("Failed to parse {}", path.display()))?;
+            if std::env::var_os(&key).is_none() {
+                vars.push((key, value));
+            }
+        }
+
+        Ok(vars)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use tempfile::tempdir;
+
+        #[test]
+        fn test_load_dotenv_prefers_real_environment() {
+            let temp_dir = tempdir().unwrap();
+            std::fs::write(
+                temp_dir.path().join(".env"),
+                "ORBITON_TEST_DOTENV_ONLY=from-file\nPATH=/from/dotenv\n",
+            )
+            .unwrap();
+
+            let vars = load_dotenv(temp_dir.path()).unwrap();
+            assert_eq!(
+                vars,
+                vec![(
+                    "ORBITON_TEST_DOTENV_ONLY".to_string(),
+                    "from-file".to_string()
+                )]
+            );
+
+            assert!(load_dotenv(&temp_dir.path().join("missing"))
+                .unwrap()
+                .is_empty());
+        }
+    }
+}
+
+pub mod toolchain {
+    use anyhow::{Context, Result};
+    use console::style;
+    use std::io::IsTerminal;
+    use std::process::Command;
+
+    /// Make sure a rustup toolchain such as `beta` is installed
+    ///
+    /// Only runs `rustup toolchain install` when the toolchain is missing, so
+    /// calling this repeatedly is cheap.
+    pub fn ensure_toolchain(name: &str) -> Result<()> {
+        let output = Command::new("rustup")
+            .args(["toolchain", "list"])
+            .output()
+            .with_context(|| {
+                format!("Failed to check for {name} toolchain. Is rustup installed?")
+            })?;
+
+        if is_installed(&String::from_utf8_lossy(&output.stdout), name) {
+            return Ok(());
+        }
+
+        super::network::ensure_online(&format!(
+            "The {name} toolchain is not installed. Installing it (`rustup toolchain install {name}`)"
+        ))?;
+
+        println!(
+            "{} {name} toolchain not installed. Installing...",
+            style("Warning:").bold().yellow()
+        );
+        let status = Command::new("rustup")
+            .args(["toolchain", "install", name])
+            .status()
+            .with_context(|| format!("Failed to install {name} toolchain"))?;
+        if !status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to install {name} toolchain: rustup exited with {status}"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Make sure the standard library for a target triple is installed
+    ///
+    /// Offers to run `rustup target add` when the target is missing. In
+    /// non-interactive sessions the missing target is reported as an error.
+    pub fn ensure_target(triple: &str, toolchain: Option<&str>) -> Result<()> {
+        let mut list = Command::new("rustup");
+        if let Some(toolchain) = toolchain {
+            list.arg(format!("+{toolchain}"));
+        }
+        let output = list
+            .args(["target", "list", "--installed"])
+            .output()
+            .with_context(|| {
+                format!("Failed to check for {triple} target. Is rustup installed?")
+            })?;
+
+        if is_target_installed(&String::from_utf8_lossy(&output.stdout), triple) {
+            return Ok(());
+        }
+
+        let install_hint = format!("rustup target add {triple}");
+        super::network::ensure_online(&format!(
+            "Target {triple} is not installed. Installing it (`{install_hint}`)"
+        ))?;
+        if !std::io::stdin().is_terminal() {
+            return Err(anyhow::anyhow!(
+                "Target {triple} is not installed. Install it with `{install_hint}`"
+            ));
+        }
+
+        let install = dialoguer::Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt(format!("Target {triple} is not installed. Install it now?"))
+            .default(true)
+            .interact()
+            .context("Failed to get user confirmation")?;
+        if !install {
+            return Err(anyhow::anyhow!(
+                "Target {triple} is not installed. Install it with `{install_hint}`"
+            ));
+        }
+
+        let mut add = Command::new("rustup");
+        if let Some(toolchain) = toolchain {
+            add.arg(format!("+{toolchain}"));
+        }
+        let status = add
+            .args(["target", "add", triple])
+            .status()
+            .with_context(|| format!("Failed to install {triple} target"))?;
+        if !status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to install {triple} target: rustup exited with {status}"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check `rustup toolchain list` output for a toolchain
+    pub(crate) fn is_installed(list: &str, name: &str) -> bool {
+        let prefix = format!("{name}-");
+        list.lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .any(|toolchain| toolchain == name || toolchain.starts_with(&prefix))
+    }
+
+    /// Check `rustup target list --installed` output for a target
+    fn is_target_installed(list: &str, triple: &str) -> bool {
+        list.lines().any(|line| line.trim() == triple)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_is_target_installed() {
+            let list = "wasm32-unknown-unknown\nx86_64-unknown-linux-gnu\n";
+            assert!(is_target_installed(list, "wasm32-unknown-unknown"));
+            assert!(!is_target_installed(list, "thumbv7em-none-eabihf"));
+            assert!(!is_target_installed(list, "wasm32"));
+        }
+
+        #[test]
+        fn test_is_installed() {
+            let list = "stable-x86_64-unknown-linux-gnu (default)\nnightly-2024-01-01-x86_64-unknown-linux-gnu\n";
+            assert!(is_installed(list, "stable"));
+            assert!(is_installed(list, "nightly-2024-01-01"));
+            assert!(!is_installed(list, "beta"));
+            assert!(!is_installed("", "stable"));
+        }
+    }
+}
+
+pub mod network {
+    use anyhow::Result;
+
+    /// Environment variable cargo reads to stay offline; `--offline` sets it too
+    pub const OFFLINE_ENV_VAR: &str = "CARGO_NET_OFFLINE";
+
+    /// Whether network access is forbidden
+    pub fn is_offline() -> bool {
+        std::env::var(OFFLINE_ENV_VAR).is_ok_and(|value| is_enabled(&value))
+    }
+
+    /// Fail fast when offline, naming the action that needs the network
+    pub fn ensure_online(action: &str) -> Result<()> {
+        if is_offline() {
+            return Err(anyhow::anyhow!(
+                "{action} needs network access, but offline mode is enabled (--offline or {OFFLINE_ENV_VAR})"
+            ));
+        }
+        Ok(())
+    }
+
+    /// `--offline` for the cargo commands we spawn, when offline mode is enabled
+    pub fn cargo_offline_arg() -> Option<&'static str> {
+        is_offline().then_some("--offline")
+    }
+
+    fn is_enabled(value: &str) -> bool {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_is_enabled() {
+            assert!(is_enabled("true"));
+            assert!(is_enabled("1"));
+            assert!(is_enabled(" TRUE "));
+            assert!(!is_enabled("false"));
+            assert!(!is_enabled("0"));
+            assert!(!is_enabled(""));
+        }
+    }
 }
 
 pub mod crypto {
     /// Generate a random identifier
-    #[allow(dead_code)]
     pub fn random_id() -> String {
         use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -77,4 +469,56 @@ pub mod crypto {
 
         format!("{now:x}")
     }
+
+    /// Compute a hex-encoded SHA-256 digest of the given bytes
+    pub fn content_hash(bytes: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_content_hash_is_stable() {
+            assert_eq!(content_hash(b"orbit"), content_hash(b"orbit"));
+            assert_ne!(content_hash(b"orbit"), content_hash(b"orbiton"));
+            assert_eq!(
+                content_hash(b""),
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_orbit_project() {
+        let dir = tempdir().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        assert!(!is_orbit_project(dir.path()));
+
+        std::fs::write(&manifest, "[dependencies]\nserde = \"1.0\"\n").unwrap();
+        assert!(!is_orbit_project(dir.path()));
+
+        std::fs::write(
+            &manifest,
+            "[target.'cfg(target_arch = \"wasm32\")'.dependencies]\nui = { package = \"orbitrs\", version = \"0.1\" }\n",
+        )
+        .unwrap();
+        assert!(is_orbit_project(dir.path()));
+
+        std::fs::remove_file(&manifest).unwrap();
+        std::fs::write(dir.path().join("orbit.config.json"), "{}").unwrap();
+        assert!(is_orbit_project(dir.path()));
+    }
 }